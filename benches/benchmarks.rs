@@ -1,6 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
-use greeks_calculator::pricing::{BlackScholesParams, calculate_greeks};
-use greeks_calculator::types::OptionType;
+use greeks_calculator::pricing::{american, binomial, AmericanApproximation, BinomialMethod, BlackScholesParams, calculate_greeks};
+use greeks_calculator::types::{ExerciseStyle, OptionType};
 
 fn bench_single_greeks(c: &mut Criterion) {
     let params = BlackScholesParams::new(100.0, 100.0, 1.0, 0.25, 0.05, 0.0);
@@ -50,5 +50,32 @@ fn bench_different_maturities(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_single_greeks, bench_option_chain, bench_different_maturities);
+fn bench_american(c: &mut Criterion) {
+    let mut group = c.benchmark_group("american_put");
+    let params = BlackScholesParams::new(100.0, 100.0, 1.0, 0.25, 0.05, 0.02);
+
+    for (name, method) in [
+        ("barone_adesi_whaley", AmericanApproximation::BaroneAdesiWhaley),
+        ("bjerksund_stensland", AmericanApproximation::BjerksundStensland),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| american::calculate_greeks(black_box(&params), black_box(OptionType::Put), method));
+        });
+    }
+    group.bench_function("leisen_reimer_201", |b| {
+        b.iter(|| {
+            binomial::calculate_greeks(
+                black_box(&params),
+                black_box(OptionType::Put),
+                ExerciseStyle::American,
+                BinomialMethod::LeisenReimer,
+                201,
+            )
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_single_greeks, bench_option_chain, bench_different_maturities, bench_american);
 criterion_main!(benches);
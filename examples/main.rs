@@ -35,6 +35,7 @@ fn main() {
     println!("  Vega:   {:.4}", greeks.vega);
     println!("  Theta:  {:.4}", greeks.theta);
     println!("  Rho:    {:.4}", greeks.rho);
+    println!("  Phi:    {:.4}", greeks.phi);
     println!("\nCalculation time: {:.2?}", duration);
 
     // Example 2: Option chain calculation
@@ -58,7 +59,7 @@ fn main() {
     println!("\nSample results (first 5 strikes):");
     println!("  Strike    Price     Delta     Gamma     Vega");
     println!("  ------    -----     -----     -----     ----");
-    for (i, (&strike, greeks)) in strikes.iter().zip(chain_greeks.iter()).take(5).enumerate() {
+    for (&strike, greeks) in strikes.iter().zip(chain_greeks.iter()).take(5) {
         println!("  ${:>5.0}   ${:>6.3}    {:>5.3}    {:>5.4}   {:>5.3}",
                  strike, greeks.price, greeks.delta, greeks.gamma, greeks.vega);
     }
@@ -0,0 +1,215 @@
+//! Complex numbers over any `Scalar`
+//!
+//! Characteristic-function pricers work in complex arithmetic; building the
+//! real and imaginary parts from AD numbers differentiates them for free.
+
+use super::scalar::Scalar;
+use std::f64::consts::PI;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Complex number re + i im
+#[derive(Debug, Clone, Copy)]
+pub struct Complex<T> {
+    pub re: T,
+    pub im: T,
+}
+
+impl<T: Scalar> Complex<T> {
+    #[inline]
+    pub fn new(re: T, im: T) -> Self {
+        Self { re, im }
+    }
+
+    /// Real number lifted to the complex plane
+    #[inline]
+    pub fn real(re: T) -> Self {
+        Self { re, im: T::constant(0.0) }
+    }
+
+    /// Modulus |z|
+    #[inline]
+    pub fn norm(self) -> T {
+        (self.re * self.re + self.im * self.im).sqrt()
+    }
+
+    /// Principal argument in (-π, π]
+    #[inline]
+    pub fn arg(self) -> T {
+        atan2(self.im, self.re)
+    }
+
+    #[inline]
+    pub fn exp(self) -> Self {
+        let modulus = self.re.exp();
+        Self {
+            re: modulus * self.im.cos(),
+            im: modulus * self.im.sin(),
+        }
+    }
+
+    /// Principal logarithm
+    #[inline]
+    pub fn ln(self) -> Self {
+        Self {
+            re: (self.re * self.re + self.im * self.im).ln() * 0.5,
+            im: self.arg(),
+        }
+    }
+
+    /// Principal square root, with non-negative real part
+    #[inline]
+    pub fn sqrt(self) -> Self {
+        let modulus = self.norm();
+        let re = ((modulus + self.re) * 0.5).max(T::constant(0.0)).sqrt();
+        let im = ((modulus - self.re) * 0.5).max(T::constant(0.0)).sqrt();
+        if self.im.value() < 0.0 {
+            Self { re, im: -im }
+        } else {
+            Self { re, im }
+        }
+    }
+}
+
+/// Four-quadrant arctangent of y / x in (-π, π]
+#[inline]
+pub fn atan2<T: Scalar>(y: T, x: T) -> T {
+    if x.value() > 0.0 {
+        (y / x).atan()
+    } else if x.value() < 0.0 {
+        if y.value() >= 0.0 {
+            (y / x).atan() + PI
+        } else {
+            (y / x).atan() - PI
+        }
+    } else if y.value() >= 0.0 {
+        -(x / y).atan() + PI / 2.0
+    } else {
+        -(x / y).atan() - PI / 2.0
+    }
+}
+
+impl<T: Scalar> Add for Complex<T> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl<T: Scalar> Sub for Complex<T> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl<T: Scalar> Mul for Complex<T> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+impl<T: Scalar> Div for Complex<T> {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: Self) -> Self {
+        let denominator = rhs.re * rhs.re + rhs.im * rhs.im;
+        Self::new(
+            (self.re * rhs.re + self.im * rhs.im) / denominator,
+            (self.im * rhs.re - self.re * rhs.im) / denominator,
+        )
+    }
+}
+
+impl<T: Scalar> Neg for Complex<T> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.im)
+    }
+}
+
+impl<T: Scalar> Add<T> for Complex<T> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: T) -> Self {
+        Self::new(self.re + rhs, self.im)
+    }
+}
+
+impl<T: Scalar> Sub<T> for Complex<T> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: T) -> Self {
+        Self::new(self.re - rhs, self.im)
+    }
+}
+
+impl<T: Scalar> Mul<T> for Complex<T> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: T) -> Self {
+        Self::new(self.re * rhs, self.im * rhs)
+    }
+}
+
+impl<T: Scalar> Div<T> for Complex<T> {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: T) -> Self {
+        Self::new(self.re / rhs, self.im / rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ad::Dual;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_elementary_functions() {
+        let z = Complex::new(-1.5, 0.8);
+        let w = z.ln().exp();
+        assert_relative_eq!(w.re, -1.5, epsilon = 1e-14);
+        assert_relative_eq!(w.im, 0.8, epsilon = 1e-14);
+
+        let root = z.sqrt();
+        assert!(root.re > 0.0);
+        let square = root * root;
+        assert_relative_eq!(square.re, -1.5, epsilon = 1e-14);
+        assert_relative_eq!(square.im, 0.8, epsilon = 1e-14);
+
+        let quotient = (z * Complex::new(0.3, -2.0)) / Complex::new(0.3, -2.0);
+        assert_relative_eq!(quotient.re, -1.5, epsilon = 1e-14);
+        assert_relative_eq!(quotient.im, 0.8, epsilon = 1e-14);
+
+        for &(y, x) in &[(1.0, 2.0), (1.0, -2.0), (-1.0, -2.0), (-1.0, 0.0), (0.0, -1.0)] {
+            assert_relative_eq!(atan2(y, x), f64::atan2(y, x), epsilon = 1e-15);
+        }
+    }
+
+    #[test]
+    fn test_derivative_of_complex_expression() {
+        // d/dx Re[ln(x + 2i)] = x / (x² + 4) and d/dx Im[...] = -2 / (x² + 4)
+        let x = -0.7;
+        let z = Complex::new(Dual::variable(x), Dual::constant(2.0)).ln();
+        assert_relative_eq!(z.re.deriv, x / (x * x + 4.0), epsilon = 1e-14);
+        assert_relative_eq!(z.im.deriv, -2.0 / (x * x + 4.0), epsilon = 1e-14);
+    }
+}
@@ -0,0 +1,441 @@
+use super::ops::{erf_value, erfc_value};
+use super::scalar::Scalar;
+use std::f64::consts::PI;
+use std::ops::{Add, Sub, Mul, Div, Neg};
+
+/// Hyper-dual number for exact second derivatives
+/// Represents f + f₁ε₁ + f₂ε₂ + f₁₂ε₁ε₂ where ε₁² = ε₂² = 0 and ε₁ε₂ ≠ 0.
+///
+/// Seeding input x along ε₁ and input y along ε₂ yields ∂f/∂x, ∂f/∂y and
+/// ∂²f/∂x∂y; seeding the same input along both yields its second derivative.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HyperDual {
+    /// The value of the function
+    pub value: f64,
+    /// First derivative along ε₁
+    pub eps1: f64,
+    /// First derivative along ε₂
+    pub eps2: f64,
+    /// Mixed second derivative along ε₁ε₂
+    pub eps12: f64,
+}
+
+impl HyperDual {
+    /// Create a new hyper-dual number
+    #[inline]
+    pub fn new(value: f64, eps1: f64, eps2: f64, eps12: f64) -> Self {
+        Self { value, eps1, eps2, eps12 }
+    }
+
+    /// Create a constant (all derivatives = 0)
+    #[inline]
+    pub fn constant(value: f64) -> Self {
+        Self { value, eps1: 0.0, eps2: 0.0, eps12: 0.0 }
+    }
+
+    /// Create a variable seeded along both directions, so `eps12` is d²f/dx²
+    #[inline]
+    pub fn variable(value: f64) -> Self {
+        Self { value, eps1: 1.0, eps2: 1.0, eps12: 0.0 }
+    }
+
+    /// Create a variable seeded along ε₁ only
+    #[inline]
+    pub fn variable1(value: f64) -> Self {
+        Self { value, eps1: 1.0, eps2: 0.0, eps12: 0.0 }
+    }
+
+    /// Create a variable seeded along ε₂ only
+    #[inline]
+    pub fn variable2(value: f64) -> Self {
+        Self { value, eps1: 0.0, eps2: 1.0, eps12: 0.0 }
+    }
+
+    /// Get the value
+    #[inline]
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Get the first derivative along ε₁
+    #[inline]
+    pub fn deriv1(&self) -> f64 {
+        self.eps1
+    }
+
+    /// Get the first derivative along ε₂
+    #[inline]
+    pub fn deriv2(&self) -> f64 {
+        self.eps2
+    }
+
+    /// Get the second (mixed) derivative
+    #[inline]
+    pub fn second_deriv(&self) -> f64 {
+        self.eps12
+    }
+
+    /// Apply a scalar function given its value, first and second derivative
+    /// at `self.value`: g(f)₁₂ = g'(f) f₁₂ + g''(f) f₁ f₂
+    #[inline]
+    fn chain(self, g: f64, dg: f64, d2g: f64) -> Self {
+        Self {
+            value: g,
+            eps1: dg * self.eps1,
+            eps2: dg * self.eps2,
+            eps12: dg * self.eps12 + d2g * self.eps1 * self.eps2,
+        }
+    }
+
+    /// Exponential function: all derivatives equal exp(f)
+    #[inline]
+    pub fn exp(self) -> Self {
+        let exp_val = self.value.exp();
+        self.chain(exp_val, exp_val, exp_val)
+    }
+
+    /// Natural logarithm: ln' = 1/f, ln'' = -1/f²
+    #[inline]
+    pub fn ln(self) -> Self {
+        let inv = 1.0 / self.value;
+        self.chain(self.value.ln(), inv, -inv * inv)
+    }
+
+    /// Square root: sqrt' = 1/(2 sqrt(f)), sqrt'' = -1/(4 f sqrt(f))
+    #[inline]
+    pub fn sqrt(self) -> Self {
+        let sqrt_val = self.value.sqrt();
+        self.chain(sqrt_val, 0.5 / sqrt_val, -0.25 / (self.value * sqrt_val))
+    }
+
+    /// Power function: (f^n)' = n f^(n-1), (f^n)'' = n (n-1) f^(n-2)
+    #[inline]
+    pub fn powf(self, n: f64) -> Self {
+        self.chain(
+            self.value.powf(n),
+            n * self.value.powf(n - 1.0),
+            n * (n - 1.0) * self.value.powf(n - 2.0),
+        )
+    }
+
+    /// Square: f² = f * f
+    #[inline]
+    pub fn powi2(self) -> Self {
+        self.chain(self.value * self.value, 2.0 * self.value, 2.0)
+    }
+
+    /// Error function: erf' = 2/sqrt(π) exp(-f²), erf'' = -2f erf'
+    #[inline]
+    pub fn erf(self) -> Self {
+        let deriv = (2.0 / PI.sqrt()) * (-self.value * self.value).exp();
+        self.chain(erf_value(self.value), deriv, -2.0 * self.value * deriv)
+    }
+
+    /// Complementary error function: erfc' = -erf', erfc'' = -erf''
+    #[inline]
+    pub fn erfc(self) -> Self {
+        let deriv = -(2.0 / PI.sqrt()) * (-self.value * self.value).exp();
+        self.chain(erfc_value(self.value), deriv, -2.0 * self.value * deriv)
+    }
+
+    /// Sine: sin' = cos(f), sin'' = -sin(f)
+    #[inline]
+    pub fn sin(self) -> Self {
+        let (sin_val, cos_val) = self.value.sin_cos();
+        self.chain(sin_val, cos_val, -sin_val)
+    }
+
+    /// Cosine: cos' = -sin(f), cos'' = -cos(f)
+    #[inline]
+    pub fn cos(self) -> Self {
+        let (sin_val, cos_val) = self.value.sin_cos();
+        self.chain(cos_val, -sin_val, -cos_val)
+    }
+
+    /// Arctangent: atan' = 1/(1 + f²), atan'' = -2f/(1 + f²)²
+    #[inline]
+    pub fn atan(self) -> Self {
+        let inv = 1.0 / (1.0 + self.value * self.value);
+        self.chain(self.value.atan(), inv, -2.0 * self.value * inv * inv)
+    }
+}
+
+// Arithmetic operations using the product rule up to second order
+
+impl Add for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            value: self.value + rhs.value,
+            eps1: self.eps1 + rhs.eps1,
+            eps2: self.eps2 + rhs.eps2,
+            eps12: self.eps12 + rhs.eps12,
+        }
+    }
+}
+
+impl Add<f64> for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: f64) -> Self::Output {
+        Self { value: self.value + rhs, ..self }
+    }
+}
+
+impl Add<HyperDual> for f64 {
+    type Output = HyperDual;
+
+    #[inline]
+    fn add(self, rhs: HyperDual) -> Self::Output {
+        rhs + self
+    }
+}
+
+impl Sub for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            value: self.value - rhs.value,
+            eps1: self.eps1 - rhs.eps1,
+            eps2: self.eps2 - rhs.eps2,
+            eps12: self.eps12 - rhs.eps12,
+        }
+    }
+}
+
+impl Sub<f64> for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: f64) -> Self::Output {
+        Self { value: self.value - rhs, ..self }
+    }
+}
+
+impl Sub<HyperDual> for f64 {
+    type Output = HyperDual;
+
+    #[inline]
+    fn sub(self, rhs: HyperDual) -> Self::Output {
+        -rhs + self
+    }
+}
+
+impl Mul for HyperDual {
+    type Output = Self;
+
+    /// (fg)₁₂ = f₁₂ g + f₁ g₂ + f₂ g₁ + f g₁₂
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self {
+            value: self.value * rhs.value,
+            eps1: self.eps1 * rhs.value + self.value * rhs.eps1,
+            eps2: self.eps2 * rhs.value + self.value * rhs.eps2,
+            eps12: self.eps12 * rhs.value
+                + self.eps1 * rhs.eps2
+                + self.eps2 * rhs.eps1
+                + self.value * rhs.eps12,
+        }
+    }
+}
+
+impl Mul<f64> for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: f64) -> Self::Output {
+        Self {
+            value: self.value * rhs,
+            eps1: self.eps1 * rhs,
+            eps2: self.eps2 * rhs,
+            eps12: self.eps12 * rhs,
+        }
+    }
+}
+
+impl Mul<HyperDual> for f64 {
+    type Output = HyperDual;
+
+    #[inline]
+    fn mul(self, rhs: HyperDual) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl HyperDual {
+    /// Reciprocal: (1/g)' = -1/g², (1/g)'' = 2/g³
+    #[inline]
+    fn recip(self) -> Self {
+        let inv = 1.0 / self.value;
+        self.chain(inv, -inv * inv, 2.0 * inv * inv * inv)
+    }
+}
+
+impl Div for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: Self) -> Self::Output {
+        Mul::mul(self, rhs.recip())
+    }
+}
+
+impl Div<f64> for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: f64) -> Self::Output {
+        self * (1.0 / rhs)
+    }
+}
+
+impl Div<HyperDual> for f64 {
+    type Output = HyperDual;
+
+    #[inline]
+    fn div(self, rhs: HyperDual) -> Self::Output {
+        let inv = 1.0 / rhs.value;
+        rhs.chain(self * inv, -self * inv * inv, 2.0 * self * inv * inv * inv)
+    }
+}
+
+impl Neg for HyperDual {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        self * -1.0
+    }
+}
+
+impl Scalar for HyperDual {
+    #[inline]
+    fn constant(value: f64) -> Self {
+        HyperDual::constant(value)
+    }
+
+    #[inline]
+    fn value(&self) -> f64 {
+        self.value
+    }
+
+    #[inline]
+    fn exp(self) -> Self {
+        HyperDual::exp(self)
+    }
+
+    #[inline]
+    fn ln(self) -> Self {
+        HyperDual::ln(self)
+    }
+
+    #[inline]
+    fn sqrt(self) -> Self {
+        HyperDual::sqrt(self)
+    }
+
+    #[inline]
+    fn powf(self, n: f64) -> Self {
+        HyperDual::powf(self, n)
+    }
+
+    #[inline]
+    fn powi2(self) -> Self {
+        HyperDual::powi2(self)
+    }
+
+    #[inline]
+    fn erf(self) -> Self {
+        HyperDual::erf(self)
+    }
+
+    #[inline]
+    fn erfc(self) -> Self {
+        HyperDual::erfc(self)
+    }
+
+    #[inline]
+    fn sin(self) -> Self {
+        HyperDual::sin(self)
+    }
+
+    #[inline]
+    fn cos(self) -> Self {
+        HyperDual::cos(self)
+    }
+
+    #[inline]
+    fn atan(self) -> Self {
+        HyperDual::atan(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ad::norm_cdf;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_second_derivative() {
+        // f(x) = x³ / ln(x), checked against hand-derived derivatives at x = 2
+        let x = HyperDual::variable(2.0);
+        let f = x.powf(3.0) / x.ln();
+
+        let l = 2.0_f64.ln();
+        let df = 3.0 * 4.0 / l - 4.0 / (l * l);
+        let d2f = 6.0 * 2.0 / l - 3.0 * 2.0 / (l * l) - 2.0 * 2.0 / (l * l) + 2.0 * 2.0 / (l * l * l);
+
+        assert_relative_eq!(f.value, 8.0 / l, epsilon = 1e-12);
+        assert_relative_eq!(f.eps1, df, epsilon = 1e-12);
+        assert_relative_eq!(f.eps2, df, epsilon = 1e-12);
+        assert_relative_eq!(f.eps12, d2f, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_mixed_derivative() {
+        // f(x, y) = exp(x y) sqrt(y) at (0.5, 2)
+        let x = HyperDual::variable1(0.5);
+        let y = HyperDual::variable2(2.0);
+        let f = (x * y).exp() * y.sqrt();
+
+        let (xv, yv) = (0.5_f64, 2.0_f64);
+        let e = (xv * yv).exp();
+        assert_relative_eq!(f.eps1, e * yv * yv.sqrt(), epsilon = 1e-12);
+        assert_relative_eq!(f.eps2, e * (xv * yv.sqrt() + 0.5 / yv.sqrt()), epsilon = 1e-12);
+        assert_relative_eq!(
+            f.eps12,
+            e * (1.5 * yv.sqrt() + xv * yv * yv.sqrt()),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_norm_cdf_second_derivative() {
+        // N''(x) = -x φ(x)
+        let x = 0.7;
+        let result = norm_cdf(HyperDual::variable(x));
+        let pdf = (-0.5 * x * x).exp() / (2.0 * PI).sqrt();
+
+        assert_relative_eq!(result.eps1, pdf, epsilon = 1e-12);
+        assert_relative_eq!(result.eps12, -x * pdf, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_trigonometric_second_derivatives() {
+        // f(x) = sin(x) cos(x) + atan(x) at x = 0.6
+        let x = 0.6_f64;
+        let f = HyperDual::variable(x);
+        let f = f.sin() * f.cos() + f.atan();
+
+        let inv = 1.0 / (1.0 + x * x);
+        assert_relative_eq!(f.value, x.sin() * x.cos() + x.atan(), epsilon = 1e-12);
+        assert_relative_eq!(f.eps1, (2.0 * x).cos() + inv, epsilon = 1e-12);
+        assert_relative_eq!(f.eps12, -2.0 * (2.0 * x).sin() - 2.0 * x * inv * inv, epsilon = 1e-12);
+    }
+}
@@ -2,9 +2,23 @@
 //! 
 //! Provides dual number implementation for forward-mode automatic differentiation.
 //! This enables exact calculation of derivatives (Greeks) without numerical approximation.
+//! `MultiDual` carries a full gradient so several inputs can be seeded in one pass,
+//! `HyperDual` propagates exact second derivatives, and the `Scalar` trait lets
+//! pricing code run unchanged on any of these types. The reverse-mode `tape`
+//! returns sensitivities to many inputs from a single backward sweep.
+//! `Complex` carries any of them through characteristic-function pricers.
 
+pub mod complex;
 pub mod dual;
+pub mod hyper_dual;
+pub mod multi_dual;
 pub mod ops;
+pub mod scalar;
+pub mod tape;
 
+pub use complex::Complex;
 pub use dual::Dual;
-pub use ops::{erf, norm_cdf, norm_pdf};
+pub use hyper_dual::HyperDual;
+pub use multi_dual::MultiDual;
+pub use ops::{bivariate_norm_cdf, erf, erfc, norm_cdf, norm_cdf_complement, norm_pdf};
+pub use scalar::Scalar;
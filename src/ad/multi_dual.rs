@@ -0,0 +1,367 @@
+use super::ops::{erf_value, erfc_value};
+use super::scalar::Scalar;
+use std::f64::consts::PI;
+use std::ops::{Add, Sub, Mul, Div, Neg};
+
+/// Multi-variable dual number for forward-mode automatic differentiation
+/// Represents a value and its gradient with respect to N seeded inputs:
+/// f(x) + Σ ∂f/∂xᵢ εᵢ where εᵢεⱼ = 0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MultiDual<const N: usize> {
+    /// The value of the function
+    pub value: f64,
+    /// The partial derivatives with respect to each seeded input
+    pub grad: [f64; N],
+}
+
+impl<const N: usize> MultiDual<N> {
+    /// Create a new multi-dual number
+    #[inline]
+    pub fn new(value: f64, grad: [f64; N]) -> Self {
+        Self { value, grad }
+    }
+
+    /// Create a constant (zero gradient)
+    #[inline]
+    pub fn constant(value: f64) -> Self {
+        Self { value, grad: [0.0; N] }
+    }
+
+    /// Create the `index`-th input variable (unit tangent in that direction)
+    #[inline]
+    pub fn variable(value: f64, index: usize) -> Self {
+        let mut grad = [0.0; N];
+        grad[index] = 1.0;
+        Self { value, grad }
+    }
+
+    /// Get the value
+    #[inline]
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Get the full gradient
+    #[inline]
+    pub fn grad(&self) -> [f64; N] {
+        self.grad
+    }
+
+    /// Get the partial derivative with respect to the `index`-th input
+    #[inline]
+    pub fn partial(&self, index: usize) -> f64 {
+        self.grad[index]
+    }
+
+    /// Apply a scalar function with value `f` and derivative `df` at `self.value`
+    #[inline]
+    fn chain(self, f: f64, df: f64) -> Self {
+        let mut grad = self.grad;
+        for g in grad.iter_mut() {
+            *g *= df;
+        }
+        Self { value: f, grad }
+    }
+
+    /// Exponential function: exp(f)' = f' * exp(f)
+    #[inline]
+    pub fn exp(self) -> Self {
+        let exp_val = self.value.exp();
+        self.chain(exp_val, exp_val)
+    }
+
+    /// Natural logarithm: ln(f)' = f' / f
+    #[inline]
+    pub fn ln(self) -> Self {
+        self.chain(self.value.ln(), 1.0 / self.value)
+    }
+
+    /// Square root: sqrt(f)' = f' / (2 * sqrt(f))
+    #[inline]
+    pub fn sqrt(self) -> Self {
+        let sqrt_val = self.value.sqrt();
+        self.chain(sqrt_val, 0.5 / sqrt_val)
+    }
+
+    /// Power function: (f^n)' = n * f^(n-1) * f'
+    #[inline]
+    pub fn powf(self, n: f64) -> Self {
+        self.chain(self.value.powf(n), n * self.value.powf(n - 1.0))
+    }
+
+    /// Square: f² = f * f
+    #[inline]
+    pub fn powi2(self) -> Self {
+        self.chain(self.value * self.value, 2.0 * self.value)
+    }
+
+    /// Error function: erf(f)' = f' * 2/sqrt(π) * exp(-f²)
+    #[inline]
+    pub fn erf(self) -> Self {
+        let deriv = (2.0 / PI.sqrt()) * (-self.value * self.value).exp();
+        self.chain(erf_value(self.value), deriv)
+    }
+
+    /// Complementary error function: erfc(f)' = -f' * 2/sqrt(π) * exp(-f²)
+    #[inline]
+    pub fn erfc(self) -> Self {
+        let deriv = -(2.0 / PI.sqrt()) * (-self.value * self.value).exp();
+        self.chain(erfc_value(self.value), deriv)
+    }
+
+    /// Sine: sin(f)' = f' * cos(f)
+    #[inline]
+    pub fn sin(self) -> Self {
+        self.chain(self.value.sin(), self.value.cos())
+    }
+
+    /// Cosine: cos(f)' = -f' * sin(f)
+    #[inline]
+    pub fn cos(self) -> Self {
+        self.chain(self.value.cos(), -self.value.sin())
+    }
+
+    /// Arctangent: atan(f)' = f' / (1 + f²)
+    #[inline]
+    pub fn atan(self) -> Self {
+        self.chain(self.value.atan(), 1.0 / (1.0 + self.value * self.value))
+    }
+}
+
+// Arithmetic operations using the chain rule, applied component-wise
+
+impl<const N: usize> Add for MultiDual<N> {
+    type Output = Self;
+
+    /// ∇(f + g) = ∇f + ∇g
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut grad = self.grad;
+        for (g, r) in grad.iter_mut().zip(rhs.grad.iter()) {
+            *g += r;
+        }
+        Self { value: self.value + rhs.value, grad }
+    }
+}
+
+impl<const N: usize> Add<f64> for MultiDual<N> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: f64) -> Self::Output {
+        Self { value: self.value + rhs, grad: self.grad }
+    }
+}
+
+impl<const N: usize> Add<MultiDual<N>> for f64 {
+    type Output = MultiDual<N>;
+
+    #[inline]
+    fn add(self, rhs: MultiDual<N>) -> Self::Output {
+        rhs + self
+    }
+}
+
+impl<const N: usize> Sub for MultiDual<N> {
+    type Output = Self;
+
+    /// ∇(f - g) = ∇f - ∇g
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut grad = self.grad;
+        for (g, r) in grad.iter_mut().zip(rhs.grad.iter()) {
+            *g -= r;
+        }
+        Self { value: self.value - rhs.value, grad }
+    }
+}
+
+impl<const N: usize> Sub<f64> for MultiDual<N> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: f64) -> Self::Output {
+        Self { value: self.value - rhs, grad: self.grad }
+    }
+}
+
+impl<const N: usize> Sub<MultiDual<N>> for f64 {
+    type Output = MultiDual<N>;
+
+    #[inline]
+    fn sub(self, rhs: MultiDual<N>) -> Self::Output {
+        -rhs + self
+    }
+}
+
+impl<const N: usize> Mul for MultiDual<N> {
+    type Output = Self;
+
+    /// ∇(f * g) = g ∇f + f ∇g
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut grad = self.grad;
+        for (g, r) in grad.iter_mut().zip(rhs.grad.iter()) {
+            *g = *g * rhs.value + self.value * r;
+        }
+        Self { value: self.value * rhs.value, grad }
+    }
+}
+
+impl<const N: usize> Mul<f64> for MultiDual<N> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: f64) -> Self::Output {
+        self.chain(self.value * rhs, rhs)
+    }
+}
+
+impl<const N: usize> Mul<MultiDual<N>> for f64 {
+    type Output = MultiDual<N>;
+
+    #[inline]
+    fn mul(self, rhs: MultiDual<N>) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl<const N: usize> Div for MultiDual<N> {
+    type Output = Self;
+
+    /// ∇(f / g) = (g ∇f - f ∇g) / g²
+    #[inline]
+    fn div(self, rhs: Self) -> Self::Output {
+        let g_squared = rhs.value * rhs.value;
+        let mut grad = self.grad;
+        for (g, r) in grad.iter_mut().zip(rhs.grad.iter()) {
+            *g = (*g * rhs.value - self.value * r) / g_squared;
+        }
+        Self { value: self.value / rhs.value, grad }
+    }
+}
+
+impl<const N: usize> Div<f64> for MultiDual<N> {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: f64) -> Self::Output {
+        self.chain(self.value / rhs, 1.0 / rhs)
+    }
+}
+
+impl<const N: usize> Div<MultiDual<N>> for f64 {
+    type Output = MultiDual<N>;
+
+    #[inline]
+    fn div(self, rhs: MultiDual<N>) -> Self::Output {
+        rhs.chain(self / rhs.value, -self / (rhs.value * rhs.value))
+    }
+}
+
+impl<const N: usize> Neg for MultiDual<N> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        self.chain(-self.value, -1.0)
+    }
+}
+
+impl<const N: usize> Scalar for MultiDual<N> {
+    #[inline]
+    fn constant(value: f64) -> Self {
+        MultiDual::constant(value)
+    }
+
+    #[inline]
+    fn value(&self) -> f64 {
+        self.value
+    }
+
+    #[inline]
+    fn exp(self) -> Self {
+        MultiDual::exp(self)
+    }
+
+    #[inline]
+    fn ln(self) -> Self {
+        MultiDual::ln(self)
+    }
+
+    #[inline]
+    fn sqrt(self) -> Self {
+        MultiDual::sqrt(self)
+    }
+
+    #[inline]
+    fn powf(self, n: f64) -> Self {
+        MultiDual::powf(self, n)
+    }
+
+    #[inline]
+    fn powi2(self) -> Self {
+        MultiDual::powi2(self)
+    }
+
+    #[inline]
+    fn erf(self) -> Self {
+        MultiDual::erf(self)
+    }
+
+    #[inline]
+    fn erfc(self) -> Self {
+        MultiDual::erfc(self)
+    }
+
+    #[inline]
+    fn sin(self) -> Self {
+        MultiDual::sin(self)
+    }
+
+    #[inline]
+    fn cos(self) -> Self {
+        MultiDual::cos(self)
+    }
+
+    #[inline]
+    fn atan(self) -> Self {
+        MultiDual::atan(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ad::{norm_cdf, Dual};
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_gradient_arithmetic() {
+        // f(x, y) = x * y / (x + y) at (3, 2)
+        let x = MultiDual::<2>::variable(3.0, 0);
+        let y = MultiDual::<2>::variable(2.0, 1);
+        let f = x * y / (x + y);
+
+        assert_relative_eq!(f.value, 1.2);
+        // ∂f/∂x = y² / (x + y)², ∂f/∂y = x² / (x + y)²
+        assert_relative_eq!(f.partial(0), 4.0 / 25.0, epsilon = 1e-12);
+        assert_relative_eq!(f.partial(1), 9.0 / 25.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_matches_single_dual() {
+        // Each gradient component should equal a Dual pass seeded on that input
+        let x = MultiDual::<2>::variable(0.7, 0);
+        let y = MultiDual::<2>::variable(1.3, 1);
+        let f = norm_cdf((x.ln() - y.sqrt()) * y.exp()) + 2.0 / x.powf(1.5);
+
+        let eval = |x: Dual, y: Dual| norm_cdf((x.ln() - y.sqrt()) * y.exp()) + 2.0 / x.powf(1.5);
+        let fx = eval(Dual::variable(0.7), Dual::constant(1.3));
+        let fy = eval(Dual::constant(0.7), Dual::variable(1.3));
+
+        assert_relative_eq!(f.value, fx.value, epsilon = 1e-12);
+        assert_relative_eq!(f.partial(0), fx.deriv, epsilon = 1e-12);
+        assert_relative_eq!(f.partial(1), fy.deriv, epsilon = 1e-12);
+    }
+}
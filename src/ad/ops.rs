@@ -1,7 +1,8 @@
 use super::dual::Dual;
-use std::f64::consts::{E, PI, SQRT_2};
+use super::scalar::Scalar;
+use std::f64::consts::{PI, SQRT_2};
 
-/// Mathematical operations for dual numbers
+// Mathematical operations for dual numbers
 
 impl Dual {
     /// Exponential function: exp(f)' = f' * exp(f)
@@ -81,63 +82,445 @@ impl Dual {
             other
         }
     }
+
+    /// Error function: erf(f)' = f' * 2/sqrt(π) * exp(-f²)
+    #[inline]
+    pub fn erf(self) -> Self {
+        Self {
+            value: erf_value(self.value),
+            deriv: (2.0 / PI.sqrt()) * (-self.value * self.value).exp() * self.deriv,
+        }
+    }
+
+    /// Complementary error function: erfc(f)' = -f' * 2/sqrt(π) * exp(-f²)
+    #[inline]
+    pub fn erfc(self) -> Self {
+        Self {
+            value: erfc_value(self.value),
+            deriv: -(2.0 / PI.sqrt()) * (-self.value * self.value).exp() * self.deriv,
+        }
+    }
+
+    /// Sine: sin(f)' = f' * cos(f)
+    #[inline]
+    pub fn sin(self) -> Self {
+        Self {
+            value: self.value.sin(),
+            deriv: self.value.cos() * self.deriv,
+        }
+    }
+
+    /// Cosine: cos(f)' = -f' * sin(f)
+    #[inline]
+    pub fn cos(self) -> Self {
+        Self {
+            value: self.value.cos(),
+            deriv: -self.value.sin() * self.deriv,
+        }
+    }
+
+    /// Arctangent: atan(f)' = f' / (1 + f²)
+    #[inline]
+    pub fn atan(self) -> Self {
+        Self {
+            value: self.value.atan(),
+            deriv: self.deriv / (1.0 + self.value * self.value),
+        }
+    }
 }
 
-/// Error function approximation (needed for normal CDF)
-/// Using Abramowitz and Stegun approximation
+impl Scalar for Dual {
+    #[inline]
+    fn constant(value: f64) -> Self {
+        Dual::constant(value)
+    }
+
+    #[inline]
+    fn value(&self) -> f64 {
+        self.value
+    }
+
+    #[inline]
+    fn exp(self) -> Self {
+        Dual::exp(self)
+    }
+
+    #[inline]
+    fn ln(self) -> Self {
+        Dual::ln(self)
+    }
+
+    #[inline]
+    fn sqrt(self) -> Self {
+        Dual::sqrt(self)
+    }
+
+    #[inline]
+    fn powf(self, n: f64) -> Self {
+        Dual::powf(self, n)
+    }
+
+    #[inline]
+    fn erf(self) -> Self {
+        Dual::erf(self)
+    }
+
+    #[inline]
+    fn erfc(self) -> Self {
+        Dual::erfc(self)
+    }
+
+    #[inline]
+    fn sin(self) -> Self {
+        Dual::sin(self)
+    }
+
+    #[inline]
+    fn cos(self) -> Self {
+        Dual::cos(self)
+    }
+
+    #[inline]
+    fn atan(self) -> Self {
+        Dual::atan(self)
+    }
+}
+
+/// Error function value, double precision
 #[inline]
-pub fn erf(x: Dual) -> Dual {
-    // erf'(x) = 2/sqrt(π) * exp(-x²)
-    let t = 1.0 / (1.0 + 0.5 * x.value.abs());
-    
-    let tau = t * (-x.value * x.value - 1.26551223 +
-                    t * (1.00002368 +
-                    t * (0.37409196 +
-                    t * (0.09678418 +
-                    t * (-0.18628806 +
-                    t * (0.27886807 +
-                    t * (-1.13520398 +
-                    t * (1.48851587 +
-                    t * (-0.82215223 +
-                    t * 0.17087277)))))))));
-    
-    let erf_val = if x.value >= 0.0 {
-        1.0 - tau.exp()
+pub(crate) fn erf_value(x: f64) -> f64 {
+    calerf(x, ErfKind::Erf)
+}
+
+/// Complementary error function value, keeping full relative precision for large x
+#[inline]
+pub(crate) fn erfc_value(x: f64) -> f64 {
+    calerf(x, ErfKind::Erfc)
+}
+
+/// Scaled complementary error function value erfcx(x) = e^(x²) erfc(x)
+#[inline]
+pub(crate) fn erfcx_value(x: f64) -> f64 {
+    calerf(x, ErfKind::Erfcx)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ErfKind {
+    Erf,
+    Erfc,
+    Erfcx,
+}
+
+/// W. J. Cody's rational Chebyshev approximations of erf, erfc and erfcx,
+/// accurate to about one ulp over the whole real line
+///
+/// Reference: W. J. Cody, "Rational Chebyshev approximations for the error
+/// function", Math. Comp. 23 (1969), 631-637.
+fn calerf(x: f64, kind: ErfKind) -> f64 {
+    const A: [f64; 5] = [
+        3.1611237438705655,
+        113.86415415105016,
+        377.485237685302,
+        3209.3775891384694,
+        0.18577770618460315,
+    ];
+    const B: [f64; 4] = [
+        23.601290952344122,
+        244.02463793444417,
+        1282.6165260773723,
+        2844.236833439171,
+    ];
+    const C: [f64; 9] = [
+        0.5641884969886701,
+        8.883149794388377,
+        66.11919063714163,
+        298.6351381974001,
+        881.952221241769,
+        1712.0476126340707,
+        2051.0783778260716,
+        1230.3393547979972,
+        2.1531153547440383e-8,
+    ];
+    const D: [f64; 8] = [
+        15.744926110709835,
+        117.6939508913125,
+        537.1811018620099,
+        1621.3895745666903,
+        3290.7992357334597,
+        4362.619090143247,
+        3439.3676741437216,
+        1230.3393548037495,
+    ];
+    const P: [f64; 6] = [
+        0.30532663496123236,
+        0.36034489994980445,
+        0.12578172611122926,
+        0.016083785148742275,
+        0.0006587491615298378,
+        0.016315387137302097,
+    ];
+    const Q: [f64; 5] = [
+        2.568520192289822,
+        1.8729528499234673,
+        0.5279051029514285,
+        0.06051834131244132,
+        0.0023352049762686918,
+    ];
+    const ONE_OVER_SQRT_PI: f64 = 5.641895835477563e-1;
+    const THRESHOLD: f64 = 0.46875;
+    const X_SMALL: f64 = 1.11e-16;
+    const X_BIG: f64 = 26.543;
+    const X_HUGE: f64 = 6.71000000e7;
+    const X_MAX: f64 = 2.53e307;
+    const X_NEG: f64 = -26.628;
+
+    // e^(±y²) split as e^(±y₀²) e^(±(y - y₀)(y + y₀)) with y₀ a multiple of 1/16 to limit rounding
+    let exp_square = |y: f64, sign: f64| {
+        let y0 = (y * 16.0).trunc() / 16.0;
+        (sign * y0 * y0).exp() * (sign * (y - y0) * (y + y0)).exp()
+    };
+
+    let y = x.abs();
+    if y <= THRESHOLD {
+        let ysq = if y > X_SMALL { y * y } else { 0.0 };
+        let mut num = A[4] * ysq;
+        let mut den = ysq;
+        for i in 0..3 {
+            num = (num + A[i]) * ysq;
+            den = (den + B[i]) * ysq;
+        }
+        let erf = x * (num + A[3]) / (den + B[3]);
+        return match kind {
+            ErfKind::Erf => erf,
+            ErfKind::Erfc => 1.0 - erf,
+            ErfKind::Erfcx => ysq.exp() * (1.0 - erf),
+        };
+    }
+
+    // erfc(|x|), or erfcx(|x|) when the scaled form is requested
+    let result = if y <= 4.0 {
+        let mut num = C[8] * y;
+        let mut den = y;
+        for i in 0..7 {
+            num = (num + C[i]) * y;
+            den = (den + D[i]) * y;
+        }
+        let r = (num + C[7]) / (den + D[7]);
+        if kind == ErfKind::Erfcx { r } else { exp_square(y, -1.0) * r }
+    } else if y >= X_BIG && (kind != ErfKind::Erfcx || y >= X_MAX) {
+        0.0
+    } else if y >= X_HUGE {
+        ONE_OVER_SQRT_PI / y
     } else {
-        tau.exp() - 1.0
+        let ysq = 1.0 / (y * y);
+        let mut num = P[5] * ysq;
+        let mut den = ysq;
+        for i in 0..4 {
+            num = (num + P[i]) * ysq;
+            den = (den + Q[i]) * ysq;
+        }
+        let r = (ONE_OVER_SQRT_PI - ysq * (num + P[4]) / (den + Q[4])) / y;
+        if kind == ErfKind::Erfcx { r } else { exp_square(y, -1.0) * r }
     };
-    
-    // Derivative: erf'(x) = 2/sqrt(π) * exp(-x²)
-    let erf_deriv = (2.0 / PI.sqrt()) * (-x.value * x.value).exp() * x.deriv;
-    
-    Dual {
-        value: erf_val,
-        deriv: erf_deriv,
+
+    match kind {
+        ErfKind::Erf => {
+            let erf = (0.5 - result) + 0.5;
+            if x < 0.0 { -erf } else { erf }
+        }
+        ErfKind::Erfc => {
+            if x < 0.0 { 2.0 - result } else { result }
+        }
+        ErfKind::Erfcx => {
+            if x >= 0.0 {
+                result
+            } else if x < X_NEG {
+                f64::INFINITY
+            } else {
+                2.0 * exp_square(x, 1.0) - result
+            }
+        }
     }
 }
 
+/// Error function for any AD number type
+#[inline]
+pub fn erf<T: Scalar>(x: T) -> T {
+    x.erf()
+}
+
+/// Complementary error function for any AD number type
+#[inline]
+pub fn erfc<T: Scalar>(x: T) -> T {
+    x.erfc()
+}
+
 /// Standard normal cumulative distribution function
-/// N(x) = 0.5 * (1 + erf(x / sqrt(2)))
+/// N(x) = 0.5 * erfc(-x / sqrt(2)), accurate in relative terms far into the left tail
 #[inline]
-pub fn norm_cdf(x: Dual) -> Dual {
-    let scaled = x / SQRT_2;
-    let erf_result = erf(scaled);
-    (erf_result + 1.0) * 0.5
+pub fn norm_cdf<T: Scalar>(x: T) -> T {
+    erfc(-x / SQRT_2) * 0.5
+}
+
+/// Upper tail of the standard normal distribution
+/// 1 - N(x) = N(-x) = 0.5 * erfc(x / sqrt(2)), without the cancellation of 1 - N(x)
+#[inline]
+pub fn norm_cdf_complement<T: Scalar>(x: T) -> T {
+    erfc(x / SQRT_2) * 0.5
 }
 
 /// Standard normal probability density function
 /// φ(x) = 1/sqrt(2π) * exp(-x²/2)
 #[inline]
-pub fn norm_pdf(x: Dual) -> Dual {
+pub fn norm_pdf<T: Scalar>(x: T) -> T {
     let coeff = 1.0 / (2.0 * PI).sqrt();
     let exp_term = (-x.powi2() / 2.0).exp();
-    Dual::constant(coeff) * exp_term
+    exp_term * coeff
+}
+
+/// (eˣ - 1) / x, by series near zero
+pub(crate) fn relative_expm1<T: Scalar>(x: T) -> T {
+    if x.value().abs() < 1e-2 {
+        x * (x * (x * (x * (x / 720.0 + 1.0 / 120.0) + 1.0 / 24.0) + 1.0 / 6.0) + 0.5) + 1.0
+    } else {
+        (x.exp() - 1.0) / x
+    }
+}
+
+/// ln N(x), finite with finite derivatives wherever N(x) itself underflows
+///
+/// Below x = -10 the Gaussian tail goes through the Laplace continued fraction
+/// for the Mills ratio, N(x) = φ(x) / (t + 1/(t + 2/(t + ...))) with t = -x.
+pub(crate) fn ln_norm_cdf<T: Scalar>(x: T) -> T {
+    if x.value() > -10.0 {
+        return norm_cdf(x).ln();
+    }
+    let t = -x;
+    let mut denominator = t;
+    for k in (1..=20).rev() {
+        denominator = t + T::constant(k as f64) / denominator;
+    }
+    -x.powi2() * 0.5 - denominator.ln() - (2.0 * PI).sqrt().ln()
+}
+
+/// Gauss-Legendre abscissae and weights on [-1, 1], one half of each symmetric rule
+const GAUSS_LEGENDRE_6: [(f64, f64); 3] = [
+    (0.932469514203152, 0.17132449237917036),
+    (0.6612093864662645, 0.3607615730481386),
+    (0.2386191860831969, 0.46791393457269104),
+];
+const GAUSS_LEGENDRE_12: [(f64, f64); 6] = [
+    (0.9815606342467192, 0.04717533638651183),
+    (0.9041172563704749, 0.10693932599531843),
+    (0.7699026741943047, 0.16007832854334622),
+    (0.5873179542866175, 0.20316742672306592),
+    (0.3678314989981802, 0.2334925365383548),
+    (0.1252334085114689, 0.24914704581340277),
+];
+pub(crate) const GAUSS_LEGENDRE_20: [(f64, f64); 10] = [
+    (0.9931285991850949, 0.017614007139152118),
+    (0.9639719272779138, 0.04060142980038694),
+    (0.912234428251326, 0.06267204833410907),
+    (0.8391169718222188, 0.08327674157670475),
+    (0.7463319064601508, 0.10193011981724044),
+    (0.636053680726515, 0.11819453196151841),
+    (0.5108670019508271, 0.13168863844917664),
+    (0.37370608871541955, 0.14209610931838204),
+    (0.22778585114164507, 0.14917298647260374),
+    (0.07652652113349734, 0.15275338713072584),
+];
+
+/// Bivariate standard normal CDF M(a, b; ρ) = P(X < a, Y < b) with correlation ρ
+///
+/// Genz (2004), "Numerical computation of rectangular bivariate and trivariate
+/// normal and t probabilities": Drezner-Wesolowsky quadrature in arcsin ρ for
+/// |ρ| < 0.925, and an expansion of the near-singular integrand above that.
+/// Accurate to about 1e-15 absolute. Generic in the limits and the correlation,
+/// so sensitivities to all three flow through.
+pub fn bivariate_norm_cdf<T: Scalar>(a: T, b: T, rho: T) -> T {
+    let r = rho.value();
+    let rule: &[(f64, f64)] = if r.abs() < 0.3 {
+        &GAUSS_LEGENDRE_6
+    } else if r.abs() < 0.75 {
+        &GAUSS_LEGENDRE_12
+    } else {
+        &GAUSS_LEGENDRE_20
+    };
+    // Genz works with the upper probability P(X > h, Y > k)
+    let h = -a;
+    let mut k = -b;
+    let mut hk = h * k;
+    let zero = T::constant(0.0);
+    let one = T::constant(1.0);
+
+    if r.abs() < 0.925 {
+        let hs = (h * h + k * k) * 0.5;
+        let asr = (rho / (one - rho * rho).sqrt()).atan();
+        let mut sum = zero;
+        for &(x, w) in rule {
+            for sn in [(asr * (1.0 - x) * 0.5).sin(), (asr * (1.0 + x) * 0.5).sin()] {
+                sum = sum + ((hk * sn - hs) / (one - sn * sn)).exp() * w;
+            }
+        }
+        return sum * asr / (4.0 * PI) + norm_cdf(-h) * norm_cdf(-k);
+    }
+
+    if r < 0.0 {
+        k = -k;
+        hk = -hk;
+    }
+    let mut bvn = zero;
+    if r.abs() < 1.0 {
+        let as_ = (one - rho) * (one + rho);
+        let mut a = as_.sqrt();
+        let bs = (h - k).powi2();
+        let c = (-hk + 4.0) / 8.0;
+        let d = (-hk + 12.0) / 16.0;
+        let asr = -(bs / as_ + hk) * 0.5;
+        if asr.value() > -100.0 {
+            bvn = asr.exp() * a * (one - c * (bs - as_) * (one - d * bs / 5.0) / 3.0 + c * d * as_ * as_ / 5.0);
+        }
+        if hk.value() > -100.0 {
+            let b = bs.sqrt();
+            let sp = norm_cdf(-b / a) * (2.0 * PI).sqrt();
+            bvn = bvn - (-hk * 0.5).exp() * sp * b * (one - c * bs * (one - d * bs / 5.0) / 3.0);
+        }
+        a = a * 0.5;
+        for &(x, w) in rule {
+            for sign in [-1.0, 1.0] {
+                let xs = (a + a * (sign * x)).powi2();
+                let rs = (one - xs).sqrt();
+                let asr = -(bs / xs + hk) * 0.5;
+                if asr.value() > -100.0 {
+                    let sp = c * xs * (d * xs + 1.0) + 1.0;
+                    let ep = (-hk * ((one - rs) / ((rs + 1.0) * 2.0))).exp() / rs;
+                    bvn = bvn + asr.exp() * (ep - sp) * a * w;
+                }
+            }
+        }
+        bvn = -bvn / (2.0 * PI);
+    }
+
+    if r > 0.0 {
+        bvn + norm_cdf(-h.max(k))
+    } else if h.value() >= k.value() {
+        -bvn
+    } else {
+        let l = if h.value() < 0.0 {
+            norm_cdf(k) - norm_cdf(h)
+        } else {
+            norm_cdf(-h) - norm_cdf(-k)
+        };
+        l - bvn
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use approx::assert_relative_eq;
+    use crate::ad::HyperDual;
+    use std::f64::consts::E;
 
     #[test]
     fn test_exp() {
@@ -167,7 +550,84 @@ mod tests {
     fn test_norm_cdf() {
         let x = Dual::variable(0.0);
         let result = norm_cdf(x);
-        assert_relative_eq!(result.value, 0.5, epsilon = 1e-6);
+        assert_relative_eq!(result.value, 0.5, epsilon = 1e-15);
+        assert_relative_eq!(result.deriv, norm_pdf(0.0), epsilon = 1e-15);
+
+        // N(x) evaluated in 50-digit arithmetic; relative accuracy must survive the far left tail
+        let cases = [
+            (-37.0, 5.725571222524577e-300),
+            (-20.0, 2.7536241186062337e-89),
+            (-10.0, 7.619853024160525e-24),
+            (-5.0, 2.866515718791939e-07),
+            (-2.5, 0.006209665325776135),
+            (-1.0, 0.15865525393145705),
+            (-0.5, 0.3085375387259869),
+            (0.5, 0.6914624612740131),
+            (1.0, 0.8413447460685429),
+            (3.0, 0.9986501019683699),
+        ];
+        for &(x, expected) in &cases {
+            assert_relative_eq!(norm_cdf(x), expected, max_relative = 1e-14);
+            assert_relative_eq!(norm_cdf_complement(-x), expected, max_relative = 1e-14);
+        }
+
+        // ln N(x) joins the direct logarithm at -10 and stays finite past underflow
+        for &(x, expected) in &[(-9.5, -48.30601929896523), (-10.5, -58.40418706107324), (-30.0, -454.3212439563432), (-30.5, -469.4627373229121), (-40.0, -804.6084420137538), (-200.0, -20006.21728089819)] {
+            assert_relative_eq!(ln_norm_cdf(x), expected, max_relative = 1e-14);
+        }
+        assert_relative_eq!(ln_norm_cdf(Dual::variable(-40.0)).deriv, 40.02496884720726, max_relative = 1e-14);
+        assert!(ln_norm_cdf(HyperDual::variable(-25.0)).second_deriv().is_finite());
+    }
+
+    #[test]
+    fn test_erf_reference_table() {
+        // erf(x) evaluated in 50-digit arithmetic, spanning every rational approximation
+        let cases = [
+            (-2.5, -0.999593047982555),
+            (-0.3, -0.3286267594591274),
+            (1e-10, 1.1283791670955126e-10),
+            (0.1, 0.1124629160182849),
+            (0.46875, 0.49261347321793797),
+            (0.5, 0.5204998778130465),
+            (1.0, 0.8427007929497149),
+            (2.0, 0.9953222650189527),
+            (3.5, 0.9999992569016276),
+            (4.5, 0.9999999998033839),
+            (6.0, 1.0),
+        ];
+        for &(x, expected) in &cases {
+            assert_relative_eq!(erf(x), expected, max_relative = 1e-15);
+        }
+    }
+
+    #[test]
+    fn test_erfc_reference_table() {
+        // erfc(x) evaluated in 50-digit arithmetic, down to values near underflow
+        let cases = [
+            (-1.0, 1.8427007929497148),
+            (0.2, 0.7772974107895215),
+            (0.5, 0.4795001221869535),
+            (1.0, 0.15729920705028513),
+            (3.0, 2.209049699858544e-05),
+            (4.0, 1.541725790028002e-08),
+            (5.5, 7.357847917974398e-15),
+            (10.0, 2.088487583762545e-45),
+            (26.0, 5.663192408856143e-296),
+        ];
+        for &(x, expected) in &cases {
+            assert_relative_eq!(erfc(x), expected, max_relative = 1e-14);
+        }
+    }
+
+    #[test]
+    fn test_erfc_derivative() {
+        // erfc' = -erf' and the two stay complementary
+        for &x in &[-2.0, -0.2, 0.3, 1.5, 4.0] {
+            let a = Dual::variable(x).erf();
+            let b = Dual::variable(x).erfc();
+            assert_relative_eq!(b.deriv, -a.deriv, epsilon = 1e-15);
+            assert_relative_eq!(a.value + b.value, 1.0, epsilon = 1e-15);
+        }
     }
 
     #[test]
@@ -181,4 +641,35 @@ mod tests {
         assert_relative_eq!(result.value, E.powf(4.0), epsilon = 1e-10);
         assert_relative_eq!(result.deriv, 4.0 * E.powf(4.0), epsilon = 1e-10);
     }
+
+    #[test]
+    fn test_bivariate_norm_cdf() {
+        // Reference values by adaptive quadrature of φ(x) N((b - ρx)/√(1 - ρ²)) in 30 digits
+        let cases = [
+            (0.0, 0.0, 0.5, 1.0 / 3.0),
+            (0.3, -0.7, 0.2, 0.17289130039851154),
+            (-1.2, 0.8, -0.6, 0.046258880035091136),
+            (1.5, 2.0, 0.95, 0.9325426755471476),
+            (-0.4, -0.9, -0.97, 9.38937073150775e-10),
+            (0.25, -1.3, 0.99, 0.09680048458561033),
+            (-2.5, -2.1, 0.8, 0.003680713589395699),
+        ];
+        for &(a, b, rho, expected) in &cases {
+            let value: f64 = bivariate_norm_cdf(a, b, rho);
+            assert_relative_eq!(value, expected, epsilon = 1e-15, max_relative = 1e-10);
+
+            // ∂M/∂a = φ(a) N((b - ρa)/√(1 - ρ²))
+            let wrt_a = bivariate_norm_cdf(Dual::variable(a), Dual::constant(b), Dual::constant(rho));
+            let conditional = (b - rho * a) / (1.0 - rho * rho).sqrt();
+            let expected_deriv = norm_pdf(a) * norm_cdf(conditional);
+            assert_relative_eq!(wrt_a.deriv, expected_deriv, epsilon = 1e-13, max_relative = 1e-8);
+
+            // ∂M/∂ρ is the bivariate density
+            let wrt_rho = bivariate_norm_cdf(Dual::constant(a), Dual::constant(b), Dual::variable(rho));
+            let one_minus_rho2 = 1.0 - rho * rho;
+            let density = (-(a * a - 2.0 * rho * a * b + b * b) / (2.0 * one_minus_rho2)).exp()
+                / (2.0 * PI * one_minus_rho2.sqrt());
+            assert_relative_eq!(wrt_rho.deriv, density, epsilon = 1e-13, max_relative = 1e-8);
+        }
+    }
 }
@@ -0,0 +1,118 @@
+use std::ops::{Add, Sub, Mul, Div, Neg};
+
+/// Number type that pricing code can be written against once and then
+/// evaluated on plain `f64` or on any of the AD number types.
+///
+/// Implementors provide the arithmetic operators (also mixed with `f64` on the
+/// right-hand side) and the elementary functions used by the pricers.
+pub trait Scalar:
+    Copy
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + Add<f64, Output = Self>
+    + Sub<f64, Output = Self>
+    + Mul<f64, Output = Self>
+    + Div<f64, Output = Self>
+{
+    /// Lift a plain value (all derivative parts zero)
+    fn constant(value: f64) -> Self;
+
+    /// The underlying value, with derivative information dropped
+    fn value(&self) -> f64;
+
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn powf(self, n: f64) -> Self;
+    fn erf(self) -> Self;
+    fn erfc(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn atan(self) -> Self;
+
+    /// Square: f² = f * f
+    #[inline]
+    fn powi2(self) -> Self {
+        self * self
+    }
+
+    /// Maximum by value
+    #[inline]
+    fn max(self, other: Self) -> Self {
+        if self.value() >= other.value() {
+            self
+        } else {
+            other
+        }
+    }
+
+    /// Minimum by value
+    #[inline]
+    fn min(self, other: Self) -> Self {
+        if self.value() <= other.value() {
+            self
+        } else {
+            other
+        }
+    }
+}
+
+impl Scalar for f64 {
+    #[inline]
+    fn constant(value: f64) -> Self {
+        value
+    }
+
+    #[inline]
+    fn value(&self) -> f64 {
+        *self
+    }
+
+    #[inline]
+    fn exp(self) -> Self {
+        f64::exp(self)
+    }
+
+    #[inline]
+    fn ln(self) -> Self {
+        f64::ln(self)
+    }
+
+    #[inline]
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+
+    #[inline]
+    fn powf(self, n: f64) -> Self {
+        f64::powf(self, n)
+    }
+
+    #[inline]
+    fn erf(self) -> Self {
+        super::ops::erf_value(self)
+    }
+
+    #[inline]
+    fn erfc(self) -> Self {
+        super::ops::erfc_value(self)
+    }
+
+    #[inline]
+    fn sin(self) -> Self {
+        f64::sin(self)
+    }
+
+    #[inline]
+    fn cos(self) -> Self {
+        f64::cos(self)
+    }
+
+    #[inline]
+    fn atan(self) -> Self {
+        f64::atan(self)
+    }
+}
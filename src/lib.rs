@@ -4,7 +4,7 @@
 //! 
 //! # Features
 //! - Custom automatic differentiation engine using dual numbers
-//! - SVI volatility surface parameterization with arbitrage-free constraints
+//! - SVI and SABR volatility surface parameterizations with arbitrage-free constraints
 //! - Black-Scholes pricing with exact Greeks via AD
 //! - Optimized for sub-millisecond performance
 //!
@@ -33,6 +33,6 @@ pub mod types;
 pub mod volatility;
 pub mod wasm;
 
-pub use types::{Greeks, OptionData, OptionType};
-pub use pricing::{BlackScholesParams, calculate_greeks};
-pub use volatility::{SVIParams, VolatilitySurface};
+pub use types::{ExerciseStyle, Greeks, HigherOrderGreeks, OptionData, OptionType};
+pub use pricing::{AmericanApproximation, BachelierParams, BinomialMethod, Black76Params, BlackScholesParams, DeltaConvention, GarmanKohlhagenParams, HestonParams, ImpliedVolMethod, PricingError, calculate_greeks, calculate_higher_order_greeks, implied_vol, implied_vol_with_method, try_calculate_greeks};
+pub use volatility::{SABRParams, SVIParams, VolatilitySurface};
@@ -0,0 +1,487 @@
+//! Closed-form approximations for American options
+//!
+//! Barone-Adesi and Whaley (1987) add a quadratic early-exercise premium to the
+//! European price, with the exercise boundary found by Newton's method.
+//! Bjerksund and Stensland (2002) value the option under a two-step flat
+//! exercise boundary, which needs the bivariate normal distribution.
+//!
+//! Against a fine binomial tree Bjerksund-Stensland stays below the true price
+//! and within about 0.1 on a 100 strike out to three years; Barone-Adesi-Whaley
+//! is as good for short maturities but drifts upwards for long-dated options.
+//!
+//! Both are generic over `Scalar` with the same signature as
+//! `black_scholes::call_price`, so AD Greeks flow through them unchanged.
+
+use crate::ad::ops::bivariate_norm_cdf;
+use crate::ad::{norm_cdf, norm_pdf, Scalar};
+use crate::pricing::black_scholes::{self, expired_greeks, BlackScholesParams};
+use crate::pricing::closed_form::{self, ClosedForm, Inputs};
+use crate::pricing::PricingError;
+use crate::types::{Greeks, OptionType};
+
+/// American approximation to evaluate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AmericanApproximation {
+    /// Barone-Adesi and Whaley (1987)
+    BaroneAdesiWhaley,
+    /// Bjerksund and Stensland (2002)
+    #[default]
+    BjerksundStensland,
+}
+
+/// Price an American call with the Barone-Adesi-Whaley approximation
+///
+/// Without a dividend yield the call is never exercised early and this is the
+/// European price. The critical spot is solved for in `f64` and then refined by
+/// two Newton steps on `T`, which carries its first and second derivatives by
+/// the implicit function theorem.
+pub fn baw_call_price<T: Scalar>(s: T, k: f64, t: T, sigma: T, r: T, q: T) -> T {
+    let european = black_scholes::call_price(s, k, t, sigma, r, q);
+    if q.value() <= 0.0 || degenerate(t, sigma) {
+        return european.max(s - k);
+    }
+
+    let baw = Baw::new(k, t, sigma, r, q, OptionType::Call);
+    let critical = baw.critical_spot();
+    if s.value() >= critical.value() {
+        return s - k;
+    }
+    let premium = critical / baw.exponent * (T::constant(1.0) - baw.dividend_discount * norm_cdf(baw.d1(critical)));
+    european + premium * (baw.exponent * (s / critical).ln()).exp()
+}
+
+/// Price an American put with the Barone-Adesi-Whaley approximation
+///
+/// With a non-positive interest rate the put is never exercised early and this
+/// is the European price.
+pub fn baw_put_price<T: Scalar>(s: T, k: f64, t: T, sigma: T, r: T, q: T) -> T {
+    let european = black_scholes::put_price(s, k, t, sigma, r, q);
+    if r.value() <= 0.0 || degenerate(t, sigma) {
+        return european.max(-(s - k));
+    }
+
+    let baw = Baw::new(k, t, sigma, r, q, OptionType::Put);
+    let critical = baw.critical_spot();
+    if s.value() <= critical.value() {
+        return -(s - k);
+    }
+    let premium = -critical / baw.exponent * (T::constant(1.0) - baw.dividend_discount * norm_cdf(-baw.d1(critical)));
+    european + premium * (baw.exponent * (s / critical).ln()).exp()
+}
+
+/// Price an American call with the Bjerksund-Stensland (2002) approximation
+///
+/// The price is homogeneous of degree one in spot and strike, so it is
+/// evaluated at unit strike and scaled.
+pub fn bjerksund_stensland_call_price<T: Scalar>(s: T, k: f64, t: T, sigma: T, r: T, q: T) -> T {
+    if degenerate(t, sigma) {
+        return black_scholes::call_price(s, k, t, sigma, r, q).max(s - k);
+    }
+    bjerksund_stensland_unit_call(s / k, t, sigma, r, q) * k
+}
+
+/// Price an American put with the Bjerksund-Stensland (2002) approximation
+///
+/// Uses the put-call transformation P(S, K, r, q) = C(K, S, q, r).
+pub fn bjerksund_stensland_put_price<T: Scalar>(s: T, k: f64, t: T, sigma: T, r: T, q: T) -> T {
+    if degenerate(t, sigma) {
+        return black_scholes::put_price(s, k, t, sigma, r, q).max(-(s - k));
+    }
+    bjerksund_stensland_unit_call(T::constant(k) / s, t, sigma, q, r) * s
+}
+
+/// No time value left to approximate; the option is worth the larger of its
+/// European price and immediate exercise
+#[inline]
+fn degenerate<T: Scalar>(t: T, sigma: T) -> bool {
+    t.value() == 0.0 || sigma.value() == 0.0
+}
+
+/// Barone-Adesi-Whaley quadratic approximation for one option type
+struct Baw<T> {
+    k: f64,
+    t: T,
+    sigma: T,
+    r: T,
+    q: T,
+    option_type: OptionType,
+    dividend_discount: T,
+    /// q2 for calls, q1 for puts
+    exponent: T,
+}
+
+impl<T: Scalar> Baw<T> {
+    fn new(k: f64, t: T, sigma: T, r: T, q: T, option_type: OptionType) -> Self {
+        let variance = sigma.powi2();
+        let n = (r - q) * 2.0 / variance;
+        // M / K(T) = 2r / (σ² (1 - e^(-rT))), which tends to 2 / (σ² T) as r → 0
+        let m_over_k = if r.value() == 0.0 {
+            T::constant(2.0) / (variance * t)
+        } else {
+            r * 2.0 / (variance * (T::constant(1.0) - (-r * t).exp()))
+        };
+        let root = ((n - 1.0).powi2() + m_over_k * 4.0).sqrt();
+        let exponent = match option_type {
+            OptionType::Call => (-(n - 1.0) + root) * 0.5,
+            OptionType::Put => (-(n - 1.0) - root) * 0.5,
+        };
+        Self {
+            k,
+            t,
+            sigma,
+            r,
+            q,
+            option_type,
+            dividend_discount: (-q * t).exp(),
+            exponent,
+        }
+    }
+
+    fn d1(&self, s: T) -> T {
+        ((s / self.k).ln() + (self.r - self.q + self.sigma.powi2() * 0.5) * self.t) / (self.sigma * self.t.sqrt())
+    }
+
+    /// Mismatch between exercise value and continuation value at spot `s`, with its slope
+    fn residual(&self, s: T) -> (T, T) {
+        let d1 = self.d1(s);
+        let density = self.dividend_discount * norm_pdf(d1) / (self.sigma * self.t.sqrt());
+        let one = T::constant(1.0);
+        match self.option_type {
+            OptionType::Call => {
+                let delta = self.dividend_discount * norm_cdf(d1);
+                let value = black_scholes::call_price(s, self.k, self.t, self.sigma, self.r, self.q)
+                    + (one - delta) * s / self.exponent
+                    - (s - self.k);
+                let slope = delta * (one - one / self.exponent) + (one - density) / self.exponent - 1.0;
+                (value, slope)
+            }
+            OptionType::Put => {
+                let delta = self.dividend_discount * norm_cdf(-d1);
+                let value = black_scholes::put_price(s, self.k, self.t, self.sigma, self.r, self.q)
+                    - (one - delta) * s / self.exponent
+                    + (s - self.k);
+                let slope = -delta * (one - one / self.exponent) - (one + density) / self.exponent + 1.0;
+                (value, slope)
+            }
+        }
+    }
+
+    /// Spot at which early exercise becomes optimal
+    fn critical_spot(&self) -> T {
+        let values = Baw {
+            t: self.t.value(),
+            sigma: self.sigma.value(),
+            r: self.r.value(),
+            q: self.q.value(),
+            dividend_discount: self.dividend_discount.value(),
+            exponent: self.exponent.value(),
+            k: self.k,
+            option_type: self.option_type,
+        };
+        let mut s = values.seed();
+        for _ in 0..MAX_NEWTON_ITERATIONS {
+            let (value, slope) = values.residual(s);
+            let mut next = s - value / slope;
+            if next <= 0.0 || next.is_nan() {
+                next = 0.5 * s;
+            }
+            let converged = (next - s).abs() <= NEWTON_TOLERANCE * s;
+            s = next;
+            if converged {
+                break;
+            }
+        }
+
+        // Newton steps from the root carry the derivatives; two are exact to second order
+        let mut critical = T::constant(s);
+        for _ in 0..2 {
+            let (value, slope) = self.residual(critical);
+            critical = critical - value / slope;
+        }
+        critical
+    }
+}
+
+impl Baw<f64> {
+    /// Starting point from Barone-Adesi and Whaley's interpolation between the
+    /// strike and the perpetual boundary
+    fn seed(&self) -> f64 {
+        let variance = self.sigma * self.sigma;
+        let carry = self.r - self.q;
+        let n = 2.0 * carry / variance;
+        let m = 2.0 * self.r / variance;
+        let root = ((n - 1.0).powi(2) + 4.0 * m).sqrt();
+        let total_vol = self.sigma * self.t.sqrt();
+        match self.option_type {
+            OptionType::Call => {
+                let perpetual = self.k / (1.0 - 2.0 / (-(n - 1.0) + root));
+                let h = -(carry * self.t + 2.0 * total_vol) * self.k / (perpetual - self.k);
+                self.k + (perpetual - self.k) * (1.0 - h.exp())
+            }
+            OptionType::Put => {
+                let perpetual = self.k / (1.0 - 2.0 / (-(n - 1.0) - root));
+                let h = (carry * self.t - 2.0 * total_vol) * self.k / (self.k - perpetual);
+                perpetual + (self.k - perpetual) * h.exp()
+            }
+        }
+    }
+}
+
+const MAX_NEWTON_ITERATIONS: usize = 100;
+const NEWTON_TOLERANCE: f64 = 1e-13;
+
+/// Bjerksund-Stensland (2002) call at unit strike, spot `x`
+fn bjerksund_stensland_unit_call<T: Scalar>(x: T, t: T, sigma: T, r: T, q: T) -> T {
+    if q.value() <= 0.0 {
+        return black_scholes::call_price(x, 1.0, t, sigma, r, q);
+    }
+
+    let one = T::constant(1.0);
+    let variance = sigma.powi2();
+    let carry = r - q;
+    let beta = (-(carry / variance) + 0.5) + ((carry / variance - 0.5).powi2() + r * 2.0 / variance).sqrt();
+    let b_infinity = beta / (beta - 1.0);
+    let b_zero = (r / q).max(one);
+
+    // Flat exercise boundaries I1 on [0, t1] and I2 on [t1, T]
+    let t1 = t * (0.5 * (5f64.sqrt() - 1.0));
+    let boundary = |tau: T| {
+        let h = -(carry * tau + sigma * tau.sqrt() * 2.0) / ((b_infinity - b_zero) * b_zero);
+        b_zero + (b_infinity - b_zero) * (one - h.exp())
+    };
+    let i1 = boundary(t1);
+    let i2 = boundary(t);
+    if x.value() >= i2.value() {
+        return x - 1.0;
+    }
+    let alpha1 = (i1 - 1.0) * (-beta * i1.ln()).exp();
+    let alpha2 = (i2 - 1.0) * (-beta * i2.ln()).exp();
+
+    let model = TwoStepBoundary { x, t, t1, sigma, r, carry, i1, i2 };
+    let (zero, unit) = (T::constant(0.0), one);
+    alpha2 * (beta * x.ln()).exp() - alpha2 * model.phi(t1, beta, i2, i2) + model.phi(t1, unit, i2, i2)
+        - model.phi(t1, unit, i1, i2)
+        - model.phi(t1, zero, i2, i2)
+        + model.phi(t1, zero, i1, i2)
+        + alpha1 * model.phi(t1, beta, i1, i2)
+        - alpha1 * model.psi(beta, i1)
+        + model.psi(unit, i1)
+        - model.psi(unit, one)
+        - model.psi(zero, i1)
+        + model.psi(zero, one)
+}
+
+/// Inputs shared by the φ and ψ building blocks of Bjerksund-Stensland (2002)
+struct TwoStepBoundary<T> {
+    x: T,
+    t: T,
+    t1: T,
+    sigma: T,
+    r: T,
+    carry: T,
+    i1: T,
+    i2: T,
+}
+
+impl<T: Scalar> TwoStepBoundary<T> {
+    fn lambda(&self, gamma: T) -> T {
+        -self.r + gamma * self.carry + gamma * (gamma - 1.0) * self.sigma.powi2() * 0.5
+    }
+
+    fn kappa(&self, gamma: T) -> T {
+        self.carry * 2.0 / self.sigma.powi2() + gamma * 2.0 - 1.0
+    }
+
+    /// Drift term (b + (γ - ½)σ²) τ
+    fn drift(&self, gamma: T, tau: T) -> T {
+        (self.carry + (gamma - 0.5) * self.sigma.powi2()) * tau
+    }
+
+    /// φ(S, τ, γ, H, I): value of S^γ paid at τ if the spot stays below I and ends above H
+    fn phi(&self, tau: T, gamma: T, h: T, i: T) -> T {
+        let total_vol = self.sigma * tau.sqrt();
+        let d = -((self.x / h).ln() + self.drift(gamma, tau)) / total_vol;
+        let log_barrier = (i / self.x).ln();
+        (self.lambda(gamma) * tau + gamma * self.x.ln()).exp()
+            * (norm_cdf(d) - (self.kappa(gamma) * log_barrier).exp() * norm_cdf(d - log_barrier * 2.0 / total_vol))
+    }
+
+    /// ψ(S, T, γ, H, I2, I1, t1): the two-period analogue of φ
+    fn psi(&self, gamma: T, h: T) -> T {
+        let (x, i1, i2) = (self.x, self.i1, self.i2);
+        let vol_t1 = self.sigma * self.t1.sqrt();
+        let vol_t = self.sigma * self.t.sqrt();
+        let drift_t1 = self.drift(gamma, self.t1);
+        let drift_t = self.drift(gamma, self.t);
+
+        let e1 = ((x / i1).ln() + drift_t1) / vol_t1;
+        let e2 = ((i2 * i2 / (x * i1)).ln() + drift_t1) / vol_t1;
+        let e3 = ((x / i1).ln() - drift_t1) / vol_t1;
+        let e4 = ((i2 * i2 / (x * i1)).ln() - drift_t1) / vol_t1;
+        let f1 = ((x / h).ln() + drift_t) / vol_t;
+        let f2 = ((i2 * i2 / (x * h)).ln() + drift_t) / vol_t;
+        let f3 = ((i1 * i1 / (x * h)).ln() + drift_t) / vol_t;
+        let f4 = ((x * i1 * i1 / (h * i2 * i2)).ln() + drift_t) / vol_t;
+
+        // t1 is a fixed fraction of T, so the correlation is a constant
+        let rho = T::constant((0.5 * (5f64.sqrt() - 1.0)).sqrt());
+        let kappa = self.kappa(gamma);
+        let power = |ratio: T| (kappa * ratio.ln()).exp();
+        (self.lambda(gamma) * self.t + gamma * x.ln()).exp()
+            * (bivariate_norm_cdf(-e1, -f1, rho) - power(i2 / x) * bivariate_norm_cdf(-e2, -f2, rho)
+                - power(i1 / x) * bivariate_norm_cdf(-e3, -f3, -rho)
+                + power(i1 / i2) * bivariate_norm_cdf(-e4, -f4, -rho))
+    }
+}
+
+/// Calculate the approximate American price and Greeks using automatic differentiation
+///
+/// As for `black_scholes::calculate_greeks`: one `MultiDual` pass gives price,
+/// delta, vega, theta, rho and phi, and a `HyperDual` pass gives gamma. Beyond
+/// the exercise boundary the option is worth its intrinsic value and the Greeks
+/// are those of the payoff.
+pub fn calculate_greeks(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    method: AmericanApproximation,
+) -> Greeks {
+    closed_form::greeks(params, &(option_type, method))
+}
+
+/// Calculate the approximate American price and Greeks after validating the inputs
+pub fn try_calculate_greeks(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    method: AmericanApproximation,
+) -> Result<Greeks, PricingError> {
+    params.validate()?;
+    Ok(calculate_greeks(params, option_type, method))
+}
+
+impl ClosedForm for (OptionType, AmericanApproximation) {
+    fn price<T: Scalar>(&self, inputs: &Inputs<T>) -> T {
+        let &Inputs { s, k, t, sigma, r, q, .. } = inputs;
+        match (self.1, self.0) {
+            (AmericanApproximation::BaroneAdesiWhaley, OptionType::Call) => baw_call_price(s, k, t, sigma, r, q),
+            (AmericanApproximation::BaroneAdesiWhaley, OptionType::Put) => baw_put_price(s, k, t, sigma, r, q),
+            (AmericanApproximation::BjerksundStensland, OptionType::Call) => {
+                bjerksund_stensland_call_price(s, k, t, sigma, r, q)
+            }
+            (AmericanApproximation::BjerksundStensland, OptionType::Put) => {
+                bjerksund_stensland_put_price(s, k, t, sigma, r, q)
+            }
+        }
+    }
+
+    fn at_expiry(&self, params: &BlackScholesParams) -> Greeks {
+        expired_greeks(params, self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::binomial::{self, BinomialMethod};
+    use crate::types::ExerciseStyle;
+    use approx::assert_relative_eq;
+
+    const METHODS: [AmericanApproximation; 2] =
+        [AmericanApproximation::BaroneAdesiWhaley, AmericanApproximation::BjerksundStensland];
+
+    fn tree_price(params: &BlackScholesParams, option_type: OptionType) -> f64 {
+        binomial::price(params, option_type, ExerciseStyle::American, BinomialMethod::LeisenReimer, 1001)
+    }
+
+    #[test]
+    fn test_against_binomial_reference() {
+        for &spot in &[80.0, 95.0, 100.0, 110.0] {
+            // BAW drifts with maturity, by up to about 0.35 on a 100 strike at three years
+            for &(t, vol, baw_tolerance) in &[(0.25, 0.2, 0.05), (1.0, 0.3, 0.12), (3.0, 0.25, 0.4)] {
+                for &(r, q) in &[(0.05, 0.0), (0.08, 0.04), (0.03, 0.07)] {
+                    let params = BlackScholesParams::new(spot, 100.0, t, vol, r, q);
+                    for &option_type in &[OptionType::Call, OptionType::Put] {
+                        let reference = tree_price(&params, option_type);
+
+                        let price = |method| calculate_greeks(&params, option_type, method).price;
+
+                        let baw = price(AmericanApproximation::BaroneAdesiWhaley);
+                        assert!(
+                            (baw - reference).abs() < baw_tolerance,
+                            "BAW {:?} {:?}: {} vs {}",
+                            option_type,
+                            params,
+                            baw,
+                            reference
+                        );
+
+                        // The flat boundary is suboptimal, so Bjerksund-Stensland is a lower bound
+                        let bs = price(AmericanApproximation::BjerksundStensland);
+                        assert!(
+                            bs < reference + 2e-3 && bs > reference - 0.1,
+                            "BS2002 {:?} {:?}: {} vs {}",
+                            option_type,
+                            params,
+                            bs,
+                            reference
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reduces_to_european_without_early_exercise() {
+        let params = BlackScholesParams::new(100.0, 100.0, 1.0, 0.2, 0.05, 0.0);
+        let european = black_scholes::calculate_greeks(&params, OptionType::Call);
+        for &method in &METHODS {
+            let american = calculate_greeks(&params, OptionType::Call, method);
+            assert_relative_eq!(american.price, european.price, epsilon = 1e-13);
+            assert_relative_eq!(american.delta, european.delta, epsilon = 1e-13);
+            assert_relative_eq!(american.vega, european.vega, epsilon = 1e-12);
+        }
+
+        let params = BlackScholesParams { risk_free_rate: 0.0, dividend_yield: 0.02, ..params };
+        let european = black_scholes::calculate_greeks(&params, OptionType::Put).price;
+        let american = calculate_greeks(&params, OptionType::Put, AmericanApproximation::BaroneAdesiWhaley).price;
+        assert_relative_eq!(american, european, epsilon = 1e-13);
+    }
+
+    #[test]
+    fn test_deep_in_the_money_is_intrinsic() {
+        let params = BlackScholesParams::new(40.0, 100.0, 1.0, 0.2, 0.08, 0.0);
+        for &method in &METHODS {
+            let greeks = calculate_greeks(&params, OptionType::Put, method);
+            assert_relative_eq!(greeks.price, 60.0, epsilon = 1e-12);
+            assert_relative_eq!(greeks.delta, -1.0, epsilon = 1e-12);
+            assert_eq!(greeks.gamma, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_ad_greeks_match_bumped_prices() {
+        let params = BlackScholesParams::new(95.0, 100.0, 0.75, 0.3, 0.06, 0.03);
+        let h = 1e-5;
+        for &method in &METHODS {
+            for &option_type in &[OptionType::Call, OptionType::Put] {
+                let greeks = calculate_greeks(&params, option_type, method);
+                let price = |p: BlackScholesParams| (option_type, method).price(&Inputs::<f64>::constant(&p));
+                let central = |up: BlackScholesParams, down: BlackScholesParams| (price(up) - price(down)) / (2.0 * h);
+
+                let spot = |s| BlackScholesParams { spot: s, ..params };
+                assert_relative_eq!(greeks.delta, central(spot(95.0 + h), spot(95.0 - h)), epsilon = 1e-7);
+                let gamma = (price(spot(95.0 + 1e-3)) - 2.0 * greeks.price + price(spot(95.0 - 1e-3))) / 1e-6;
+                assert_relative_eq!(greeks.gamma, gamma, epsilon = 1e-5);
+
+                let vol = |v| BlackScholesParams { volatility: v, ..params };
+                assert_relative_eq!(greeks.vega, central(vol(0.3 + h), vol(0.3 - h)), epsilon = 1e-6);
+                let time = |t| BlackScholesParams { time_to_maturity: t, ..params };
+                assert_relative_eq!(greeks.theta, -central(time(0.75 + h), time(0.75 - h)), epsilon = 1e-6);
+                let rate = |r| BlackScholesParams { risk_free_rate: r, ..params };
+                assert_relative_eq!(greeks.rho, central(rate(0.06 + h), rate(0.06 - h)), epsilon = 1e-6);
+                let dividend = |q| BlackScholesParams { dividend_yield: q, ..params };
+                assert_relative_eq!(greeks.phi, central(dividend(0.03 + h), dividend(0.03 - h)), epsilon = 1e-6);
+            }
+        }
+    }
+}
@@ -0,0 +1,465 @@
+//! Average-price (Asian) options under Black-Scholes
+//!
+//! The average is taken over a window that ends at maturity, either
+//! continuously or over equally spaced fixings. Once the window has opened,
+//! the fixings already observed enter through their running average and only
+//! the remainder is random.
+//!
+//! A geometric average is lognormal, so its options have exact closed forms
+//! (Kemna and Vorst, 1990, for continuous averaging). An arithmetic average is
+//! approximated by a lognormal with the same first two moments: Turnbull and
+//! Wakeman (1991) match the moments of the average as monitored, Levy (1992)
+//! those of a continuous average whatever the fixings.
+//!
+//! Every formula is generic over `Scalar`, so Greeks come from AD. Theta keeps
+//! the fixing dates and the running average fixed as calendar time passes.
+
+use crate::ad::ops::{relative_expm1, GAUSS_LEGENDRE_20};
+use crate::ad::{norm_cdf, Scalar};
+use crate::pricing::black_scholes::BlackScholesParams;
+use crate::pricing::closed_form::{self, ClosedForm, Inputs};
+use crate::pricing::PricingError;
+use crate::types::{Greeks, OptionType};
+
+/// Kind of average and how its option is valued
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsianMethod {
+    /// Geometric average, priced exactly
+    Geometric,
+    /// Arithmetic average, matching the moments of the average as monitored
+    TurnbullWakeman,
+    /// Arithmetic average, matching the moments of a continuous average
+    Levy,
+}
+
+/// How the average is sampled over its window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Averaging {
+    #[default]
+    Continuous,
+    /// Number of equally spaced fixings, the last at maturity
+    Discrete(usize),
+}
+
+/// Averaging terms of an Asian option
+#[derive(Debug, Clone, Copy)]
+pub struct AsianAverage {
+    pub method: AsianMethod,
+    pub averaging: Averaging,
+    /// Length of the averaging window ending at maturity, in years; longer than
+    /// the time to maturity once averaging has begun
+    pub window: f64,
+    /// Average of the fixings observed so far, of the same kind as the method;
+    /// ignored before the window opens
+    pub running_average: f64,
+}
+
+impl AsianAverage {
+    /// Continuously averaged over `window`
+    pub fn new(method: AsianMethod, window: f64, running_average: f64) -> Self {
+        Self {
+            method,
+            averaging: Averaging::Continuous,
+            window,
+            running_average,
+        }
+    }
+
+    /// Check the window, fixings and running average
+    pub fn validate(&self) -> Result<(), PricingError> {
+        if !self.window.is_finite() {
+            return Err(PricingError::NonFinite("window"));
+        }
+        if !self.running_average.is_finite() {
+            return Err(PricingError::NonFinite("running_average"));
+        }
+        if self.window <= 0.0 {
+            return Err(PricingError::InvalidParameter { name: "window", value: self.window });
+        }
+        if self.averaging == Averaging::Discrete(0) {
+            return Err(PricingError::InvalidParameter { name: "fixings", value: 0.0 });
+        }
+        if self.running_average < 0.0 {
+            return Err(PricingError::InvalidParameter { name: "running_average", value: self.running_average });
+        }
+        Ok(())
+    }
+}
+
+/// Price an Asian option
+///
+/// Generic over `Scalar`, so seeding any input on an AD number type yields its
+/// sensitivity. At maturity the option pays off on the running average.
+#[allow(clippy::too_many_arguments)]
+pub fn price<T: Scalar>(s: T, k: f64, t: T, sigma: T, r: T, q: T, option_type: OptionType, average: &AsianAverage) -> T {
+    (average, option_type).price(&Inputs::new(s, k, t, sigma, r, q))
+}
+
+/// Calculate price and Greeks
+///
+/// First-order Greeks come from one `MultiDual` pass and gamma from a
+/// `HyperDual` pass. An expired option is worth its payoff on the running
+/// average, with zero Greeks.
+pub fn calculate_greeks(params: &BlackScholesParams, option_type: OptionType, average: &AsianAverage) -> Greeks {
+    closed_form::greeks(params, &(average, option_type))
+}
+
+/// Calculate price and Greeks after validating the inputs
+///
+/// A geometric average that has begun needs a positive running average.
+pub fn try_calculate_greeks(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    average: &AsianAverage,
+) -> Result<Greeks, PricingError> {
+    params.validate()?;
+    average.validate()?;
+    let started = average.window > params.time_to_maturity;
+    if average.method == AsianMethod::Geometric && started && average.running_average <= 0.0 {
+        return Err(PricingError::InvalidParameter { name: "running_average", value: average.running_average });
+    }
+    Ok(calculate_greeks(params, option_type, average))
+}
+
+/// Part of the averaging window still to come, as times from now
+enum Remaining<T> {
+    Continuous { start: T, length: T },
+    /// Times of the fixings still to come, in increasing order, out of `fixings` in all
+    Discrete { times: Vec<T>, fixings: usize },
+}
+
+/// Lognormal fitted to the payoff-relevant part of the average, and the strike it must beat
+struct Lognormal<T> {
+    forward: T,
+    variance: T,
+    strike: T,
+}
+
+impl<T: Scalar> Lognormal<T> {
+    fn price(&self, discount: T, option_type: OptionType) -> T {
+        let (f, k, v) = (self.forward, self.strike, self.variance);
+        // Fixings already beyond the strike, or nothing random left
+        if k.value() <= 0.0 || v.value() <= 0.0 {
+            let intrinsic = match option_type {
+                OptionType::Call => f - k,
+                OptionType::Put => k - f,
+            };
+            return intrinsic.max(T::constant(0.0)) * discount;
+        }
+        let std_dev = v.sqrt();
+        let d1 = (f / k).ln() / std_dev + std_dev * 0.5;
+        let d2 = d1 - std_dev;
+        match option_type {
+            OptionType::Call => (f * norm_cdf(d1) - k * norm_cdf(d2)) * discount,
+            OptionType::Put => (k * norm_cdf(-d2) - f * norm_cdf(-d1)) * discount,
+        }
+    }
+}
+
+impl ClosedForm for (&AsianAverage, OptionType) {
+    fn price<T: Scalar>(&self, inputs: &Inputs<T>) -> T {
+        let &(average, option_type) = self;
+        if inputs.t.value() <= 0.0 {
+            let intrinsic = match option_type {
+                OptionType::Call => average.running_average - inputs.k,
+                OptionType::Put => inputs.k - average.running_average,
+            };
+            return T::constant(intrinsic.max(0.0));
+        }
+        let fit = match average.method {
+            AsianMethod::Geometric => inputs.geometric(average),
+            AsianMethod::TurnbullWakeman => inputs.arithmetic(average, average.averaging),
+            AsianMethod::Levy => inputs.arithmetic(average, Averaging::Continuous),
+        };
+        fit.price((-inputs.r * inputs.t).exp(), option_type)
+    }
+}
+
+impl<T: Scalar> Inputs<T> {
+    /// Weight of the fixings already observed, and the schedule of the rest
+    fn remaining(&self, averaging: Averaging, window: f64) -> (T, Remaining<T>) {
+        match averaging {
+            Averaging::Continuous if self.t.value() >= window => (
+                T::constant(0.0),
+                Remaining::Continuous { start: self.t - window, length: T::constant(window) },
+            ),
+            Averaging::Continuous => (
+                (-self.t + window) / window,
+                Remaining::Continuous { start: T::constant(0.0), length: self.t },
+            ),
+            Averaging::Discrete(fixings) => {
+                let times: Vec<T> = (1..=fixings)
+                    .map(|i| self.t - window + i as f64 * window / fixings as f64)
+                    .filter(|time| time.value() > 0.0)
+                    .collect();
+                let observed = fixings - times.len();
+                (T::constant(observed as f64 / fixings as f64), Remaining::Discrete { times, fixings })
+            }
+        }
+    }
+
+    /// ln G is normal, so the geometric average is exactly lognormal
+    fn geometric(&self, average: &AsianAverage) -> Lognormal<T> {
+        let drift = self.r - self.q - self.sigma.powi2() * 0.5;
+        let (observed, remaining) = self.remaining(average.averaging, average.window);
+        // Mean and variance of the remaining fixings' share of ln G
+        let (mean, variance) = match remaining {
+            Remaining::Continuous { start, length } => {
+                let end = start + length;
+                let mean = (self.s.ln() * length + drift * (end.powi2() - start.powi2()) * 0.5) / average.window;
+                let covariance = start * length.powi2() + length.powi2() * length / 3.0;
+                (mean, self.sigma.powi2() * covariance / (average.window * average.window))
+            }
+            Remaining::Discrete { times, fixings } => {
+                let n = fixings as f64;
+                let m = times.len();
+                // Σ min(t_i, t_j) over all pairs: t_i is the smaller in 2(m - i) - 1 of them
+                let (time_sum, covariance) = times.iter().enumerate().fold(
+                    (T::constant(0.0), T::constant(0.0)),
+                    |(sum, covariance), (i, &time)| (sum + time, covariance + time * (2 * (m - i) - 1) as f64),
+                );
+                let mean = (self.s.ln() * m as f64 + drift * time_sum) / n;
+                (mean, self.sigma.powi2() * covariance / (n * n))
+            }
+        };
+        let mean = if observed.value() > 0.0 {
+            mean + observed * average.running_average.ln()
+        } else {
+            mean
+        };
+        Lognormal {
+            forward: (mean + variance * 0.5).exp(),
+            variance,
+            strike: T::constant(self.k),
+        }
+    }
+
+    /// Lognormal with the first two moments of the remaining fixings' share of
+    /// the arithmetic average, against the strike net of the observed share
+    fn arithmetic(&self, average: &AsianAverage, averaging: Averaging) -> Lognormal<T> {
+        let carry = self.r - self.q;
+        let variance_rate = self.sigma.powi2();
+        let (observed, remaining) = self.remaining(averaging, average.window);
+        // E[A] / S and E[A²] / S² for the average A of the remaining fixings
+        let (first, second) = match remaining {
+            Remaining::Continuous { start, length } => {
+                // From the start of the remaining window, E[A] / S = φ(bL) and
+                // E[A²] / S² = 2 ∫₀¹ u e^(bLu) φ((b + σ²)Lu) du with φ(x) = (eˣ - 1) / x,
+                // integrated by quadrature since the closed form cancels badly as b + σ² → 0
+                let scaled_carry = carry * length;
+                let scaled_cross = (carry + variance_rate) * length;
+                let integral = GAUSS_LEGENDRE_20.iter().fold(T::constant(0.0), |sum, &(x, w)| {
+                    [0.5 * (1.0 - x), 0.5 * (1.0 + x)].iter().fold(sum, |sum, &u| {
+                        sum + (scaled_carry * u).exp() * relative_expm1(scaled_cross * u) * (u * w)
+                    })
+                });
+                let growth = (carry * start).exp();
+                (growth * relative_expm1(scaled_carry), growth.powi2() * (variance_rate * start).exp() * integral)
+            }
+            Remaining::Discrete { times, .. } => {
+                let m = times.len() as f64;
+                // E[S_i S_j] = S² e^(b(t_i + t_j) + σ² min(t_i, t_j)), summed with later fixings as a suffix
+                let (later, second) = times.iter().rev().fold(
+                    (T::constant(0.0), T::constant(0.0)),
+                    |(later, second), &time| {
+                        let growth = (carry * time).exp();
+                        (later + growth, second + ((carry + variance_rate) * time).exp() * (growth + later * 2.0))
+                    },
+                );
+                (later / m, second / (m * m))
+            }
+        };
+        Lognormal {
+            forward: self.s * first * (-observed + 1.0),
+            variance: (second / first.powi2()).ln(),
+            strike: -(observed * average.running_average) + self.k,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::monte_carlo::{self, MonteCarloSettings, PathPayoff};
+    use approx::assert_relative_eq;
+
+    /// Average of the simulated path, blended with fixings already observed
+    struct AveragePayoff {
+        strike: f64,
+        option_type: OptionType,
+        geometric: bool,
+        observed: f64,
+        running_average: f64,
+    }
+
+    impl PathPayoff for AveragePayoff {
+        fn payoff<T: Scalar>(&self, path: &[T]) -> T {
+            let n = path.len() as f64;
+            let average = if self.geometric {
+                let mean_log = path.iter().fold(T::constant(0.0), |acc, &s| acc + s.ln()) / n;
+                let observed = self.observed * self.running_average.max(f64::MIN_POSITIVE).ln();
+                (mean_log * (1.0 - self.observed) + observed).exp()
+            } else {
+                let mean = path.iter().fold(T::constant(0.0), |acc, &s| acc + s) / n;
+                mean * (1.0 - self.observed) + self.observed * self.running_average
+            };
+            let intrinsic = match self.option_type {
+                OptionType::Call => average - self.strike,
+                OptionType::Put => -(average - self.strike),
+            };
+            intrinsic.max(T::constant(0.0))
+        }
+
+        fn control_type(&self) -> OptionType {
+            self.option_type
+        }
+    }
+
+    #[test]
+    fn test_reference_prices() {
+        // Haug, The Complete Guide to Option Pricing Formulas (2007): Kemna-Vorst with b = 0.08
+        let geometric = AsianAverage::new(AsianMethod::Geometric, 0.25, 0.0);
+        assert_relative_eq!(price(80.0, 85.0, 0.25, 0.2, 0.05, -0.03, OptionType::Put, &geometric), 4.692221312245336, epsilon = 1e-13);
+        // Levy with b = -0.02
+        let levy = AsianAverage::new(AsianMethod::Levy, 0.5, 6.8);
+        assert_relative_eq!(price(6.8, 6.9, 0.5, 0.14, 0.07, 0.09, OptionType::Call, &levy), 0.09441578028993586, epsilon = 1e-13);
+        // Halfway through a one-year window
+        let levy = AsianAverage::new(AsianMethod::Levy, 1.0, 95.0);
+        assert_relative_eq!(price(100.0, 100.0, 0.5, 0.25, 0.05, 0.02, OptionType::Call, &levy), 1.177045420874205, max_relative = 1e-12);
+
+        // The two moment fits coincide for continuous averaging, forward-starting windows included
+        for &(t, window, carry) in &[(1.0, 1.0, 0.03), (0.4, 1.0, -0.04), (1.5, 0.5, 0.0), (1.0, 1.0, -0.0625)] {
+            for &option_type in &[OptionType::Call, OptionType::Put] {
+                let value = |method| price(100.0, 98.0, t, 0.25, 0.05, 0.05 - carry, option_type, &AsianAverage::new(method, window, 102.0));
+                assert_relative_eq!(value(AsianMethod::TurnbullWakeman), value(AsianMethod::Levy), epsilon = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_discrete_averages_against_monte_carlo() {
+        let params = BlackScholesParams::new(100.0, 100.0, 1.0, 0.25, 0.05, 0.02);
+        let settings = MonteCarloSettings { paths: 100_000, time_steps: 12, ..MonteCarloSettings::default() };
+        for &option_type in &[OptionType::Call, OptionType::Put] {
+            // The discrete geometric average is exact, Greeks included
+            let average = AsianAverage { averaging: Averaging::Discrete(12), ..AsianAverage::new(AsianMethod::Geometric, 1.0, 0.0) };
+            let payoff = AveragePayoff { strike: 100.0, option_type, geometric: true, observed: 0.0, running_average: 0.0 };
+            let estimate = monte_carlo::calculate_greeks(&params, &payoff, &settings);
+            let greeks = calculate_greeks(&params, option_type, &average);
+            assert!((greeks.price - estimate.greeks.price).abs() < 4.0 * estimate.standard_error.price);
+            assert!((greeks.delta - estimate.greeks.delta).abs() < 4.0 * estimate.standard_error.delta);
+            assert!((greeks.vega - estimate.greeks.vega).abs() < 4.0 * estimate.standard_error.vega);
+
+            // Turnbull-Wakeman is close on the fixings it was matched to; Levy's continuous average is further off
+            let payoff = AveragePayoff { geometric: false, ..payoff };
+            let estimate = monte_carlo::calculate_greeks(&params, &payoff, &settings);
+            let average = AsianAverage { method: AsianMethod::TurnbullWakeman, ..average };
+            let turnbull_wakeman = calculate_greeks(&params, option_type, &average);
+            let levy = calculate_greeks(&params, option_type, &AsianAverage { method: AsianMethod::Levy, ..average });
+            let error = (turnbull_wakeman.price - estimate.greeks.price).abs();
+            assert!(error < 4.0 * estimate.standard_error.price + 0.01, "{} vs {}", turnbull_wakeman.price, estimate.greeks.price);
+            assert!(error < (levy.price - estimate.greeks.price).abs());
+            assert!((turnbull_wakeman.delta - estimate.greeks.delta).abs() < 4.0 * estimate.standard_error.delta + 0.01);
+        }
+    }
+
+    #[test]
+    fn test_partially_elapsed_window() {
+        // Six of twelve monthly fixings observed, six left over the remaining half year
+        let params = BlackScholesParams::new(100.0, 100.0, 0.5, 0.3, 0.04, 0.0);
+        let settings = MonteCarloSettings { paths: 100_000, time_steps: 6, ..MonteCarloSettings::default() };
+        for &(method, running_average) in &[(AsianMethod::Geometric, 96.0), (AsianMethod::TurnbullWakeman, 97.0)] {
+            for &option_type in &[OptionType::Call, OptionType::Put] {
+                let average = AsianAverage { averaging: Averaging::Discrete(12), ..AsianAverage::new(method, 1.0, running_average) };
+                let payoff = AveragePayoff {
+                    strike: 100.0,
+                    option_type,
+                    geometric: method == AsianMethod::Geometric,
+                    observed: 0.5,
+                    running_average,
+                };
+                let estimate = monte_carlo::calculate_greeks(&params, &payoff, &settings);
+                let value = calculate_greeks(&params, option_type, &average).price;
+                assert!((value - estimate.greeks.price).abs() < 4.0 * estimate.standard_error.price + 0.01, "{} vs {}", value, estimate.greeks.price);
+            }
+        }
+
+        // Fixings already above the strike leave only the forward of the average
+        let average = AsianAverage { averaging: Averaging::Discrete(12), ..AsianAverage::new(AsianMethod::TurnbullWakeman, 1.0, 250.0) };
+        let call = price(100.0, 100.0, 0.5, 0.3, 0.04, 0.0, OptionType::Call, &average);
+        let forward: f64 = (1..=6).map(|i| 100.0 * (0.04 * i as f64 / 12.0).exp()).sum::<f64>() / 12.0;
+        assert_relative_eq!(call, (-0.02_f64).exp() * (125.0 + forward - 100.0), epsilon = 1e-12);
+        assert_eq!(price(100.0, 100.0, 0.5, 0.3, 0.04, 0.0, OptionType::Put, &average), 0.0);
+
+        // Dense fixings converge to the continuous average
+        for method in [AsianMethod::Geometric, AsianMethod::TurnbullWakeman] {
+            let continuous = AsianAverage::new(method, 1.0, 97.0);
+            let dense = AsianAverage { averaging: Averaging::Discrete(20_000), ..continuous };
+            let value = |average: &AsianAverage| price(100.0, 100.0, 0.5, 0.3, 0.04, 0.0, OptionType::Call, average);
+            assert_relative_eq!(value(&dense), value(&continuous), epsilon = 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_greeks_against_bumps() {
+        let cases = [
+            (AsianMethod::Geometric, Averaging::Continuous, 1.0),
+            (AsianMethod::Geometric, Averaging::Discrete(12), 1.25),
+            (AsianMethod::TurnbullWakeman, Averaging::Continuous, 0.5),
+            (AsianMethod::TurnbullWakeman, Averaging::Discrete(52), 1.5),
+            (AsianMethod::Levy, Averaging::Continuous, 1.25),
+        ];
+        let params = BlackScholesParams::new(100.0, 105.0, 0.8, 0.3, 0.05, 0.02);
+        for &(method, averaging, window) in &cases {
+            let average = AsianAverage { averaging, ..AsianAverage::new(method, window, 98.0) };
+            for &option_type in &[OptionType::Call, OptionType::Put] {
+                let greeks = calculate_greeks(&params, option_type, &average);
+                let value = |p: &BlackScholesParams| {
+                    price(p.spot, p.strike, p.time_to_maturity, p.volatility, p.risk_free_rate, p.dividend_yield, option_type, &average)
+                };
+                let bumped = |f: &dyn Fn(&mut BlackScholesParams, f64), h: f64| {
+                    let (mut up, mut down) = (params, params);
+                    f(&mut up, h);
+                    f(&mut down, -h);
+                    (value(&up) - value(&down)) / (2.0 * h)
+                };
+                assert_relative_eq!(greeks.price, value(&params), epsilon = 1e-13);
+                assert_relative_eq!(greeks.delta, bumped(&|p, h| p.spot += h, 1e-4), epsilon = 1e-7);
+                assert_relative_eq!(greeks.vega, bumped(&|p, h| p.volatility += h, 1e-6), epsilon = 1e-6);
+                assert_relative_eq!(greeks.theta, -bumped(&|p, h| p.time_to_maturity += h, 1e-6), epsilon = 1e-6);
+                assert_relative_eq!(greeks.rho, bumped(&|p, h| p.risk_free_rate += h, 1e-6), epsilon = 1e-6);
+                assert_relative_eq!(greeks.phi, bumped(&|p, h| p.dividend_yield += h, 1e-6), epsilon = 1e-6);
+                let h = 1e-3;
+                let (mut up, mut down) = (params, params);
+                up.spot += h;
+                down.spot -= h;
+                assert_relative_eq!(greeks.gamma, (value(&up) - 2.0 * greeks.price + value(&down)) / (h * h), epsilon = 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_expiry_and_validation() {
+        let expired = BlackScholesParams::new(120.0, 100.0, 0.0, 0.2, 0.03, 0.0);
+        let average = AsianAverage::new(AsianMethod::TurnbullWakeman, 1.0, 104.0);
+        let greeks = calculate_greeks(&expired, OptionType::Call, &average);
+        assert_relative_eq!(greeks.price, 4.0);
+        assert_eq!(greeks.delta, 0.0);
+        assert_eq!(calculate_greeks(&expired, OptionType::Put, &average).price, 0.0);
+
+        // Without volatility the geometric average is known in advance
+        let average = AsianAverage::new(AsianMethod::Geometric, 1.0, 0.0);
+        let forward = 100.0 * (0.5 * 0.03_f64).exp();
+        assert_relative_eq!(price(100.0, 95.0, 1.0, 0.0, 0.03, 0.0, OptionType::Call, &average), (-0.03_f64).exp() * (forward - 95.0), epsilon = 1e-12);
+
+        let params = BlackScholesParams::new(100.0, 100.0, 0.5, 0.2, 0.03, 0.0);
+        let invalid = |average: AsianAverage| try_calculate_greeks(&params, OptionType::Call, &average).unwrap_err();
+        let base = AsianAverage::new(AsianMethod::Geometric, 1.0, 100.0);
+        assert_eq!(invalid(AsianAverage { window: 0.0, ..base }), PricingError::InvalidParameter { name: "window", value: 0.0 });
+        assert_eq!(invalid(AsianAverage { averaging: Averaging::Discrete(0), ..base }), PricingError::InvalidParameter { name: "fixings", value: 0.0 });
+        assert_eq!(invalid(AsianAverage { running_average: f64::NAN, ..base }), PricingError::NonFinite("running_average"));
+        assert_eq!(invalid(AsianAverage { running_average: 0.0, ..base }), PricingError::InvalidParameter { name: "running_average", value: 0.0 });
+        // A zero running average is fine before the window opens
+        assert!(try_calculate_greeks(&params, OptionType::Call, &AsianAverage { window: 0.25, running_average: 0.0, ..base }).is_ok());
+    }
+}
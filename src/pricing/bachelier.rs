@@ -0,0 +1,402 @@
+//! Bachelier (normal) model pricing with automatic differentiation for Greeks
+//!
+//! The forward follows arithmetic Brownian motion, so forwards and strikes may
+//! be zero or negative, as is common for rates and spreads. Volatility is quoted
+//! in absolute (normal) terms, e.g. 0.0075 for 75 basis points a year.
+
+use crate::ad::{norm_cdf, norm_pdf, Scalar};
+use crate::pricing::black_scholes::{intrinsic_greeks, BlackScholesParams};
+use crate::pricing::closed_form::{self, ClosedForm, Inputs};
+use crate::pricing::lets_be_rational::{black_price, implied_black_volatility};
+use crate::pricing::PricingError;
+use crate::types::{Greeks, OptionType};
+use std::f64::consts::PI;
+
+/// Bachelier pricing parameters
+#[derive(Debug, Clone, Copy)]
+pub struct BachelierParams {
+    pub forward: f64,
+    pub strike: f64,
+    pub t: f64,
+    /// Normal volatility, in units of the forward per square root of a year
+    pub vol: f64,
+    pub discount_rate: f64,
+}
+
+impl BachelierParams {
+    pub fn new(forward: f64, strike: f64, t: f64, vol: f64, discount_rate: f64) -> Self {
+        Self {
+            forward,
+            strike,
+            t,
+            vol,
+            discount_rate,
+        }
+    }
+
+    /// Create parameters, rejecting inputs the model cannot price
+    pub fn try_new(forward: f64, strike: f64, t: f64, vol: f64, discount_rate: f64) -> Result<Self, PricingError> {
+        let params = Self::new(forward, strike, t, vol, discount_rate);
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Check that every input is finite and within the model's domain
+    ///
+    /// Forward and strike may take any sign. Zero time to maturity is accepted
+    /// and prices at discounted intrinsic value.
+    pub fn validate(&self) -> Result<(), PricingError> {
+        let fields = [
+            ("forward", self.forward),
+            ("strike", self.strike),
+            ("t", self.t),
+            ("vol", self.vol),
+            ("discount_rate", self.discount_rate),
+        ];
+        if let Some(&(name, _)) = fields.iter().find(|(_, v)| !v.is_finite()) {
+            return Err(PricingError::NonFinite(name));
+        }
+
+        if self.t < 0.0 {
+            return Err(PricingError::NegativeMaturity(self.t));
+        }
+        if self.vol <= 0.0 {
+            return Err(PricingError::NonPositiveVolatility(self.vol));
+        }
+
+        Ok(())
+    }
+}
+
+/// Undiscounted Bachelier value: θ(F - K) N(θd) + σ√T φ(d) with d = (F - K) / (σ√T)
+#[inline]
+fn undiscounted_price<T: Scalar>(f: T, k: f64, t: T, sigma: T, option_type: OptionType) -> T {
+    let moneyness = match option_type {
+        OptionType::Call => f - k,
+        OptionType::Put => -(f - k),
+    };
+    if sigma.value() == 0.0 || t.value() == 0.0 {
+        return moneyness.max(T::constant(0.0));
+    }
+
+    let total_vol = sigma * t.sqrt();
+    let d = moneyness / total_vol;
+    moneyness * norm_cdf(d) + total_vol * norm_pdf(d)
+}
+
+/// Price a European call under the Bachelier model
+///
+/// Generic over `Scalar`, so seeding any input on an AD number type yields its sensitivity.
+#[inline]
+pub fn call_price<T: Scalar>(f: T, k: f64, t: T, sigma: T, r: T) -> T {
+    (-r * t).exp() * undiscounted_price(f, k, t, sigma, OptionType::Call)
+}
+
+/// Price a European put under the Bachelier model
+///
+/// Generic over `Scalar`, so seeding any input on an AD number type yields its sensitivity.
+#[inline]
+pub fn put_price<T: Scalar>(f: T, k: f64, t: T, sigma: T, r: T) -> T {
+    (-r * t).exp() * undiscounted_price(f, k, t, sigma, OptionType::Put)
+}
+
+/// Calculate option price and all Greeks using automatic differentiation
+///
+/// Delta and gamma are taken with respect to the forward and vega with respect
+/// to the normal volatility. Phi is always zero since the model has no dividend yield.
+pub fn calculate_greeks(params: &BachelierParams, option_type: OptionType) -> Greeks {
+    let contract = NormalOption(option_type);
+    closed_form::forward_greeks(params.forward, params.strike, params.t, params.vol, params.discount_rate, &contract)
+}
+
+/// European option priced under the Bachelier model
+struct NormalOption(OptionType);
+
+impl ClosedForm for NormalOption {
+    #[inline]
+    fn price<T: Scalar>(&self, inputs: &Inputs<T>) -> T {
+        let &Inputs { s: f, k, t, sigma, r, .. } = inputs;
+        match self.0 {
+            OptionType::Call => call_price(f, k, t, sigma, r),
+            OptionType::Put => put_price(f, k, t, sigma, r),
+        }
+    }
+
+    fn at_expiry(&self, params: &BlackScholesParams) -> Greeks {
+        intrinsic_greeks(params.spot, params.strike, self.0)
+    }
+}
+
+/// Calculate option price and all Greeks after validating the inputs
+pub fn try_calculate_greeks(params: &BachelierParams, option_type: OptionType) -> Result<Greeks, PricingError> {
+    params.validate()?;
+    Ok(calculate_greeks(params, option_type))
+}
+
+/// Solve the discounted Bachelier price for normal volatility
+///
+/// Uses Jäckel's analytic inversion ("Implied Normal Volatility", Wilmott 2017):
+/// a rational approximation of the inverse of φ̃(x) = N(x) + φ(x)/x followed by
+/// one third-order Householder step, which is accurate to machine precision
+/// without any iteration. Returns `PricingError::ArbitrageViolation` unless the
+/// price exceeds the discounted intrinsic value.
+pub fn implied_normal_vol(
+    price: f64,
+    forward: f64,
+    strike: f64,
+    t: f64,
+    discount_rate: f64,
+    option_type: OptionType,
+) -> Result<f64, PricingError> {
+    let fields = [
+        ("price", price),
+        ("forward", forward),
+        ("strike", strike),
+        ("t", t),
+        ("discount_rate", discount_rate),
+    ];
+    if let Some(&(name, _)) = fields.iter().find(|(_, v)| !v.is_finite()) {
+        return Err(PricingError::NonFinite(name));
+    }
+    if t < 0.0 {
+        return Err(PricingError::NegativeMaturity(t));
+    }
+
+    let discount = (-discount_rate * t).exp();
+    let moneyness = match option_type {
+        OptionType::Call => forward - strike,
+        OptionType::Put => strike - forward,
+    };
+    let lower = discount * moneyness.max(0.0);
+    if price <= lower {
+        return Err(PricingError::ArbitrageViolation { price, lower, upper: f64::INFINITY });
+    }
+    // Without time to expiry the price carries no information about volatility
+    if t == 0.0 {
+        return Err(PricingError::ImpliedVolNotFound);
+    }
+
+    // Time value of the undiscounted price, identical for calls and puts by parity
+    let time_value = price / discount - moneyness.max(0.0);
+    let distance = (forward - strike).abs();
+    if distance == 0.0 {
+        return Ok(time_value * (2.0 * PI).sqrt() / t.sqrt());
+    }
+
+    // The out-of-the-money option is worth |F - K| φ̃(-|F - K| / σ√T)
+    let target = -time_value / distance;
+    // A time value that underflows against |F - K| leaves the zero-volatility limit
+    if target == 0.0 {
+        return Ok(0.0);
+    }
+    let x = inverse_phi_tilde(target);
+    let vol = distance / (x.abs() * t.sqrt());
+    if !vol.is_finite() {
+        return Err(PricingError::ImpliedVolNotFound);
+    }
+    Ok(vol)
+}
+
+/// φ̃(x) = N(x) + φ(x)/x, the normalised time value of an out-of-the-money option for x < 0
+fn phi_tilde(x: f64) -> f64 {
+    norm_cdf(x) + norm_pdf(x) / x
+}
+
+/// Inverse of φ̃ on (-∞, 0), per Jäckel (2017)
+fn inverse_phi_tilde(phi_star: f64) -> f64 {
+    let x_bar = if phi_star < -0.001882039271 {
+        let g = 1.0 / (phi_star - 0.5);
+        let g2 = g * g;
+        let xi = (0.032114372355 - g2 * (0.016969777977 - g2 * (2.6207332461e-3 - 9.6066952861e-5 * g2)))
+            / (1.0 - g2 * (0.6635646938 - g2 * (0.14528712196 - 0.010472855461 * g2)));
+        g * (1.0 / (2.0 * PI).sqrt() + xi * g2)
+    } else {
+        let h = (-(-phi_star).ln()).sqrt();
+        (9.4883409779 - h * (9.6320903635 - h * (0.58556997323 + 2.1464093351 * h)))
+            / (1.0 - h * (0.65174820867 + h * (1.5120247828 + 6.6437847132e-5 * h)))
+    };
+
+    // One Householder(3) step on φ̃(x) = φ*
+    let q = (phi_tilde(x_bar) - phi_star) / norm_pdf(x_bar);
+    let x2 = x_bar * x_bar;
+    x_bar
+        + 3.0 * q * x2 * (2.0 - q * x_bar * (2.0 + x2))
+            / (6.0 + q * x_bar * (-12.0 + x_bar * (6.0 * q + x_bar * (-6.0 + q * x_bar * (3.0 + x2)))))
+}
+
+/// Option type whose price carries the most information about volatility
+fn out_of_the_money(forward: f64, strike: f64) -> OptionType {
+    if strike >= forward {
+        OptionType::Call
+    } else {
+        OptionType::Put
+    }
+}
+
+/// Normal volatility giving the same option price as a lognormal (Black) volatility
+///
+/// The conversion is exact: the out-of-the-money option is priced with Black-76
+/// and inverted with `implied_normal_vol`. Forward and strike must be positive.
+pub fn normal_vol_from_lognormal(forward: f64, strike: f64, t: f64, lognormal_vol: f64) -> Result<f64, PricingError> {
+    if forward <= 0.0 {
+        return Err(PricingError::NonPositiveForward(forward));
+    }
+    if strike <= 0.0 {
+        return Err(PricingError::NonPositiveStrike(strike));
+    }
+    let option_type = out_of_the_money(forward, strike);
+    let price = black_price(forward, strike, lognormal_vol, t, option_type);
+    implied_normal_vol(price, forward, strike, t, 0.0, option_type)
+}
+
+/// Lognormal (Black) volatility giving the same option price as a normal volatility
+///
+/// The conversion is exact: the out-of-the-money option is priced with Bachelier
+/// and inverted with Let's Be Rational. Forward and strike must be positive.
+pub fn lognormal_vol_from_normal(forward: f64, strike: f64, t: f64, normal_vol: f64) -> Result<f64, PricingError> {
+    if forward <= 0.0 {
+        return Err(PricingError::NonPositiveForward(forward));
+    }
+    if strike <= 0.0 {
+        return Err(PricingError::NonPositiveStrike(strike));
+    }
+    let option_type = out_of_the_money(forward, strike);
+    let price = undiscounted_price(forward, strike, t, normal_vol, option_type);
+    implied_black_volatility(price, forward, strike, t, option_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_greeks_match_closed_form() {
+        let cases = [
+            BachelierParams::new(0.02, 0.025, 1.0, 0.008, 0.03),
+            BachelierParams::new(-0.003, 0.001, 2.5, 0.005, 0.01),
+            BachelierParams::new(0.01, 0.01, 0.5, 0.01, -0.005),
+        ];
+
+        for params in cases.iter() {
+            let BachelierParams { forward: f, strike: k, t, vol, discount_rate: r } = *params;
+            let total_vol = vol * t.sqrt();
+            let d = (f - k) / total_vol;
+            let df = (-r * t).exp();
+
+            for &option_type in &[OptionType::Call, OptionType::Put] {
+                let greeks = calculate_greeks(params, option_type);
+                let (price, delta) = match option_type {
+                    OptionType::Call => (df * ((f - k) * norm_cdf(d) + total_vol * norm_pdf(d)), df * norm_cdf(d)),
+                    OptionType::Put => (df * ((k - f) * norm_cdf(-d) + total_vol * norm_pdf(d)), -df * norm_cdf(-d)),
+                };
+
+                assert_relative_eq!(greeks.price, price, epsilon = 1e-15);
+                assert_relative_eq!(greeks.delta, delta, epsilon = 1e-12);
+                assert_relative_eq!(greeks.gamma, df * norm_pdf(d) / total_vol, max_relative = 1e-12);
+                assert_relative_eq!(greeks.vega, df * norm_pdf(d) * t.sqrt(), epsilon = 1e-12);
+                assert_relative_eq!(
+                    greeks.theta,
+                    -df * norm_pdf(d) * vol / (2.0 * t.sqrt()) + r * price,
+                    epsilon = 1e-12
+                );
+                assert_relative_eq!(greeks.rho, -t * price, epsilon = 1e-15);
+            }
+        }
+    }
+
+    #[test]
+    fn test_put_call_parity_with_negative_forward() {
+        let params = BachelierParams::new(-0.004, -0.001, 1.0, 0.006, 0.02);
+        let call = calculate_greeks(&params, OptionType::Call);
+        let put = calculate_greeks(&params, OptionType::Put);
+        let df = (-0.02_f64).exp();
+
+        assert!(call.price > 0.0 && put.price > 0.0);
+        assert_relative_eq!(call.price - put.price, df * (-0.003), epsilon = 1e-16);
+        assert_relative_eq!(call.delta - put.delta, df, epsilon = 1e-15);
+    }
+
+    #[test]
+    fn test_implied_normal_vol_round_trip() {
+        for &(forward, strike) in &[(0.02, 0.02), (0.02, 0.03), (0.03, 0.005), (-0.005, 0.01), (0.01, -0.02)] {
+            for &vol in &[0.0005, 0.005, 0.02] {
+                for &t in &[0.05, 1.0, 10.0] {
+                    for &option_type in &[OptionType::Call, OptionType::Put] {
+                        let params = BachelierParams::new(forward, strike, t, vol, 0.03);
+                        let greeks = calculate_greeks(&params, option_type);
+                        // Skip quotes whose time value (of order σ vega) is lost to rounding
+                        if vol * greeks.vega <= 1e-15 * greeks.price {
+                            continue;
+                        }
+
+                        let solved = implied_normal_vol(greeks.price, forward, strike, t, 0.03, option_type).unwrap();
+
+                        // The price pins σ down to about ε p / (σ vega); allow a small multiple of that
+                        let conditioning = f64::EPSILON * greeks.price / (vol * greeks.vega);
+                        assert_relative_eq!(solved, vol, max_relative = 1e-13_f64.max(16.0 * conditioning));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_implied_normal_vol_bounds() {
+        assert!(matches!(
+            implied_normal_vol(0.004, 0.025, 0.02, 1.0, 0.0, OptionType::Call),
+            Err(PricingError::ArbitrageViolation { .. })
+        ));
+        assert_eq!(
+            implied_normal_vol(0.001, 0.02, 0.02, 0.0, 0.0, OptionType::Call).unwrap_err(),
+            PricingError::ImpliedVolNotFound
+        );
+
+        // Time value too small to resolve against |F - K|: the zero-volatility limit, not NaN
+        assert_eq!(implied_normal_vol(5e-324, 100.0, 200.0, 1.0, 0.0, OptionType::Call), Ok(0.0));
+        let tiny = implied_normal_vol(1e-300, 100.0, 200.0, 1.0, 0.0, OptionType::Call).unwrap();
+        assert!(tiny > 0.0 && tiny < 5.0);
+    }
+
+    #[test]
+    fn test_vol_conversion_round_trip() {
+        for &(forward, strike) in &[(0.03, 0.03), (0.03, 0.045), (0.03, 0.015), (100.0, 80.0)] {
+            for &t in &[0.25, 5.0] {
+                let lognormal = 0.3;
+                let normal = normal_vol_from_lognormal(forward, strike, t, lognormal).unwrap();
+                assert_relative_eq!(lognormal_vol_from_normal(forward, strike, t, normal).unwrap(), lognormal, max_relative = 1e-12);
+
+                // Both vols must reproduce the same option price
+                let option_type = out_of_the_money(forward, strike);
+                assert_relative_eq!(
+                    undiscounted_price(forward, strike, t, normal, option_type),
+                    black_price(forward, strike, lognormal, t, option_type),
+                    max_relative = 1e-12
+                );
+            }
+        }
+
+        // At the money σ_N ≈ σ_B F (1 - σ_B² T / 24) to leading orders
+        let normal = normal_vol_from_lognormal(0.03, 0.03, 1.0, 0.2).unwrap();
+        assert_relative_eq!(normal, 0.2 * 0.03 * (1.0 - 0.04 / 24.0), max_relative = 1e-5);
+
+        assert_eq!(
+            normal_vol_from_lognormal(-0.01, 0.02, 1.0, 0.2).unwrap_err(),
+            PricingError::NonPositiveForward(-0.01)
+        );
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        assert!(BachelierParams::try_new(-0.01, -0.02, 1.0, 0.005, 0.0).is_ok());
+        assert_eq!(
+            BachelierParams::try_new(0.01, 0.01, 1.0, 0.0, 0.0).unwrap_err(),
+            PricingError::NonPositiveVolatility(0.0)
+        );
+        let bad = BachelierParams::new(0.01, f64::NAN, 1.0, 0.005, 0.0);
+        assert_eq!(
+            try_calculate_greeks(&bad, OptionType::Call).unwrap_err(),
+            PricingError::NonFinite("strike")
+        );
+    }
+}
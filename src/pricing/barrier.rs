@@ -0,0 +1,657 @@
+//! Closed-form barrier options under Black-Scholes
+//!
+//! Single barriers use the Reiner-Rubinstein (1991) formulas for all eight
+//! up/down, in/out, call/put combinations. A knock-out rebate is paid when the
+//! barrier is hit; a knock-in rebate is paid at expiry if it never is.
+//!
+//! Double knock-out options are priced with the Ikeda-Kunitomo (1992) image
+//! series, and double knock-ins by parity with the vanilla. Double-barrier
+//! rebates are paid at expiry.
+//!
+//! Discretely monitored barriers are priced as continuous ones moved away from
+//! spot by e^(βσ√Δt), β = -ζ(½)/√(2π), after Broadie, Glasserman and Kou (1997).
+//!
+//! Every formula is generic over `Scalar`, so Greeks come from AD, vega
+//! included through the monitoring shift.
+
+use crate::ad::ops::ln_norm_cdf;
+use crate::ad::{norm_cdf, Scalar};
+use crate::pricing::black_scholes::{expired_greeks, BlackScholesParams};
+use crate::pricing::closed_form::{self, ClosedForm, Inputs};
+use crate::pricing::PricingError;
+use crate::types::{Greeks, OptionType};
+
+/// Barrier direction and effect for a single-barrier option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarrierType {
+    DownAndIn,
+    DownAndOut,
+    UpAndIn,
+    UpAndOut,
+}
+
+impl BarrierType {
+    fn is_down(self) -> bool {
+        matches!(self, BarrierType::DownAndIn | BarrierType::DownAndOut)
+    }
+
+    fn is_knock_in(self) -> bool {
+        matches!(self, BarrierType::DownAndIn | BarrierType::UpAndIn)
+    }
+}
+
+/// Effect of touching either barrier of a double-barrier option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoubleBarrierType {
+    KnockIn,
+    KnockOut,
+}
+
+/// How often the barrier is observed
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Monitoring {
+    #[default]
+    Continuous,
+    /// Observed at a fixed interval, in years
+    Discrete(f64),
+}
+
+impl Monitoring {
+    /// Continuity correction factor e^(βσ√Δt) applied away from spot
+    fn shift<T: Scalar>(self, sigma: T) -> T {
+        match self {
+            Monitoring::Continuous => T::constant(1.0),
+            Monitoring::Discrete(interval) => (sigma * (BROADIE_GLASSERMAN * interval.sqrt())).exp(),
+        }
+    }
+
+    fn validate(self) -> Result<(), PricingError> {
+        match self {
+            Monitoring::Continuous => Ok(()),
+            Monitoring::Discrete(interval) if !interval.is_finite() => Err(PricingError::NonFinite("monitoring")),
+            Monitoring::Discrete(interval) if interval <= 0.0 => {
+                Err(PricingError::InvalidParameter { name: "monitoring", value: interval })
+            }
+            Monitoring::Discrete(_) => Ok(()),
+        }
+    }
+}
+
+/// -ζ(½)/√(2π)
+const BROADIE_GLASSERMAN: f64 = 0.5825971579390106;
+
+/// Single barrier
+#[derive(Debug, Clone, Copy)]
+pub struct Barrier {
+    pub barrier_type: BarrierType,
+    pub level: f64,
+    /// Cash paid instead of the option: at the hit for knock-outs, at expiry for knock-ins
+    pub rebate: f64,
+    pub monitoring: Monitoring,
+}
+
+impl Barrier {
+    /// Continuously monitored barrier
+    pub fn new(barrier_type: BarrierType, level: f64, rebate: f64) -> Self {
+        Self {
+            barrier_type,
+            level,
+            rebate,
+            monitoring: Monitoring::Continuous,
+        }
+    }
+
+    /// Check the level, rebate and monitoring interval
+    pub fn validate(&self) -> Result<(), PricingError> {
+        validate_levels(&[("level", self.level)], self.rebate)?;
+        self.monitoring.validate()
+    }
+
+    fn breached(&self, spot: f64) -> bool {
+        if self.barrier_type.is_down() {
+            spot <= self.level
+        } else {
+            spot >= self.level
+        }
+    }
+
+    /// Rebate owed once the option settles, or `None` if the vanilla payoff is
+    fn rebate_due(&self, spot: f64) -> Option<f64> {
+        (self.barrier_type.is_knock_in() != self.breached(spot)).then_some(self.rebate)
+    }
+}
+
+/// Double barrier, observed at both levels
+#[derive(Debug, Clone, Copy)]
+pub struct DoubleBarrier {
+    pub barrier_type: DoubleBarrierType,
+    pub lower: f64,
+    pub upper: f64,
+    /// Cash paid at expiry if a knock-out is extinguished or a knock-in never activated
+    pub rebate: f64,
+    pub monitoring: Monitoring,
+}
+
+impl DoubleBarrier {
+    /// Continuously monitored barriers
+    pub fn new(barrier_type: DoubleBarrierType, lower: f64, upper: f64, rebate: f64) -> Self {
+        Self {
+            barrier_type,
+            lower,
+            upper,
+            rebate,
+            monitoring: Monitoring::Continuous,
+        }
+    }
+
+    /// Check the levels, rebate and monitoring interval
+    pub fn validate(&self) -> Result<(), PricingError> {
+        validate_levels(&[("lower", self.lower), ("upper", self.upper)], self.rebate)?;
+        if self.upper <= self.lower {
+            return Err(PricingError::InvalidParameter { name: "upper", value: self.upper });
+        }
+        self.monitoring.validate()
+    }
+
+    fn breached(&self, spot: f64) -> bool {
+        spot <= self.lower || spot >= self.upper
+    }
+
+    fn rebate_due(&self, spot: f64) -> Option<f64> {
+        ((self.barrier_type == DoubleBarrierType::KnockIn) != self.breached(spot)).then_some(self.rebate)
+    }
+}
+
+fn validate_levels(levels: &[(&'static str, f64)], rebate: f64) -> Result<(), PricingError> {
+    if let Some(&(name, _)) = levels.iter().find(|(_, v)| !v.is_finite()) {
+        return Err(PricingError::NonFinite(name));
+    }
+    if !rebate.is_finite() {
+        return Err(PricingError::NonFinite("rebate"));
+    }
+    if let Some(&(name, value)) = levels.iter().find(|(_, v)| *v <= 0.0) {
+        return Err(PricingError::InvalidParameter { name, value });
+    }
+    if rebate < 0.0 {
+        return Err(PricingError::InvalidParameter { name: "rebate", value: rebate });
+    }
+    Ok(())
+}
+
+/// Price a single-barrier option
+///
+/// Generic over `Scalar`, so seeding any input on an AD number type yields its
+/// sensitivity. A barrier already breached settles at once: a knock-in becomes
+/// the vanilla, a knock-out pays its rebate.
+#[allow(clippy::too_many_arguments)]
+pub fn price<T: Scalar>(s: T, k: f64, t: T, sigma: T, r: T, q: T, option_type: OptionType, barrier: &Barrier) -> T {
+    barrier.price(&Inputs::new(s, k, t, sigma, r, q), option_type)
+}
+
+/// Price a double-barrier option
+///
+/// Generic over `Scalar`; a barrier already breached settles as in `price`.
+#[allow(clippy::too_many_arguments)]
+pub fn double_barrier_price<T: Scalar>(
+    s: T,
+    k: f64,
+    t: T,
+    sigma: T,
+    r: T,
+    q: T,
+    option_type: OptionType,
+    barrier: &DoubleBarrier,
+) -> T {
+    barrier.price(&Inputs::new(s, k, t, sigma, r, q), option_type)
+}
+
+/// Calculate price and Greeks of a single-barrier option
+///
+/// First-order Greeks come from one `MultiDual` pass and gamma from a
+/// `HyperDual` pass, as for vanillas.
+pub fn calculate_greeks(params: &BlackScholesParams, option_type: OptionType, barrier: &Barrier) -> Greeks {
+    closed_form::greeks(params, &(barrier, option_type))
+}
+
+/// Calculate single-barrier price and Greeks after validating the inputs
+pub fn try_calculate_greeks(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    barrier: &Barrier,
+) -> Result<Greeks, PricingError> {
+    params.validate()?;
+    barrier.validate()?;
+    Ok(calculate_greeks(params, option_type, barrier))
+}
+
+/// Calculate price and Greeks of a double-barrier option
+pub fn calculate_double_barrier_greeks(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    barrier: &DoubleBarrier,
+) -> Greeks {
+    closed_form::greeks(params, &(barrier, option_type))
+}
+
+/// Calculate double-barrier price and Greeks after validating the inputs
+pub fn try_calculate_double_barrier_greeks(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    barrier: &DoubleBarrier,
+) -> Result<Greeks, PricingError> {
+    params.validate()?;
+    barrier.validate()?;
+    Ok(calculate_double_barrier_greeks(params, option_type, barrier))
+}
+
+/// Barrier contract, priced generically over the number type
+trait BarrierContract {
+    fn rebate_due(&self, spot: f64) -> Option<f64>;
+
+    fn breached(&self, spot: f64) -> bool;
+
+    /// Price while the barrier is live and time remains
+    fn live_price<T: Scalar>(&self, inputs: &Inputs<T>, option_type: OptionType) -> T;
+
+    fn price<T: Scalar>(&self, inputs: &Inputs<T>, option_type: OptionType) -> T {
+        let spot = inputs.s.value();
+        if inputs.t.value() <= 0.0 || self.breached(spot) {
+            return match self.rebate_due(spot) {
+                Some(rebate) => T::constant(rebate),
+                None => option_type.price(inputs),
+            };
+        }
+        self.live_price(inputs, option_type)
+    }
+}
+
+impl<B: BarrierContract> ClosedForm for (&B, OptionType) {
+    fn price<T: Scalar>(&self, inputs: &Inputs<T>) -> T {
+        self.0.price(inputs, self.1)
+    }
+
+    fn at_expiry(&self, params: &BlackScholesParams) -> Greeks {
+        match self.0.rebate_due(params.spot) {
+            Some(rebate) => Greeks::new(rebate, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0),
+            None => expired_greeks(params, self.1),
+        }
+    }
+}
+
+impl BarrierContract for Barrier {
+    fn rebate_due(&self, spot: f64) -> Option<f64> {
+        Barrier::rebate_due(self, spot)
+    }
+
+    fn breached(&self, spot: f64) -> bool {
+        Barrier::breached(self, spot)
+    }
+
+    /// Haug's building blocks A-F, with η = 1 for down and -1 for up barriers
+    fn live_price<T: Scalar>(&self, inputs: &Inputs<T>, option_type: OptionType) -> T {
+        let &Inputs { s, k, t, sigma, r, q, .. } = inputs;
+        let down = self.barrier_type.is_down();
+        let shift = self.monitoring.shift(sigma);
+        let h = if down { shift.powf(-1.0) * self.level } else { shift * self.level };
+
+        let phi = match option_type {
+            OptionType::Call => 1.0,
+            OptionType::Put => -1.0,
+        };
+        let eta = if down { 1.0 } else { -1.0 };
+        let variance = sigma.powi2();
+        let mu = (r - q - variance * 0.5) / variance;
+        let lambda = (mu * mu + r * 2.0 / variance).sqrt();
+        let std_dev = sigma * t.sqrt();
+        let ln_hs = (h / s).ln();
+        let ln_sk = (s / k).ln();
+
+        let x1 = ln_sk / std_dev + std_dev * (mu + 1.0);
+        let x2 = -ln_hs / std_dev + std_dev * (mu + 1.0);
+        let y1 = (ln_hs * 2.0 + ln_sk) / std_dev + std_dev * (mu + 1.0);
+        let y2 = ln_hs / std_dev + std_dev * (mu + 1.0);
+        let z = ln_hs / std_dev + lambda * std_dev;
+
+        let asset = s * (-q * t).exp();
+        let discount = (-r * t).exp();
+        let cash = discount * k;
+        let reflected_asset = (ln_hs * (mu * 2.0 + 2.0)).exp();
+        let reflected_cash = (ln_hs * (mu * 2.0)).exp();
+
+        let a = (asset * norm_cdf(x1 * phi) - cash * norm_cdf((x1 - std_dev) * phi)) * phi;
+        let b = (asset * norm_cdf(x2 * phi) - cash * norm_cdf((x2 - std_dev) * phi)) * phi;
+        let c = (asset * reflected_asset * norm_cdf(y1 * eta) - cash * reflected_cash * norm_cdf((y1 - std_dev) * eta)) * phi;
+        let d = (asset * reflected_asset * norm_cdf(y2 * eta) - cash * reflected_cash * norm_cdf((y2 - std_dev) * eta)) * phi;
+        let e = discount
+            * (norm_cdf((x2 - std_dev) * eta) - reflected_cash * norm_cdf((y2 - std_dev) * eta))
+            * self.rebate;
+        let f = ((ln_hs * (mu + lambda)).exp() * norm_cdf(z * eta)
+            + (ln_hs * (mu - lambda)).exp() * norm_cdf((z - lambda * std_dev * 2.0) * eta))
+            * self.rebate;
+
+        let above = k >= h.value();
+        match (self.barrier_type, option_type) {
+            (BarrierType::DownAndIn, OptionType::Call) if above => c + e,
+            (BarrierType::DownAndIn, OptionType::Call) => a - b + d + e,
+            (BarrierType::UpAndIn, OptionType::Call) if above => a + e,
+            (BarrierType::UpAndIn, OptionType::Call) => b - c + d + e,
+            (BarrierType::DownAndIn, OptionType::Put) if above => b - c + d + e,
+            (BarrierType::DownAndIn, OptionType::Put) => a + e,
+            (BarrierType::UpAndIn, OptionType::Put) if above => a - b + d + e,
+            (BarrierType::UpAndIn, OptionType::Put) => c + e,
+            (BarrierType::DownAndOut, OptionType::Call) if above => a - c + f,
+            (BarrierType::DownAndOut, OptionType::Call) => b - d + f,
+            (BarrierType::UpAndOut, OptionType::Call) if above => f,
+            (BarrierType::UpAndOut, OptionType::Call) => a - b + c - d + f,
+            (BarrierType::DownAndOut, OptionType::Put) if above => a - b + c - d + f,
+            (BarrierType::DownAndOut, OptionType::Put) => f,
+            (BarrierType::UpAndOut, OptionType::Put) if above => b - d + f,
+            (BarrierType::UpAndOut, OptionType::Put) => a - c + f,
+        }
+    }
+}
+
+impl BarrierContract for DoubleBarrier {
+    fn rebate_due(&self, spot: f64) -> Option<f64> {
+        DoubleBarrier::rebate_due(self, spot)
+    }
+
+    fn breached(&self, spot: f64) -> bool {
+        DoubleBarrier::breached(self, spot)
+    }
+
+    fn live_price<T: Scalar>(&self, inputs: &Inputs<T>, option_type: OptionType) -> T {
+        let &Inputs { s, k, t, sigma, r, q, .. } = inputs;
+        let shift = self.monitoring.shift(sigma);
+        let (lower, upper) = (shift.powf(-1.0) * self.lower, shift * self.upper);
+
+        // The payoff is live for terminal spots in (from, to)
+        let (from, to) = match option_type {
+            OptionType::Call if k > lower.value() => (T::constant(k), upper),
+            OptionType::Call => (lower, upper),
+            OptionType::Put if k < upper.value() => (lower, T::constant(k)),
+            OptionType::Put => (lower, upper),
+        };
+        let discount = (-r * t).exp();
+        let knock_out = if from.value() < to.value() {
+            let (asset, cash) = ikeda_kunitomo(inputs, lower, upper, from, to);
+            let value = s * (-q * t).exp() * asset - discount * cash * k;
+            match option_type {
+                OptionType::Call => value,
+                OptionType::Put => -value,
+            }
+        } else {
+            T::constant(0.0)
+        };
+        let (_, survival) = ikeda_kunitomo(inputs, lower, upper, lower, upper);
+
+        match self.barrier_type {
+            DoubleBarrierType::KnockOut => knock_out + discount * (-survival + 1.0) * self.rebate,
+            DoubleBarrierType::KnockIn => option_type.price(inputs) - knock_out + discount * survival * self.rebate,
+        }
+    }
+}
+
+/// Ikeda-Kunitomo image series for flat barriers
+///
+/// Returns the forward-measure and risk-neutral probabilities that the spot
+/// stays within (lower, upper) and ends in (from, to). The image terms decay
+/// like exp(-2n² width² / σ²T), so the series runs to five standard deviations
+/// past the corridor, and each weight is applied to its probability in log
+/// space: on their own the weights overflow once σ² is small beside the carry.
+fn ikeda_kunitomo<T: Scalar>(inputs: &Inputs<T>, lower: T, upper: T, from: T, to: T) -> (T, T) {
+    let &Inputs { s, t, sigma, r, q, .. } = inputs;
+    let variance = sigma.powi2();
+    let std_dev = sigma * t.sqrt();
+    let drift = (r - q + variance * 0.5) * t;
+    let mu = (r - q) * 2.0 / variance + 1.0;
+    let (ln_s, ln_lower, ln_upper) = (s.ln(), lower.ln(), upper.ln());
+    let (ln_from, ln_to) = (from.ln(), to.ln());
+    let width = ln_upper - ln_lower;
+    let terms = (5.0 * std_dev.value() / width.value()).ceil() as i32 + 1;
+
+    let mut asset = T::constant(0.0);
+    let mut cash = T::constant(0.0);
+    for n in -terms..=terms {
+        let n = n as f64;
+        // Paths shifted by 2n corridor widths, and their reflections in the lower barrier
+        let image = ln_s + width * (2.0 * n) + drift;
+        let reflection = ln_lower * (2.0 * n + 2.0) - ln_upper * (2.0 * n) - ln_s + drift;
+        let d = |level: T| (image - level) / std_dev;
+        let d_reflected = |level: T| (reflection - level) / std_dev;
+        let ln_weight = width * n;
+        let ln_reflected_weight = ln_lower * (n + 1.0) - ln_upper * n - ln_s;
+
+        asset = asset + weighted_mass(ln_weight * mu, d(ln_from), d(ln_to))
+            - weighted_mass(ln_reflected_weight * mu, d_reflected(ln_from), d_reflected(ln_to));
+        cash = cash + weighted_mass(ln_weight * (mu - 2.0), d(ln_from) - std_dev, d(ln_to) - std_dev)
+            - weighted_mass(ln_reflected_weight * (mu - 2.0), d_reflected(ln_from) - std_dev, d_reflected(ln_to) - std_dev);
+    }
+    (asset, cash)
+}
+
+/// e^(ln_weight) (N(a) - N(b)), taken from whichever tail keeps both terms small
+fn weighted_mass<T: Scalar>(ln_weight: T, a: T, b: T) -> T {
+    let (a, b) = if a.value() + b.value() > 0.0 { (-b, -a) } else { (a, b) };
+    (ln_weight + ln_norm_cdf(a)).exp() - (ln_weight + ln_norm_cdf(b)).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ad::Dual;
+    use crate::pricing::black_scholes;
+    use crate::pricing::monte_carlo::RngStream;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_reiner_rubinstein_reference_prices() {
+        // Haug, The Complete Guide to Option Pricing Formulas (2007), table 4-13:
+        // S = 100, T = 0.5, r = 0.08, q = 0.04, σ = 0.25, rebate 3
+        let cases = [
+            (BarrierType::DownAndOut, OptionType::Call, 95.0, [9.02456769496687, 6.792436575025224, 4.875857740147607]),
+            (BarrierType::DownAndOut, OptionType::Call, 100.0, [3.0, 3.0, 3.0]),
+            (BarrierType::UpAndOut, OptionType::Call, 105.0, [2.678912504840117, 2.358019790844058, 2.345348946386964]),
+            (BarrierType::DownAndIn, OptionType::Call, 95.0, [7.762670209856352, 4.010941850449067, 2.057612752728263]),
+            (BarrierType::DownAndIn, OptionType::Call, 100.0, [13.83328710179672, 7.849427622447794, 3.979519689849373]),
+            (BarrierType::UpAndIn, OptionType::Call, 105.0, [14.11117311960304, 8.448206354250171, 4.590969266108845]),
+            (BarrierType::DownAndOut, OptionType::Put, 95.0, [2.279837967201536, 2.294749633343096, 2.625213584548685]),
+            (BarrierType::UpAndOut, OptionType::Put, 105.0, [3.775955132169756, 5.493227672371937, 7.518722082113082]),
+            (BarrierType::DownAndIn, OptionType::Put, 95.0, [2.958582130655245, 6.567705376687986, 11.97522788440721]),
+            (BarrierType::DownAndIn, OptionType::Put, 100.0, [2.284469294830283, 5.908504207004585, 11.6464906659294]),
+            (BarrierType::UpAndIn, OptionType::Put, 105.0, [1.465312685306962, 3.372075057279083, 7.08456710646275]),
+        ];
+        for (barrier_type, option_type, level, expected) in cases {
+            let barrier = Barrier::new(barrier_type, level, 3.0);
+            for (strike, expected) in [90.0, 100.0, 110.0].into_iter().zip(expected) {
+                let value = price(100.0, strike, 0.5, 0.25, 0.08, 0.04, option_type, &barrier);
+                assert_relative_eq!(value, expected, epsilon = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_in_out_parity() {
+        let (s, t, sigma, r, q) = (100.0, 0.75, 0.3, 0.04, 0.01);
+        for &option_type in &[OptionType::Call, OptionType::Put] {
+            for &strike in &[85.0, 100.0, 120.0] {
+                let vanilla = option_type.price(&Inputs::new(s, strike, t, sigma, r, q));
+                for &(knock_in, knock_out, level) in &[
+                    (BarrierType::DownAndIn, BarrierType::DownAndOut, 90.0),
+                    (BarrierType::UpAndIn, BarrierType::UpAndOut, 115.0),
+                ] {
+                    let value = |barrier_type| price(s, strike, t, sigma, r, q, option_type, &Barrier::new(barrier_type, level, 0.0));
+                    assert_relative_eq!(value(knock_in) + value(knock_out), vanilla, epsilon = 1e-12);
+                }
+
+                // A double knock-in and knock-out pay the rebate between them exactly once, at expiry
+                let double = |barrier_type| {
+                    let barrier = DoubleBarrier::new(barrier_type, 80.0, 125.0, 2.0);
+                    double_barrier_price(s, strike, t, sigma, r, q, option_type, &barrier)
+                };
+                let expected = vanilla + 2.0 * (-r * t).exp();
+                assert_relative_eq!(double(DoubleBarrierType::KnockIn) + double(DoubleBarrierType::KnockOut), expected, epsilon = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ikeda_kunitomo_reference_prices() {
+        // Fourier sine expansion of the killed transition density, integrated at 30 digits
+        let knock_out = |option_type, s, k, lower, upper, t, r, q, sigma| {
+            let barrier = DoubleBarrier::new(DoubleBarrierType::KnockOut, lower, upper, 0.0);
+            double_barrier_price(s, k, t, sigma, r, q, option_type, &barrier)
+        };
+        let cases = [
+            (OptionType::Call, 100.0, 80.0, 120.0, 0.25, 0.1, 0.0, 0.25, 2.638712882539718),
+            (OptionType::Put, 105.0, 85.0, 125.0, 1.0, 0.05, 0.02, 0.3, 0.2674720365985299),
+            // Strike outside the corridor
+            (OptionType::Call, 80.0, 85.0, 125.0, 1.0, 0.05, 0.02, 0.3, 1.37408301362935),
+        ];
+        for (option_type, k, lower, upper, t, r, q, sigma, expected) in cases {
+            assert_relative_eq!(knock_out(option_type, 100.0, k, lower, upper, t, r, q, sigma), expected, epsilon = 1e-12);
+        }
+
+        // Barriers far away leave the vanilla
+        let vanilla = black_scholes::call_price(100.0, 100.0, 0.5, 0.2, 0.03, 0.0);
+        assert_relative_eq!(knock_out(OptionType::Call, 100.0, 100.0, 1.0, 1e4, 0.5, 0.03, 0.0, 0.2), vanilla, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_low_volatility_high_carry() {
+        // σ² small beside r - q pushes the image weights past f64 range; references
+        // from the same series summed at 40 digits
+        let params = BlackScholesParams::new(100.0, 100.0, 1.0, 0.03, 0.10, 0.0);
+        let barrier = DoubleBarrier::new(DoubleBarrierType::KnockOut, 80.0, 120.0, 0.0);
+        for (option_type, expected) in [(OptionType::Call, 9.444678131900403), (OptionType::Put, 0.0003198035699007798)] {
+            let greeks = try_calculate_double_barrier_greeks(&params, option_type, &barrier).unwrap();
+            assert_relative_eq!(greeks.price, expected, max_relative = 1e-10);
+            for value in [greeks.delta, greeks.gamma, greeks.vega, greeks.theta, greeks.rho, greeks.phi] {
+                assert!(value.is_finite());
+            }
+        }
+
+        // Almost no diffusion: the forward drifts to expiry inside the corridor
+        let params = BlackScholesParams { volatility: 0.01, ..params };
+        let barrier = DoubleBarrier::new(DoubleBarrierType::KnockOut, 95.0, 120.0, 0.0);
+        let greeks = try_calculate_double_barrier_greeks(&params, OptionType::Call, &barrier).unwrap();
+        assert_relative_eq!(greeks.price, 9.516258196404042, max_relative = 1e-10);
+        assert!(greeks.vega.is_finite());
+    }
+
+    #[test]
+    fn test_greeks_against_bumps() {
+        let params = BlackScholesParams::new(100.0, 100.0, 0.5, 0.3, 0.05, 0.02);
+        let mut single = Barrier::new(BarrierType::UpAndOut, 125.0, 1.5);
+        single.monitoring = Monitoring::Discrete(1.0 / 52.0);
+        let mut double = DoubleBarrier::new(DoubleBarrierType::KnockIn, 80.0, 130.0, 1.0);
+        double.monitoring = Monitoring::Discrete(1.0 / 252.0);
+
+        type Pricer<'a> = &'a dyn Fn(&BlackScholesParams) -> f64;
+        let pricers: [(Pricer, Greeks); 2] = [
+            (
+                &|p| price(p.spot, p.strike, p.time_to_maturity, p.volatility, p.risk_free_rate, p.dividend_yield, OptionType::Call, &single),
+                calculate_greeks(&params, OptionType::Call, &single),
+            ),
+            (
+                &|p| {
+                    let (s, k, t, v, r, q) = (p.spot, p.strike, p.time_to_maturity, p.volatility, p.risk_free_rate, p.dividend_yield);
+                    double_barrier_price(s, k, t, v, r, q, OptionType::Put, &double)
+                },
+                calculate_double_barrier_greeks(&params, OptionType::Put, &double),
+            ),
+        ];
+        for (pricer, greeks) in pricers {
+            let bumped = |f: &dyn Fn(&mut BlackScholesParams, f64), h: f64| {
+                let value = |sign: f64| {
+                    let mut p = params;
+                    f(&mut p, sign * h);
+                    pricer(&p)
+                };
+                (value(1.0) - value(-1.0)) / (2.0 * h)
+            };
+            assert_relative_eq!(greeks.price, pricer(&params), epsilon = 1e-12);
+            assert_relative_eq!(greeks.delta, bumped(&|p, h| p.spot += h, 1e-4), epsilon = 1e-7);
+            assert_relative_eq!(greeks.vega, bumped(&|p, h| p.volatility += h, 1e-6), epsilon = 1e-6);
+            assert_relative_eq!(greeks.theta, -bumped(&|p, h| p.time_to_maturity += h, 1e-6), epsilon = 1e-6);
+            assert_relative_eq!(greeks.rho, bumped(&|p, h| p.risk_free_rate += h, 1e-6), epsilon = 1e-6);
+            assert_relative_eq!(greeks.phi, bumped(&|p, h| p.dividend_yield += h, 1e-6), epsilon = 1e-6);
+
+            let h = 1e-3;
+            let (mut up, mut down) = (params, params);
+            up.spot += h;
+            down.spot -= h;
+            assert_relative_eq!(greeks.gamma, (pricer(&up) - 2.0 * greeks.price + pricer(&down)) / (h * h), epsilon = 1e-5);
+        }
+
+        // The generic pricer differentiates directly on a Dual
+        let delta = price(Dual::variable(100.0), 100.0, Dual::constant(0.5), Dual::constant(0.3), Dual::constant(0.05), Dual::constant(0.02), OptionType::Call, &single).deriv;
+        assert_relative_eq!(delta, calculate_greeks(&params, OptionType::Call, &single).delta, epsilon = 1e-14);
+    }
+
+    #[test]
+    fn test_discrete_monitoring_correction() {
+        // Down-and-out call observed weekly, against Monte Carlo on the same dates
+        let (s, k, t, sigma, r, q) = (100.0, 100.0, 0.5, 0.3, 0.05, 0.0);
+        let steps = 26;
+        let dt = t / steps as f64;
+        let mut barrier = Barrier::new(BarrierType::DownAndOut, 90.0, 0.0);
+        let continuous = price(s, k, t, sigma, r, q, OptionType::Call, &barrier);
+        barrier.monitoring = Monitoring::Discrete(dt);
+        let discrete = price(s, k, t, sigma, r, q, OptionType::Call, &barrier);
+
+        let paths = 40_000;
+        let (mut sum, mut sum_squares) = (0.0, 0.0);
+        let mut rng = RngStream::new(7, 0);
+        for _ in 0..paths {
+            let mut spot: f64 = s;
+            let mut alive = true;
+            for _ in 0..steps {
+                spot *= ((r - q - 0.5 * sigma * sigma) * dt + sigma * dt.sqrt() * rng.next_normal()).exp();
+                alive &= spot > barrier.level;
+            }
+            let payoff = if alive { (-r * t).exp() * (spot - k).max(0.0) } else { 0.0 };
+            sum += payoff;
+            sum_squares += payoff * payoff;
+        }
+        let mean = sum / paths as f64;
+        let standard_error = ((sum_squares / paths as f64 - mean * mean) / paths as f64).sqrt();
+
+        assert!((discrete - mean).abs() < 3.0 * standard_error, "{} vs {} ± {}", discrete, mean, standard_error);
+        assert!(mean - continuous > 6.0 * standard_error);
+    }
+
+    #[test]
+    fn test_breached_expired_and_validation() {
+        let params = BlackScholesParams::new(85.0, 100.0, 1.0, 0.2, 0.03, 0.0);
+        let knock_in = Barrier::new(BarrierType::DownAndIn, 90.0, 2.0);
+        let knock_out = Barrier::new(BarrierType::DownAndOut, 90.0, 2.0);
+        let vanilla = black_scholes::calculate_greeks(&params, OptionType::Put);
+        let greeks = calculate_greeks(&params, OptionType::Put, &knock_in);
+        assert_relative_eq!(greeks.price, vanilla.price, epsilon = 1e-14);
+        assert_relative_eq!(greeks.delta, vanilla.delta, epsilon = 1e-14);
+        assert_relative_eq!(calculate_greeks(&params, OptionType::Put, &knock_out).price, 2.0);
+
+        // At expiry an untouched knock-in pays its rebate and a knock-out its payoff
+        let expired = BlackScholesParams::new(95.0, 100.0, 0.0, 0.2, 0.03, 0.0);
+        assert_relative_eq!(calculate_greeks(&expired, OptionType::Put, &knock_in).price, 2.0);
+        assert_relative_eq!(calculate_greeks(&expired, OptionType::Put, &knock_out).price, 5.0);
+        assert_relative_eq!(calculate_greeks(&expired, OptionType::Put, &knock_out).delta, -1.0);
+
+        assert_eq!(
+            try_calculate_greeks(&params, OptionType::Call, &Barrier::new(BarrierType::UpAndOut, -1.0, 0.0)).unwrap_err(),
+            PricingError::InvalidParameter { name: "level", value: -1.0 }
+        );
+        assert_eq!(
+            try_calculate_double_barrier_greeks(&params, OptionType::Call, &DoubleBarrier::new(DoubleBarrierType::KnockOut, 120.0, 110.0, 0.0))
+                .unwrap_err(),
+            PricingError::InvalidParameter { name: "upper", value: 110.0 }
+        );
+        let mut weekly = knock_out;
+        weekly.monitoring = Monitoring::Discrete(0.0);
+        assert_eq!(
+            try_calculate_greeks(&params, OptionType::Call, &weekly).unwrap_err(),
+            PricingError::InvalidParameter { name: "monitoring", value: 0.0 }
+        );
+    }
+}
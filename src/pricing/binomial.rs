@@ -0,0 +1,402 @@
+//! Binomial tree pricing for European and American options
+//!
+//! Two lattices are provided: Cox-Ross-Rubinstein, the classic recombining tree
+//! with u = e^(σ√Δt), and Leisen-Reimer (1996), which matches the terminal
+//! distribution through the Peizer-Pratt inversion of N(d1) and N(d2) and
+//! converges smoothly at second order on an odd number of steps.
+//!
+//! The backward induction is generic over `Scalar`, so running it on AD numbers
+//! differentiates the tree price itself rather than bumping it.
+
+use crate::ad::{MultiDual, Scalar};
+use crate::pricing::black_scholes::{expired_greeks, BlackScholesParams};
+use crate::pricing::closed_form::{self, ClosedForm, Inputs};
+use crate::pricing::PricingError;
+use crate::types::{ExerciseStyle, Greeks, OptionType};
+
+/// Lattice construction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BinomialMethod {
+    /// Cox-Ross-Rubinstein: u = e^(σ√Δt), d = 1/u
+    CoxRossRubinstein,
+    /// Leisen-Reimer with Peizer-Pratt method 2 inversion; uses an odd number of steps
+    #[default]
+    LeisenReimer,
+}
+
+impl BinomialMethod {
+    /// Steps actually used for a requested count
+    ///
+    /// The tree Greeks need at least two steps, and Leisen-Reimer is only
+    /// defined on an odd number, so even requests are rounded up.
+    pub fn effective_steps(self, steps: usize) -> usize {
+        let steps = steps.max(2);
+        match self {
+            BinomialMethod::CoxRossRubinstein => steps,
+            BinomialMethod::LeisenReimer => steps | 1,
+        }
+    }
+}
+
+/// Gradient slots for the sensitivities taken by AD
+const VOL: usize = 0;
+const RATE: usize = 1;
+const DIVIDEND: usize = 2;
+
+/// Price an option on a binomial tree
+pub fn price(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    exercise: ExerciseStyle,
+    method: BinomialMethod,
+    steps: usize,
+) -> f64 {
+    if params.time_to_maturity <= 0.0 {
+        return expired_greeks(params, option_type).price;
+    }
+    if params.volatility == 0.0 {
+        let certain = Certain { option_type, exercise, steps: method.effective_steps(steps) };
+        return certain.price(&Inputs::constant(params));
+    }
+    Inputs::<f64>::constant(params)
+        .roll_back(option_type, exercise, method, steps)
+        .value
+}
+
+/// Calculate price and Greeks on a binomial tree
+///
+/// Delta and gamma are the finite differences of the option values on the
+/// first two time steps, and theta compares the middle node two steps in with
+/// the root. On a Leisen-Reimer tree that node is not at today's spot, so it is
+/// first moved there with the tree delta and gamma.
+///
+/// Vega, rho and phi come from the same backward induction run on a
+/// `MultiDual` with volatility, rate and dividend yield seeded, so they are the
+/// exact derivatives of the tree price. A CRR price oscillates as nodes cross
+/// the strike, and its sensitivities inherit that noise; Leisen-Reimer places
+/// the strike between nodes and gives much smoother ones.
+///
+/// With zero volatility the tree collapses onto the forward path, and the
+/// Greeks are those of its discounted intrinsic value, as in Black-Scholes.
+pub fn calculate_greeks(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    exercise: ExerciseStyle,
+    method: BinomialMethod,
+    steps: usize,
+) -> Greeks {
+    if params.time_to_maturity <= 0.0 {
+        return expired_greeks(params, option_type);
+    }
+    if params.volatility == 0.0 {
+        let certain = Certain { option_type, exercise, steps: method.effective_steps(steps) };
+        return closed_form::greeks(params, &certain);
+    }
+
+    let inputs = Inputs {
+        sigma: MultiDual::<3>::variable(params.volatility, VOL),
+        r: MultiDual::variable(params.risk_free_rate, RATE),
+        q: MultiDual::variable(params.dividend_yield, DIVIDEND),
+        ..Inputs::constant(params)
+    };
+    let tree = inputs.roll_back(option_type, exercise, method, steps);
+
+    let s = params.spot;
+    let [u, d] = [tree.up.value(), tree.down.value()];
+    let [v_d, v_u] = tree.step_one;
+    let [v_dd, v_ud, v_uu] = tree.step_two;
+    let price = tree.value.value();
+
+    let delta = (v_u - v_d) / (s * u - s * d);
+    let (s_uu, s_ud, s_dd) = (s * u * u, s * u * d, s * d * d);
+    let gamma = ((v_uu - v_ud) / (s_uu - s_ud) - (v_ud - v_dd) / (s_ud - s_dd)) / (0.5 * (s_uu - s_dd));
+    // Carry the middle node back to today's spot before comparing with the root
+    let shift = s_ud - s;
+    let theta = (v_ud - delta * shift - 0.5 * gamma * shift * shift - price) / (2.0 * tree.dt);
+
+    Greeks::new(
+        price,
+        delta,
+        gamma,
+        tree.value.partial(VOL),
+        theta,
+        tree.value.partial(RATE),
+        tree.value.partial(DIVIDEND),
+    )
+}
+
+/// Calculate price and Greeks on a binomial tree after validating the inputs
+pub fn try_calculate_greeks(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    exercise: ExerciseStyle,
+    method: BinomialMethod,
+    steps: usize,
+) -> Result<Greeks, PricingError> {
+    params.validate()?;
+    Ok(calculate_greeks(params, option_type, exercise, method, steps))
+}
+
+/// Peizer-Pratt method 2 inversion: the probability h(z) with which an n-step
+/// binomial reproduces N(z)
+fn peizer_pratt<T: Scalar>(z: T, n: usize) -> T {
+    let n = n as f64;
+    let m = n + 1.0 / 3.0 + 0.1 / (n + 1.0);
+    let scale = (n + 1.0 / 6.0).sqrt() / m;
+    if z.value() == 0.0 {
+        // h is linear through the origin
+        return z * (0.5 * scale) + 0.5;
+    }
+    let half_width = (T::constant(1.0) - (-(z / m).powi2() * (n + 1.0 / 6.0)).exp()).sqrt() * 0.5;
+    if z.value() > 0.0 {
+        half_width + 0.5
+    } else {
+        -half_width + 0.5
+    }
+}
+
+/// Outcome of the backward induction
+struct Lattice<T> {
+    value: T,
+    up: T,
+    down: T,
+    dt: f64,
+    /// Option values after one step, down node first
+    step_one: [f64; 2],
+    /// Option values after two steps, lowest node first
+    step_two: [f64; 3],
+}
+
+/// Tree without volatility: spot grows along the forward, and an American
+/// holder picks the best of the exercise dates on the tree
+struct Certain {
+    option_type: OptionType,
+    exercise: ExerciseStyle,
+    steps: usize,
+}
+
+impl ClosedForm for Certain {
+    fn price<T: Scalar>(&self, inputs: &Inputs<T>) -> T {
+        let &Inputs { s, k, t, r, q, .. } = inputs;
+        let exercised = |i: usize| {
+            let time = t * (i as f64 / self.steps as f64);
+            let forward_payoff = match self.option_type {
+                OptionType::Call => s * (-q * time).exp() - (-r * time).exp() * k,
+                OptionType::Put => (-r * time).exp() * k - s * (-q * time).exp(),
+            };
+            forward_payoff.max(T::constant(0.0))
+        };
+        let first = match self.exercise {
+            ExerciseStyle::European => self.steps,
+            ExerciseStyle::American => 0,
+        };
+        (first..self.steps).fold(exercised(self.steps), |best, i| best.max(exercised(i)))
+    }
+
+    fn at_expiry(&self, params: &BlackScholesParams) -> Greeks {
+        expired_greeks(params, self.option_type)
+    }
+}
+
+impl<T: Scalar> Inputs<T> {
+    fn payoff(&self, spot: T, option_type: OptionType) -> T {
+        let forward_payoff = match option_type {
+            OptionType::Call => spot - self.k,
+            OptionType::Put => -(spot - self.k),
+        };
+        forward_payoff.max(T::constant(0.0))
+    }
+
+    /// Up move, down move and up probability of each step
+    fn lattice(&self, method: BinomialMethod, n: usize, dt: T) -> (T, T, T) {
+        let growth = ((self.r - self.q) * dt).exp();
+        match method {
+            BinomialMethod::CoxRossRubinstein => {
+                let up = (self.sigma * dt.sqrt()).exp();
+                let down = T::constant(1.0) / up;
+                (up, down, (growth - down) / (up - down))
+            }
+            BinomialMethod::LeisenReimer => {
+                let total_vol = self.sigma * self.t.sqrt();
+                let d1 = ((self.s / self.k).ln() + (self.r - self.q + self.sigma.powi2() * 0.5) * self.t) / total_vol;
+                let d2 = d1 - total_vol;
+                let p = peizer_pratt(d2, n);
+                let p_bar = peizer_pratt(d1, n);
+                let up = growth * p_bar / p;
+                let down = (growth - p * up) / (T::constant(1.0) - p);
+                (up, down, p)
+            }
+        }
+    }
+
+    fn roll_back(&self, option_type: OptionType, exercise: ExerciseStyle, method: BinomialMethod, steps: usize) -> Lattice<T> {
+        let n = method.effective_steps(steps);
+        let dt = self.t / n as f64;
+        let (up, down, p) = self.lattice(method, n, dt);
+        let discount = (-self.r * dt).exp();
+        let (weight_up, weight_down) = (discount * p, discount * (T::constant(1.0) - p));
+
+        // Powers of the moves, so the spot at node j of step i is S u^j d^(i-j)
+        let mut up_pow = Vec::with_capacity(n + 1);
+        let mut down_pow = Vec::with_capacity(n + 1);
+        up_pow.push(T::constant(1.0));
+        down_pow.push(T::constant(1.0));
+        for j in 0..n {
+            up_pow.push(up_pow[j] * up);
+            down_pow.push(down_pow[j] * down);
+        }
+        let spot = |i: usize, j: usize| self.s * up_pow[j] * down_pow[i - j];
+
+        let mut values: Vec<T> = (0..=n).map(|j| self.payoff(spot(n, j), option_type)).collect();
+        let mut step_one = [0.0; 2];
+        let mut step_two = [0.0; 3];
+        for i in (0..n).rev() {
+            for j in 0..=i {
+                let continuation = weight_up * values[j + 1] + weight_down * values[j];
+                values[j] = match exercise {
+                    ExerciseStyle::European => continuation,
+                    ExerciseStyle::American => continuation.max(self.payoff(spot(i, j), option_type)),
+                };
+            }
+            match i {
+                2 => step_two = [values[0].value(), values[1].value(), values[2].value()],
+                1 => step_one = [values[0].value(), values[1].value()],
+                _ => {}
+            }
+        }
+
+        Lattice {
+            value: values[0],
+            up,
+            down,
+            dt: dt.value(),
+            step_one,
+            step_two,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::black_scholes;
+    use approx::assert_relative_eq;
+
+    const METHODS: [BinomialMethod; 2] = [BinomialMethod::CoxRossRubinstein, BinomialMethod::LeisenReimer];
+
+    #[test]
+    fn test_european_converges_to_black_scholes() {
+        let params = BlackScholesParams::new(100.0, 105.0, 0.75, 0.25, 0.04, 0.02);
+        for &option_type in &[OptionType::Call, OptionType::Put] {
+            let exact = black_scholes::calculate_greeks(&params, option_type);
+
+            let lr = calculate_greeks(&params, option_type, ExerciseStyle::European, BinomialMethod::LeisenReimer, 501);
+            assert_relative_eq!(lr.price, exact.price, epsilon = 1e-5);
+            assert_relative_eq!(lr.delta, exact.delta, epsilon = 1e-4);
+            assert_relative_eq!(lr.gamma, exact.gamma, epsilon = 5e-5);
+            assert_relative_eq!(lr.vega, exact.vega, epsilon = 1e-4);
+            assert_relative_eq!(lr.rho, exact.rho, epsilon = 1e-4);
+            assert_relative_eq!(lr.phi, exact.phi, epsilon = 1e-4);
+            assert_relative_eq!(lr.theta, exact.theta, epsilon = 5e-3);
+
+            let crr =
+                calculate_greeks(&params, option_type, ExerciseStyle::European, BinomialMethod::CoxRossRubinstein, 2000);
+            assert_relative_eq!(crr.price, exact.price, epsilon = 5e-3);
+            assert_relative_eq!(crr.delta, exact.delta, epsilon = 1e-4);
+            assert_relative_eq!(crr.gamma, exact.gamma, epsilon = 1e-4);
+            assert_relative_eq!(crr.theta, exact.theta, epsilon = 5e-3);
+            assert_relative_eq!(crr.vega, exact.vega, max_relative = 1e-2);
+            assert_relative_eq!(crr.rho, exact.rho, epsilon = 5e-3);
+        }
+    }
+
+    #[test]
+    fn test_american_put_reference() {
+        // S = K = 100, T = 1, σ = 20%, r = 5%: 6.0904 from high-resolution lattices
+        let params = BlackScholesParams::new(100.0, 100.0, 1.0, 0.2, 0.05, 0.0);
+        let european = black_scholes::calculate_greeks(&params, OptionType::Put).price;
+        for &method in &METHODS {
+            let american = price(&params, OptionType::Put, ExerciseStyle::American, method, 2001);
+            assert_relative_eq!(american, 6.0904, epsilon = 2e-3);
+            assert!(american > european + 0.5);
+        }
+    }
+
+    #[test]
+    fn test_american_call_without_dividends_is_european() {
+        let params = BlackScholesParams::new(100.0, 95.0, 0.5, 0.3, 0.03, 0.0);
+        for &method in &METHODS {
+            let american = calculate_greeks(&params, OptionType::Call, ExerciseStyle::American, method, 301);
+            let european = calculate_greeks(&params, OptionType::Call, ExerciseStyle::European, method, 301);
+            assert_relative_eq!(american.price, european.price, epsilon = 1e-12);
+            assert_relative_eq!(american.vega, european.vega, epsilon = 1e-12);
+        }
+
+        // With a dividend yield early exercise of a deep call is worth something
+        let params = BlackScholesParams { dividend_yield: 0.08, strike: 70.0, ..params };
+        let american = price(&params, OptionType::Call, ExerciseStyle::American, BinomialMethod::LeisenReimer, 301);
+        let european = price(&params, OptionType::Call, ExerciseStyle::European, BinomialMethod::LeisenReimer, 301);
+        assert!(american > european);
+    }
+
+    #[test]
+    fn test_ad_sensitivities_match_bumped_tree() {
+        let params = BlackScholesParams::new(100.0, 110.0, 1.0, 0.25, 0.05, 0.01);
+        let bump = 1e-6;
+        for &method in &METHODS {
+            let greeks = calculate_greeks(&params, OptionType::Put, ExerciseStyle::American, method, 201);
+            let bumped = |p: BlackScholesParams| {
+                let up = price(&p, OptionType::Put, ExerciseStyle::American, method, 201);
+                (up - greeks.price) / bump
+            };
+            let vega = bumped(BlackScholesParams { volatility: params.volatility + bump, ..params });
+            let rho = bumped(BlackScholesParams { risk_free_rate: params.risk_free_rate + bump, ..params });
+            let phi = bumped(BlackScholesParams { dividend_yield: params.dividend_yield + bump, ..params });
+            assert_relative_eq!(greeks.vega, vega, epsilon = 1e-3);
+            assert_relative_eq!(greeks.rho, rho, epsilon = 1e-3);
+            assert_relative_eq!(greeks.phi, phi, epsilon = 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_zero_volatility_is_deterministic() {
+        let params = BlackScholesParams::new(100.0, 90.0, 1.0, 0.0, 0.05, 0.02);
+        for &method in &METHODS {
+            for &option_type in &[OptionType::Call, OptionType::Put] {
+                let exact = black_scholes::calculate_greeks(&params, option_type);
+                let tree = calculate_greeks(&params, option_type, ExerciseStyle::European, method, 101);
+                assert_relative_eq!(tree.price, exact.price, epsilon = 1e-12);
+                assert_relative_eq!(tree.delta, exact.delta, epsilon = 1e-12);
+                assert_relative_eq!(tree.gamma, exact.gamma, epsilon = 1e-12);
+                assert_relative_eq!(tree.theta, exact.theta, epsilon = 1e-12);
+                assert_relative_eq!(tree.rho, exact.rho, epsilon = 1e-12);
+                assert_relative_eq!(tree.phi, exact.phi, epsilon = 1e-12);
+            }
+
+            // A deep put is worth more exercised today than held to expiry
+            let deep = BlackScholesParams { strike: 150.0, ..params };
+            let american = price(&deep, OptionType::Put, ExerciseStyle::American, method, 101);
+            assert_relative_eq!(american, 50.0, epsilon = 1e-12);
+            assert!(price(&deep, OptionType::Put, ExerciseStyle::European, method, 101) < american);
+        }
+    }
+
+    #[test]
+    fn test_steps_and_expiry() {
+        assert_eq!(BinomialMethod::LeisenReimer.effective_steps(100), 101);
+        assert_eq!(BinomialMethod::LeisenReimer.effective_steps(0), 3);
+        assert_eq!(BinomialMethod::CoxRossRubinstein.effective_steps(1), 2);
+
+        let params = BlackScholesParams::new(90.0, 100.0, 0.0, 0.2, 0.05, 0.0);
+        let greeks = calculate_greeks(&params, OptionType::Put, ExerciseStyle::American, BinomialMethod::LeisenReimer, 51);
+        assert_eq!(greeks.price, 10.0);
+        assert_eq!(greeks.delta, -1.0);
+
+        let invalid = BlackScholesParams { volatility: -0.1, ..params };
+        assert_eq!(
+            try_calculate_greeks(&invalid, OptionType::Put, ExerciseStyle::American, BinomialMethod::LeisenReimer, 51)
+                .unwrap_err(),
+            PricingError::NonPositiveVolatility(-0.1)
+        );
+    }
+}
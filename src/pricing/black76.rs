@@ -0,0 +1,279 @@
+//! Black-76 pricing of options on futures and forwards with automatic differentiation for Greeks
+
+use crate::ad::{norm_cdf, Scalar};
+use crate::pricing::black_scholes::{intrinsic_greeks, BlackScholesParams};
+use crate::pricing::closed_form::{self, ClosedForm, Inputs};
+use crate::pricing::PricingError;
+use crate::types::{Greeks, OptionType};
+
+/// Black-76 pricing parameters
+///
+/// The underlying is the forward (or futures) price for the option's expiry, so
+/// no carry or dividend enters the model; `discount_rate` only discounts the payoff.
+#[derive(Debug, Clone, Copy)]
+pub struct Black76Params {
+    pub forward: f64,
+    pub strike: f64,
+    pub t: f64,
+    pub vol: f64,
+    pub discount_rate: f64,
+}
+
+impl Black76Params {
+    pub fn new(forward: f64, strike: f64, t: f64, vol: f64, discount_rate: f64) -> Self {
+        Self {
+            forward,
+            strike,
+            t,
+            vol,
+            discount_rate,
+        }
+    }
+
+    /// Create parameters, rejecting inputs the model cannot price
+    pub fn try_new(forward: f64, strike: f64, t: f64, vol: f64, discount_rate: f64) -> Result<Self, PricingError> {
+        let params = Self::new(forward, strike, t, vol, discount_rate);
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Check that every input is finite and within the model's domain
+    ///
+    /// Zero time to maturity is accepted and prices at discounted intrinsic value.
+    pub fn validate(&self) -> Result<(), PricingError> {
+        let fields = [
+            ("forward", self.forward),
+            ("strike", self.strike),
+            ("t", self.t),
+            ("vol", self.vol),
+            ("discount_rate", self.discount_rate),
+        ];
+        if let Some(&(name, _)) = fields.iter().find(|(_, v)| !v.is_finite()) {
+            return Err(PricingError::NonFinite(name));
+        }
+
+        if self.forward <= 0.0 {
+            return Err(PricingError::NonPositiveForward(self.forward));
+        }
+        if self.strike <= 0.0 {
+            return Err(PricingError::NonPositiveStrike(self.strike));
+        }
+        if self.t < 0.0 {
+            return Err(PricingError::NegativeMaturity(self.t));
+        }
+        if self.vol <= 0.0 {
+            return Err(PricingError::NonPositiveVolatility(self.vol));
+        }
+
+        Ok(())
+    }
+}
+
+/// Undiscounted Black value of a call or put on the forward
+///
+/// With zero volatility or zero time the payoff is certain and equals the
+/// intrinsic value of the forward.
+#[inline]
+fn undiscounted_price<T: Scalar>(f: T, k: f64, t: T, sigma: T, option_type: OptionType) -> T {
+    let zero = T::constant(0.0);
+    if sigma.value() == 0.0 || t.value() == 0.0 {
+        return match option_type {
+            OptionType::Call => (f - k).max(zero),
+            OptionType::Put => (-(f - k)).max(zero),
+        };
+    }
+
+    let total_vol = sigma * t.sqrt();
+    let d1 = (f / k).ln() / total_vol + total_vol * 0.5;
+    let d2 = d1 - total_vol;
+    match option_type {
+        OptionType::Call => f * norm_cdf(d1) - norm_cdf(d2) * k,
+        OptionType::Put => norm_cdf(-d2) * k - f * norm_cdf(-d1),
+    }
+}
+
+/// Price a European call on a forward: e^(-rT) [F N(d1) - K N(d2)]
+///
+/// Generic over `Scalar`, so seeding any input on an AD number type yields its sensitivity.
+#[inline]
+pub fn call_price<T: Scalar>(f: T, k: f64, t: T, sigma: T, r: T) -> T {
+    (-r * t).exp() * undiscounted_price(f, k, t, sigma, OptionType::Call)
+}
+
+/// Price a European put on a forward: e^(-rT) [K N(-d2) - F N(-d1)]
+///
+/// Generic over `Scalar`, so seeding any input on an AD number type yields its sensitivity.
+#[inline]
+pub fn put_price<T: Scalar>(f: T, k: f64, t: T, sigma: T, r: T) -> T {
+    (-r * t).exp() * undiscounted_price(f, k, t, sigma, OptionType::Put)
+}
+
+/// Calculate option price and all Greeks using automatic differentiation
+///
+/// Delta and gamma are taken with respect to the forward. Phi is always zero
+/// since the model has no dividend yield.
+pub fn calculate_greeks(params: &Black76Params, option_type: OptionType) -> Greeks {
+    greeks(params, option_type, Settlement::PremiumPaid)
+}
+
+/// Calculate price and Greeks of a futures-style margined option
+///
+/// The premium is settled through daily variation margin rather than paid up
+/// front, so the option is worth its undiscounted Black value: `discount_rate`
+/// is ignored and rho is zero.
+pub fn calculate_futures_style_greeks(params: &Black76Params, option_type: OptionType) -> Greeks {
+    greeks(params, option_type, Settlement::FuturesStyle)
+}
+
+/// How the option premium is settled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Settlement {
+    /// Paid up front, so the payoff is discounted to today
+    PremiumPaid,
+    /// Margined daily like the future itself, so nothing is discounted
+    FuturesStyle,
+}
+
+fn greeks(params: &Black76Params, option_type: OptionType, settlement: Settlement) -> Greeks {
+    let contract = (option_type, settlement);
+    closed_form::forward_greeks(params.forward, params.strike, params.t, params.vol, params.discount_rate, &contract)
+}
+
+impl ClosedForm for (OptionType, Settlement) {
+    #[inline]
+    fn price<T: Scalar>(&self, inputs: &Inputs<T>) -> T {
+        let &Inputs { s: f, k, t, sigma, r, .. } = inputs;
+        // A futures-style option is not discounted, so the rate is not an input at all
+        match self {
+            (OptionType::Call, Settlement::PremiumPaid) => call_price(f, k, t, sigma, r),
+            (OptionType::Put, Settlement::PremiumPaid) => put_price(f, k, t, sigma, r),
+            (option_type, Settlement::FuturesStyle) => undiscounted_price(f, k, t, sigma, *option_type),
+        }
+    }
+
+    /// Intrinsic value of the forward, which sits in the spot slot
+    fn at_expiry(&self, params: &BlackScholesParams) -> Greeks {
+        intrinsic_greeks(params.spot, params.strike, self.0)
+    }
+}
+
+/// Calculate option price and all Greeks after validating the inputs
+pub fn try_calculate_greeks(params: &Black76Params, option_type: OptionType) -> Result<Greeks, PricingError> {
+    params.validate()?;
+    Ok(calculate_greeks(params, option_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ad::norm_pdf;
+    use crate::pricing::black_scholes::{self, BlackScholesParams};
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_greeks_match_closed_form() {
+        let cases = [
+            Black76Params::new(100.0, 100.0, 1.0, 0.2, 0.05),
+            Black76Params::new(80.0, 95.0, 0.25, 0.45, 0.02),
+            Black76Params::new(3.2, 2.9, 2.0, 0.3, 0.04),
+        ];
+
+        for params in cases.iter() {
+            let Black76Params { forward: f, strike: k, t, vol, discount_rate: r } = *params;
+            let total_vol = vol * t.sqrt();
+            let d1 = (f / k).ln() / total_vol + 0.5 * total_vol;
+            let d2 = d1 - total_vol;
+            let df = (-r * t).exp();
+
+            for &option_type in &[OptionType::Call, OptionType::Put] {
+                let greeks = calculate_greeks(params, option_type);
+                let (price, delta) = match option_type {
+                    OptionType::Call => (df * (f * norm_cdf(d1) - k * norm_cdf(d2)), df * norm_cdf(d1)),
+                    OptionType::Put => (df * (k * norm_cdf(-d2) - f * norm_cdf(-d1)), -df * norm_cdf(-d1)),
+                };
+                let gamma = df * norm_pdf(d1) / (f * total_vol);
+                let vega = df * f * norm_pdf(d1) * t.sqrt();
+                let theta = -df * f * norm_pdf(d1) * vol / (2.0 * t.sqrt()) + r * price;
+
+                assert_relative_eq!(greeks.price, price, epsilon = 1e-12);
+                assert_relative_eq!(greeks.delta, delta, epsilon = 1e-12);
+                assert_relative_eq!(greeks.gamma, gamma, epsilon = 1e-12);
+                assert_relative_eq!(greeks.vega, vega, epsilon = 1e-12);
+                assert_relative_eq!(greeks.theta, theta, epsilon = 1e-12);
+                assert_relative_eq!(greeks.rho, -t * price, epsilon = 1e-12);
+                assert_eq!(greeks.phi, 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_black_scholes_on_the_forward() {
+        // Black-Scholes with carry r - q is Black-76 on F = S e^((r-q)T)
+        let bs = BlackScholesParams::new(100.0, 105.0, 0.75, 0.25, 0.04, 0.01);
+        let forward = bs.spot * ((bs.risk_free_rate - bs.dividend_yield) * bs.time_to_maturity).exp();
+        let params = Black76Params::new(forward, bs.strike, bs.time_to_maturity, bs.volatility, bs.risk_free_rate);
+
+        for &option_type in &[OptionType::Call, OptionType::Put] {
+            let spot_greeks = black_scholes::calculate_greeks(&bs, option_type);
+            let greeks = calculate_greeks(&params, option_type);
+            let dforward_dspot = forward / bs.spot;
+
+            assert_relative_eq!(greeks.price, spot_greeks.price, epsilon = 1e-12);
+            assert_relative_eq!(greeks.delta * dforward_dspot, spot_greeks.delta, epsilon = 1e-12);
+            assert_relative_eq!(greeks.gamma * dforward_dspot * dforward_dspot, spot_greeks.gamma, epsilon = 1e-12);
+            assert_relative_eq!(greeks.vega, spot_greeks.vega, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_futures_style_is_undiscounted() {
+        let params = Black76Params::new(100.0, 90.0, 1.5, 0.3, 0.05);
+        let call = calculate_futures_style_greeks(&params, OptionType::Call);
+        let put = calculate_futures_style_greeks(&params, OptionType::Put);
+        let discount = (-0.05_f64 * 1.5).exp();
+
+        // Undiscounted parity C - P = F - K, and no sensitivity to rates
+        assert_relative_eq!(call.price - put.price, 10.0, epsilon = 1e-12);
+        assert_relative_eq!(call.delta - put.delta, 1.0, epsilon = 1e-12);
+        assert_eq!(call.rho, 0.0);
+
+        let paid = calculate_greeks(&params, OptionType::Call);
+        assert_relative_eq!(paid.price, discount * call.price, epsilon = 1e-12);
+        assert_relative_eq!(paid.gamma, discount * call.gamma, epsilon = 1e-12);
+        assert_relative_eq!(paid.vega, discount * call.vega, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_expired_and_zero_vol() {
+        let expired = Black76Params::new(105.0, 100.0, 0.0, 0.2, 0.05);
+        let call = calculate_greeks(&expired, OptionType::Call);
+        assert_relative_eq!(call.price, 5.0);
+        assert_relative_eq!(call.delta, 1.0);
+        assert_eq!(call.gamma, 0.0);
+
+        let certain = Black76Params::new(105.0, 100.0, 2.0, 0.0, 0.05);
+        let call = calculate_greeks(&certain, OptionType::Call);
+        assert_relative_eq!(call.price, 5.0 * (-0.1_f64).exp(), epsilon = 1e-12);
+        assert_relative_eq!(call.delta, (-0.1_f64).exp(), epsilon = 1e-12);
+        assert_eq!(calculate_greeks(&certain, OptionType::Put).price, 0.0);
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        assert!(Black76Params::try_new(100.0, 100.0, 1.0, 0.2, 0.05).is_ok());
+        assert_eq!(
+            Black76Params::try_new(0.0, 100.0, 1.0, 0.2, 0.05).unwrap_err(),
+            PricingError::NonPositiveForward(0.0)
+        );
+        assert_eq!(
+            Black76Params::try_new(100.0, 100.0, -1.0, 0.2, 0.05).unwrap_err(),
+            PricingError::NegativeMaturity(-1.0)
+        );
+
+        let bad = Black76Params::new(100.0, 100.0, 1.0, 0.2, f64::NAN);
+        assert_eq!(
+            try_calculate_greeks(&bad, OptionType::Put).unwrap_err(),
+            PricingError::NonFinite("discount_rate")
+        );
+    }
+}
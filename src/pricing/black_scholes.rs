@@ -1,7 +1,9 @@
 //! Black-Scholes option pricing with automatic differentiation for Greeks
 
-use crate::ad::{norm_cdf, Dual};
-use crate::types::{Greeks, OptionType};
+use crate::ad::{norm_cdf, norm_pdf, Dual, HyperDual, Scalar};
+use crate::pricing::closed_form::{self, ClosedForm, Inputs};
+use crate::pricing::PricingError;
+use crate::types::{Greeks, HigherOrderGreeks, OptionType};
 
 /// Black-Scholes pricing parameters
 #[derive(Debug, Clone, Copy)]
@@ -32,195 +34,262 @@ impl BlackScholesParams {
             dividend_yield,
         }
     }
+
+    /// Create parameters, rejecting inputs the model cannot price
+    pub fn try_new(
+        spot: f64,
+        strike: f64,
+        time_to_maturity: f64,
+        volatility: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+    ) -> Result<Self, PricingError> {
+        let params = Self::new(spot, strike, time_to_maturity, volatility, risk_free_rate, dividend_yield);
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Check that every input is finite and within the model's domain
+    ///
+    /// Zero time to maturity is accepted and prices at intrinsic value.
+    pub fn validate(&self) -> Result<(), PricingError> {
+        let fields = [
+            ("spot", self.spot),
+            ("strike", self.strike),
+            ("time_to_maturity", self.time_to_maturity),
+            ("volatility", self.volatility),
+            ("risk_free_rate", self.risk_free_rate),
+            ("dividend_yield", self.dividend_yield),
+        ];
+        if let Some(&(name, _)) = fields.iter().find(|(_, v)| !v.is_finite()) {
+            return Err(PricingError::NonFinite(name));
+        }
+
+        if self.spot <= 0.0 {
+            return Err(PricingError::NonPositiveSpot(self.spot));
+        }
+        if self.strike <= 0.0 {
+            return Err(PricingError::NonPositiveStrike(self.strike));
+        }
+        if self.time_to_maturity < 0.0 {
+            return Err(PricingError::NegativeMaturity(self.time_to_maturity));
+        }
+        if self.volatility <= 0.0 {
+            return Err(PricingError::NonPositiveVolatility(self.volatility));
+        }
+
+        Ok(())
+    }
 }
 
 /// Calculate d1 parameter for Black-Scholes
 #[inline]
-fn d1(s: Dual, k: f64, t: f64, sigma: Dual, r: f64, q: f64) -> Dual {
-    let numerator = (s / k).ln() + Dual::constant((r - q + 0.5 * sigma.value * sigma.value) * t);
-    let denominator = sigma * Dual::constant(t.sqrt());
+fn d1<T: Scalar>(s: T, k: f64, t: T, sigma: T, r: T, q: T) -> T {
+    let numerator = (s / k).ln() + (r - q + sigma.powi2() * 0.5) * t;
+    let denominator = sigma * t.sqrt();
     numerator / denominator
 }
 
 /// Calculate d2 parameter for Black-Scholes
 #[inline]
-fn d2(d1: Dual, sigma: Dual, t: f64) -> Dual {
-    d1 - sigma * Dual::constant(t.sqrt())
+fn d2<T: Scalar>(d1: T, sigma: T, t: T) -> T {
+    d1 - sigma * t.sqrt()
+}
+
+/// Price of a forward contract settled in cash at the strike, valued with
+/// certainty: S e^(-qT) - K e^(-rT)
+#[inline]
+fn discounted_forward_payoff<T: Scalar>(s: T, k: f64, t: T, r: T, q: T) -> T {
+    s * (-q * t).exp() - (-r * t).exp() * k
 }
 
 /// Price a European call option using Black-Scholes
+///
+/// Generic over `Scalar`, so seeding any input on an AD number type yields its sensitivity.
+/// With zero volatility or zero time the outcome is certain and the price is the
+/// discounted intrinsic value of the forward, rather than a division by zero in d1.
 #[inline]
-fn call_price(s: Dual, k: f64, t: f64, sigma: Dual, r: f64, q: f64) -> Dual {
+pub fn call_price<T: Scalar>(s: T, k: f64, t: T, sigma: T, r: T, q: T) -> T {
+    if sigma.value() == 0.0 || t.value() == 0.0 {
+        return discounted_forward_payoff(s, k, t, r, q).max(T::constant(0.0));
+    }
+
     let d1_val = d1(s, k, t, sigma, r, q);
     let d2_val = d2(d1_val, sigma, t);
     
-    let discount_factor = Dual::constant((-r * t).exp());
-    let forward_discount = Dual::constant((-q * t).exp());
+    let discount_factor = (-r * t).exp();
+    let forward_discount = (-q * t).exp();
     
-    s * forward_discount * norm_cdf(d1_val) - Dual::constant(k) * discount_factor * norm_cdf(d2_val)
+    s * forward_discount * norm_cdf(d1_val) - discount_factor * norm_cdf(d2_val) * k
 }
 
 /// Price a European put option using Black-Scholes
+///
+/// Generic over `Scalar`, so seeding any input on an AD number type yields its sensitivity.
+/// Zero volatility or zero time are handled as in `call_price`.
 #[inline]
-fn put_price(s: Dual, k: f64, t: f64, sigma: Dual, r: f64, q: f64) -> Dual {
+pub fn put_price<T: Scalar>(s: T, k: f64, t: T, sigma: T, r: T, q: T) -> T {
+    if sigma.value() == 0.0 || t.value() == 0.0 {
+        return (-discounted_forward_payoff(s, k, t, r, q)).max(T::constant(0.0));
+    }
+
     let d1_val = d1(s, k, t, sigma, r, q);
     let d2_val = d2(d1_val, sigma, t);
     
-    let discount_factor = Dual::constant((-r * t).exp());
-    let forward_discount = Dual::constant((-q * t).exp());
+    let discount_factor = (-r * t).exp();
+    let forward_discount = (-q * t).exp();
     
-    Dual::constant(k) * discount_factor * norm_cdf(-d2_val) - s * forward_discount * norm_cdf(-d1_val)
+    discount_factor * norm_cdf(-d2_val) * k - s * forward_discount * norm_cdf(-d1_val)
 }
 
 /// Calculate option price and all Greeks using automatic differentiation
+///
+/// Spot, volatility, time, rate and dividend yield are seeded together on a
+/// `MultiDual`, so price, delta, vega, theta, rho and phi all come out of one
+/// evaluation. Gamma is exact, from a second pass with spot seeded on a `HyperDual`.
+///
+/// Theta is the instantaneous time decay, so it stays exact however little time
+/// is left. An expired option (`time_to_maturity <= 0`) is worth its intrinsic
+/// value; see `expired_greeks`.
 pub fn calculate_greeks(params: &BlackScholesParams, option_type: OptionType) -> Greeks {
-    let BlackScholesParams {
-        spot,
-        strike,
-        time_to_maturity,
-        volatility,
-        risk_free_rate,
-        dividend_yield,
-    } = *params;
-
-    // Calculate Delta: derivative with respect to spot
-    let s_dual = Dual::variable(spot);
-    let sigma_const = Dual::constant(volatility);
-    let price_for_delta = match option_type {
-        OptionType::Call => call_price(s_dual, strike, time_to_maturity, sigma_const, risk_free_rate, dividend_yield),
-        OptionType::Put => put_price(s_dual, strike, time_to_maturity, sigma_const, risk_free_rate, dividend_yield),
-    };
-    let price = price_for_delta.value;
-    let delta = price_for_delta.deriv;
+    closed_form::greeks(params, &option_type)
+}
 
-    // Calculate Gamma: second derivative with respect to spot using finite difference
-    let ds = 0.01; // Small bump for finite difference
-    let s_up = Dual::variable(spot + ds);
-    let s_down = Dual::variable(spot - ds);
-    
-    let delta_up = match option_type {
-        OptionType::Call => call_price(s_up, strike, time_to_maturity, sigma_const, risk_free_rate, dividend_yield).deriv,
-        OptionType::Put => put_price(s_up, strike, time_to_maturity, sigma_const, risk_free_rate, dividend_yield).deriv,
-    };
-    
-    let delta_down = match option_type {
-        OptionType::Call => call_price(s_down, strike, time_to_maturity, sigma_const, risk_free_rate, dividend_yield).deriv,
-        OptionType::Put => put_price(s_down, strike, time_to_maturity, sigma_const, risk_free_rate, dividend_yield).deriv,
+/// Calculate option price and all Greeks after validating the inputs
+pub fn try_calculate_greeks(params: &BlackScholesParams, option_type: OptionType) -> Result<Greeks, PricingError> {
+    params.validate()?;
+    Ok(calculate_greeks(params, option_type))
+}
+
+/// Greeks of an option at expiry
+///
+/// The price is the intrinsic value and delta is the payoff slope, taking the
+/// limit of N(d1) at the strike, 0.5. Every other sensitivity vanishes because
+/// no time value remains.
+pub(crate) fn expired_greeks(params: &BlackScholesParams, option_type: OptionType) -> Greeks {
+    intrinsic_greeks(params.spot, params.strike, option_type)
+}
+
+/// Intrinsic value of a payoff on `underlying` with its slope as delta, as in
+/// `expired_greeks`; shared by the models quoted on a forward
+pub(crate) fn intrinsic_greeks(underlying: f64, strike: f64, option_type: OptionType) -> Greeks {
+    let moneyness = underlying - strike;
+    let call_delta = if moneyness > 0.0 {
+        1.0
+    } else if moneyness < 0.0 {
+        0.0
+    } else {
+        0.5
     };
-    
-    let gamma = (delta_up - delta_down) / (2.0 * ds);
-
-    // Calculate Vega: derivative with respect to volatility
-    let s_const = Dual::constant(spot);
-    let sigma_dual = Dual::variable(volatility);
-    let price_for_vega = match option_type {
-        OptionType::Call => call_price(s_const, strike, time_to_maturity, sigma_dual, risk_free_rate, dividend_yield),
-        OptionType::Put => put_price(s_const, strike, time_to_maturity, sigma_dual, risk_free_rate, dividend_yield),
+
+    let (price, delta) = match option_type {
+        OptionType::Call => (moneyness.max(0.0), call_delta),
+        OptionType::Put => ((-moneyness).max(0.0), call_delta - 1.0),
     };
-    let vega = price_for_vega.deriv;
 
-    // Calculate Theta: derivative with respect to time (negative for time decay)
-    let theta = calculate_theta(params, option_type);
+    Greeks::new(price, delta, 0.0, 0.0, 0.0, 0.0, 0.0)
+}
 
-    // Calculate Rho: derivative with respect to risk-free rate
-    let rho = calculate_rho(params, option_type);
+impl ClosedForm for OptionType {
+    #[inline]
+    fn price<T: Scalar>(&self, inputs: &Inputs<T>) -> T {
+        let &Inputs { s, k, t, sigma, r, q, .. } = inputs;
+        match self {
+            OptionType::Call => call_price(s, k, t, sigma, r, q),
+            OptionType::Put => put_price(s, k, t, sigma, r, q),
+        }
+    }
 
-    Greeks::new(price, delta, gamma, vega, theta, rho)
+    fn at_expiry(&self, params: &BlackScholesParams) -> Greeks {
+        expired_greeks(params, *self)
+    }
 }
 
-/// Calculate Theta using finite difference (small time step)
-fn calculate_theta(params: &BlackScholesParams, option_type: OptionType) -> f64 {
-    let dt = 1.0 / 365.0; // One day
-    
-    let price_now = match option_type {
-        OptionType::Call => call_price(
-            Dual::constant(params.spot),
-            params.strike,
-            params.time_to_maturity,
-            Dual::constant(params.volatility),
-            params.risk_free_rate,
-            params.dividend_yield,
-        ).value,
-        OptionType::Put => put_price(
-            Dual::constant(params.spot),
-            params.strike,
-            params.time_to_maturity,
-            Dual::constant(params.volatility),
-            params.risk_free_rate,
-            params.dividend_yield,
-        ).value,
-    };
-    
-    let price_later = match option_type {
-        OptionType::Call => call_price(
-            Dual::constant(params.spot),
-            params.strike,
-            params.time_to_maturity - dt,
-            Dual::constant(params.volatility),
-            params.risk_free_rate,
-            params.dividend_yield,
-        ).value,
-        OptionType::Put => put_price(
-            Dual::constant(params.spot),
-            params.strike,
-            params.time_to_maturity - dt,
-            Dual::constant(params.volatility),
-            params.risk_free_rate,
-            params.dividend_yield,
-        ).value,
-    };
-    
-    (price_later - price_now) / dt
+impl<T: Scalar> Inputs<T> {
+    #[inline]
+    fn d1(&self) -> T {
+        d1(self.s, self.k, self.t, self.sigma, self.r, self.q)
+    }
+
+    /// Analytic delta: e^(-qT) N(d1) for calls, -e^(-qT) N(-d1) for puts
+    #[inline]
+    fn delta(&self, option_type: OptionType) -> T {
+        let d1 = self.d1();
+        let dividend_discount = (-self.q * self.t).exp();
+        match option_type {
+            OptionType::Call => dividend_discount * norm_cdf(d1),
+            OptionType::Put => -(dividend_discount * norm_cdf(-d1)),
+        }
+    }
+
+    /// Analytic gamma: e^(-qT) φ(d1) / (S σ √T)
+    #[inline]
+    fn gamma(&self) -> T {
+        let d1 = self.d1();
+        (-self.q * self.t).exp() * norm_pdf(d1) / (self.s * self.sigma * self.t.sqrt())
+    }
+
+    /// Analytic vega: S e^(-qT) φ(d1) √T
+    #[inline]
+    fn vega(&self) -> T {
+        let d1 = self.d1();
+        self.s * (-self.q * self.t).exp() * norm_pdf(d1) * self.t.sqrt()
+    }
+
+    /// Analytic volga: vega d1 d2 / σ
+    #[inline]
+    fn volga(&self) -> T {
+        let d1 = self.d1();
+        let d2 = d2(d1, self.sigma, self.t);
+        self.vega() * d1 * d2 / self.sigma
+    }
 }
 
-/// Calculate Rho using finite difference (small rate change)
-fn calculate_rho(params: &BlackScholesParams, option_type: OptionType) -> f64 {
-    let dr = 0.0001; // 1 basis point
-    
-    let price_base = match option_type {
-        OptionType::Call => call_price(
-            Dual::constant(params.spot),
-            params.strike,
-            params.time_to_maturity,
-            Dual::constant(params.volatility),
-            params.risk_free_rate,
-            params.dividend_yield,
-        ).value,
-        OptionType::Put => put_price(
-            Dual::constant(params.spot),
-            params.strike,
-            params.time_to_maturity,
-            Dual::constant(params.volatility),
-            params.risk_free_rate,
-            params.dividend_yield,
-        ).value,
-    };
-    
-    let price_bumped = match option_type {
-        OptionType::Call => call_price(
-            Dual::constant(params.spot),
-            params.strike,
-            params.time_to_maturity,
-            Dual::constant(params.volatility),
-            params.risk_free_rate + dr,
-            params.dividend_yield,
-        ).value,
-        OptionType::Put => put_price(
-            Dual::constant(params.spot),
-            params.strike,
-            params.time_to_maturity,
-            Dual::constant(params.volatility),
-            params.risk_free_rate + dr,
-            params.dividend_yield,
-        ).value,
-    };
-    
-    (price_bumped - price_base) / dr
+/// Calculate second- and third-order Greeks
+///
+/// Vanna and volga are second derivatives of the price taken with hyper-dual
+/// numbers. The remaining Greeks differentiate the analytic expression of a
+/// lower-order Greek with dual numbers, so no finite-difference bumps are used.
+pub fn calculate_higher_order_greeks(params: &BlackScholesParams, option_type: OptionType) -> HigherOrderGreeks {
+    // No optionality remains without time or volatility
+    if params.time_to_maturity <= 0.0 || params.volatility == 0.0 {
+        return HigherOrderGreeks::default();
+    }
+
+    let hyper = Inputs::<HyperDual>::constant(params);
+    let vanna = option_type
+        .price(&Inputs {
+            s: HyperDual::variable1(params.spot),
+            sigma: HyperDual::variable2(params.volatility),
+            ..hyper
+        })
+        .second_deriv();
+    let volga = option_type
+        .price(&Inputs { sigma: HyperDual::variable(params.volatility), ..hyper })
+        .second_deriv();
+
+    let base = Inputs::<Dual>::constant(params);
+    let wrt_spot = Inputs { s: Dual::variable(params.spot), ..base };
+    let wrt_vol = Inputs { sigma: Dual::variable(params.volatility), ..base };
+    let wrt_time = Inputs { t: Dual::variable(params.time_to_maturity), ..base };
+
+    // Calendar time runs opposite to time to maturity, hence the sign flips
+    HigherOrderGreeks {
+        vanna,
+        volga,
+        charm: -wrt_time.delta(option_type).deriv,
+        veta: -wrt_time.vega().deriv,
+        speed: wrt_spot.gamma().deriv,
+        zomma: wrt_vol.gamma().deriv,
+        color: -wrt_time.gamma().deriv,
+        ultima: wrt_vol.volga().deriv,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ad::MultiDual;
     use approx::assert_relative_eq;
 
     #[test]
@@ -236,8 +305,8 @@ mod tests {
 
         let greeks = calculate_greeks(&params, OptionType::Call);
         
-        // ATM call should have delta around 0.5
-        assert!(greeks.delta > 0.4 && greeks.delta < 0.6);
+        // ATM call delta is N(d1) with d1 = (r + σ²/2)√T / σ = 0.35
+        assert_relative_eq!(greeks.delta, 0.6368306511756191, epsilon = 1e-15);
         
         // Gamma should be positive
         assert!(greeks.gamma > 0.0);
@@ -262,6 +331,270 @@ mod tests {
         let parity_lhs = call_greeks.price - put_greeks.price;
         let parity_rhs = params.spot - params.strike * (-params.risk_free_rate * params.time_to_maturity).exp();
         
-        assert_relative_eq!(parity_lhs, parity_rhs, epsilon = 1e-6);
+        assert_relative_eq!(parity_lhs, parity_rhs, epsilon = 1e-12);
+    }
+
+    /// Closed-form Black-Scholes-Merton higher-order Greeks for cross-checking
+    fn closed_form_higher_order(params: &BlackScholesParams, option_type: OptionType) -> HigherOrderGreeks {
+        let BlackScholesParams {
+            spot: s,
+            strike: k,
+            time_to_maturity: t,
+            volatility: sigma,
+            risk_free_rate: r,
+            dividend_yield: q,
+        } = *params;
+
+        let sqrt_t = t.sqrt();
+        let d1 = ((s / k).ln() + (r - q + 0.5 * sigma * sigma) * t) / (sigma * sqrt_t);
+        let d2 = d1 - sigma * sqrt_t;
+        let pdf = (-0.5 * d1 * d1).exp() / (2.0 * std::f64::consts::PI).sqrt();
+        let df_q = (-q * t).exp();
+
+        let gamma = df_q * pdf / (s * sigma * sqrt_t);
+        let vega = s * df_q * pdf * sqrt_t;
+        let volga = vega * d1 * d2 / sigma;
+
+        let charm_common = df_q * pdf * (2.0 * (r - q) * t - d2 * sigma * sqrt_t) / (2.0 * t * sigma * sqrt_t);
+        let charm = match option_type {
+            OptionType::Call => q * df_q * norm_cdf(Dual::constant(d1)).value - charm_common,
+            OptionType::Put => -q * df_q * norm_cdf(Dual::constant(-d1)).value - charm_common,
+        };
+
+        HigherOrderGreeks {
+            vanna: -df_q * pdf * d2 / sigma,
+            volga,
+            charm,
+            veta: vega * (q + (r - q) * d1 / (sigma * sqrt_t) - (1.0 + d1 * d2) / (2.0 * t)),
+            speed: -gamma / s * (d1 / (sigma * sqrt_t) + 1.0),
+            zomma: gamma * (d1 * d2 - 1.0) / sigma,
+            color: df_q * pdf / (2.0 * s * t * sigma * sqrt_t)
+                * (2.0 * q * t + 1.0 + (2.0 * (r - q) * t - d2 * sigma * sqrt_t) / (sigma * sqrt_t) * d1),
+            ultima: -vega / (sigma * sigma) * (d1 * d2 * (1.0 - d1 * d2) + d1 * d1 + d2 * d2),
+        }
+    }
+
+    #[test]
+    fn test_higher_order_greeks_match_closed_form() {
+        let cases = [
+            BlackScholesParams::new(100.0, 100.0, 1.0, 0.2, 0.05, 0.0),
+            BlackScholesParams::new(100.0, 120.0, 0.5, 0.35, 0.03, 0.02),
+            BlackScholesParams::new(50.0, 40.0, 2.0, 0.15, 0.01, 0.04),
+        ];
+
+        for params in cases.iter() {
+            for &option_type in &[OptionType::Call, OptionType::Put] {
+                let ad = calculate_higher_order_greeks(params, option_type);
+                let cf = closed_form_higher_order(params, option_type);
+
+                assert_relative_eq!(ad.vanna, cf.vanna, epsilon = 1e-12);
+                assert_relative_eq!(ad.volga, cf.volga, epsilon = 1e-12);
+                assert_relative_eq!(ad.charm, cf.charm, epsilon = 1e-12);
+                assert_relative_eq!(ad.veta, cf.veta, epsilon = 1e-12);
+                assert_relative_eq!(ad.speed, cf.speed, epsilon = 1e-12);
+                assert_relative_eq!(ad.zomma, cf.zomma, epsilon = 1e-12);
+                assert_relative_eq!(ad.color, cf.color, epsilon = 1e-12);
+                assert_relative_eq!(ad.ultima, cf.ultima, epsilon = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_pass_gradient_matches_finite_differences() {
+        let params = BlackScholesParams::new(100.0, 95.0, 0.75, 0.3, 0.04, 0.015);
+        let price_at = |s: f64, sigma: f64, t: f64, r: f64, q: f64| {
+            put_price(s, params.strike, t, sigma, r, q)
+        };
+
+        // Seed all five inputs at once
+        let result = put_price(
+            MultiDual::<5>::variable(params.spot, 0),
+            params.strike,
+            MultiDual::variable(params.time_to_maturity, 2),
+            MultiDual::variable(params.volatility, 1),
+            MultiDual::variable(params.risk_free_rate, 3),
+            MultiDual::variable(params.dividend_yield, 4),
+        );
+
+        let (s, sigma, t, r, q) = (params.spot, params.volatility, params.time_to_maturity,
+                                   params.risk_free_rate, params.dividend_yield);
+        let h = 1e-6;
+        let fd = [
+            (price_at(s + h, sigma, t, r, q) - price_at(s - h, sigma, t, r, q)) / (2.0 * h),
+            (price_at(s, sigma + h, t, r, q) - price_at(s, sigma - h, t, r, q)) / (2.0 * h),
+            (price_at(s, sigma, t + h, r, q) - price_at(s, sigma, t - h, r, q)) / (2.0 * h),
+            (price_at(s, sigma, t, r + h, q) - price_at(s, sigma, t, r - h, q)) / (2.0 * h),
+            (price_at(s, sigma, t, r, q + h) - price_at(s, sigma, t, r, q - h)) / (2.0 * h),
+        ];
+
+        assert_relative_eq!(result.value, price_at(s, sigma, t, r, q), epsilon = 1e-12);
+        for (ad, fd) in result.grad.iter().zip(fd.iter()) {
+            assert_relative_eq!(*ad, *fd, max_relative = 1e-7);
+        }
+
+        let greeks = calculate_greeks(&params, OptionType::Put);
+        assert_relative_eq!(greeks.delta, result.partial(0), epsilon = 1e-12);
+        assert_relative_eq!(greeks.vega, result.partial(1), epsilon = 1e-12);
+        assert_relative_eq!(greeks.theta, -result.partial(2), epsilon = 1e-12);
+        assert_relative_eq!(greeks.rho, result.partial(3), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_gamma_exact_for_low_priced_underlying() {
+        // Low-priced underlying with deep OTM and ITM strikes, where a fixed spot bump breaks down
+        let cases = [
+            BlackScholesParams::new(0.05, 0.08, 0.1, 0.4, 0.02, 0.0),
+            BlackScholesParams::new(0.5, 0.3, 0.25, 0.6, 0.05, 0.01),
+            BlackScholesParams::new(100.0, 150.0, 0.5, 0.2, 0.03, 0.02),
+        ];
+
+        for params in cases.iter() {
+            let sqrt_t = params.time_to_maturity.sqrt();
+            let d1 = ((params.spot / params.strike).ln()
+                + (params.risk_free_rate - params.dividend_yield + 0.5 * params.volatility * params.volatility)
+                    * params.time_to_maturity)
+                / (params.volatility * sqrt_t);
+            let pdf = (-0.5 * d1 * d1).exp() / (2.0 * std::f64::consts::PI).sqrt();
+            let analytic = (-params.dividend_yield * params.time_to_maturity).exp() * pdf
+                / (params.spot * params.volatility * sqrt_t);
+
+            for &option_type in &[OptionType::Call, OptionType::Put] {
+                let greeks = calculate_greeks(params, option_type);
+                assert_relative_eq!(greeks.gamma, analytic, max_relative = 1e-9);
+            }
+        }
+    }
+
+    /// Closed-form Black-Scholes-Merton theta, rho and phi
+    fn closed_form_theta_rho_phi(params: &BlackScholesParams, option_type: OptionType) -> (f64, f64, f64) {
+        let BlackScholesParams {
+            spot: s,
+            strike: k,
+            time_to_maturity: t,
+            volatility: sigma,
+            risk_free_rate: r,
+            dividend_yield: q,
+        } = *params;
+
+        let sqrt_t = t.sqrt();
+        let d1 = ((s / k).ln() + (r - q + 0.5 * sigma * sigma) * t) / (sigma * sqrt_t);
+        let d2 = d1 - sigma * sqrt_t;
+        let pdf = (-0.5 * d1 * d1).exp() / (2.0 * std::f64::consts::PI).sqrt();
+        let df_q = (-q * t).exp();
+        let df_r = (-r * t).exp();
+        let n = |x: f64| norm_cdf(x);
+
+        let decay = -s * df_q * pdf * sigma / (2.0 * sqrt_t);
+        match option_type {
+            OptionType::Call => (
+                decay - r * k * df_r * n(d2) + q * s * df_q * n(d1),
+                k * t * df_r * n(d2),
+                -s * t * df_q * n(d1),
+            ),
+            OptionType::Put => (
+                decay + r * k * df_r * n(-d2) - q * s * df_q * n(-d1),
+                -k * t * df_r * n(-d2),
+                s * t * df_q * n(-d1),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_theta_rho_phi_match_closed_form() {
+        let one_hour = 1.0 / (365.0 * 24.0);
+        let cases = [
+            BlackScholesParams::new(100.0, 100.0, 1.0, 0.2, 0.05, 0.0),
+            BlackScholesParams::new(100.0, 110.0, 0.5, 0.3, 0.02, 0.03),
+            BlackScholesParams::new(100.0, 100.0, one_hour, 0.25, 0.05, 0.01),
+            BlackScholesParams::new(100.0, 101.0, one_hour, 0.25, 0.05, 0.01),
+        ];
+
+        for params in cases.iter() {
+            for &option_type in &[OptionType::Call, OptionType::Put] {
+                let greeks = calculate_greeks(params, option_type);
+                let (theta, rho, phi) = closed_form_theta_rho_phi(params, option_type);
+
+                assert!(greeks.theta.is_finite());
+                assert_relative_eq!(greeks.theta, theta, epsilon = 1e-12, max_relative = 1e-12);
+                assert_relative_eq!(greeks.rho, rho, epsilon = 1e-12, max_relative = 1e-12);
+                assert_relative_eq!(greeks.phi, phi, epsilon = 1e-12, max_relative = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_expired_option() {
+        let itm = BlackScholesParams::new(105.0, 100.0, 0.0, 0.2, 0.05, 0.0);
+        let call = calculate_greeks(&itm, OptionType::Call);
+        let put = calculate_greeks(&itm, OptionType::Put);
+
+        assert_relative_eq!(call.price, 5.0);
+        assert_relative_eq!(call.delta, 1.0);
+        assert_relative_eq!(put.price, 0.0);
+        assert_relative_eq!(put.delta, 0.0);
+        for greeks in [call, put] {
+            assert_eq!(greeks.gamma, 0.0);
+            assert_eq!(greeks.vega, 0.0);
+            assert_eq!(greeks.theta, 0.0);
+            assert_eq!(greeks.rho, 0.0);
+            assert_eq!(greeks.phi, 0.0);
+        }
+
+        let atm = BlackScholesParams::new(100.0, 100.0, 0.0, 0.2, 0.05, 0.0);
+        assert_relative_eq!(calculate_greeks(&atm, OptionType::Call).delta, 0.5);
+        assert_relative_eq!(calculate_greeks(&atm, OptionType::Put).delta, -0.5);
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        assert!(BlackScholesParams::try_new(100.0, 100.0, 1.0, 0.2, 0.05, 0.0).is_ok());
+        assert!(BlackScholesParams::try_new(100.0, 100.0, 0.0, 0.2, 0.05, 0.0).is_ok());
+
+        assert_eq!(
+            BlackScholesParams::try_new(-1.0, 100.0, 1.0, 0.2, 0.05, 0.0).unwrap_err(),
+            PricingError::NonPositiveSpot(-1.0)
+        );
+        assert_eq!(
+            BlackScholesParams::try_new(100.0, 0.0, 1.0, 0.2, 0.05, 0.0).unwrap_err(),
+            PricingError::NonPositiveStrike(0.0)
+        );
+        assert_eq!(
+            BlackScholesParams::try_new(100.0, 100.0, -0.5, 0.2, 0.05, 0.0).unwrap_err(),
+            PricingError::NegativeMaturity(-0.5)
+        );
+        assert_eq!(
+            BlackScholesParams::try_new(100.0, 100.0, 1.0, 0.0, 0.05, 0.0).unwrap_err(),
+            PricingError::NonPositiveVolatility(0.0)
+        );
+        assert_eq!(
+            BlackScholesParams::try_new(100.0, 100.0, 1.0, 0.2, f64::NAN, 0.0).unwrap_err(),
+            PricingError::NonFinite("risk_free_rate")
+        );
+
+        let bad = BlackScholesParams::new(100.0, 100.0, 1.0, 0.2, 0.05, f64::INFINITY);
+        assert_eq!(
+            try_calculate_greeks(&bad, OptionType::Call).unwrap_err(),
+            PricingError::NonFinite("dividend_yield")
+        );
+    }
+
+    #[test]
+    fn test_zero_volatility_is_discounted_intrinsic() {
+        let params = BlackScholesParams::new(100.0, 90.0, 1.0, 0.0, 0.05, 0.02);
+        let forward_payoff = 100.0 * (-0.02_f64).exp() - 90.0 * (-0.05_f64).exp();
+
+        let call = calculate_greeks(&params, OptionType::Call);
+        assert_relative_eq!(call.price, forward_payoff, epsilon = 1e-12);
+        assert_relative_eq!(call.delta, (-0.02_f64).exp(), epsilon = 1e-12);
+        assert_eq!(call.gamma, 0.0);
+        assert_relative_eq!(call.rho, 90.0 * (-0.05_f64).exp(), epsilon = 1e-12);
+
+        let put = calculate_greeks(&params, OptionType::Put);
+        assert_eq!(put.price, 0.0);
+        assert_eq!(put.delta, 0.0);
+
+        let higher = calculate_higher_order_greeks(&params, OptionType::Call);
+        assert_eq!(higher.vanna, 0.0);
+        assert_eq!(higher.speed, 0.0);
     }
 }
@@ -0,0 +1,144 @@
+//! Market inputs lifted to AD numbers, and the Greeks pass shared by the
+//! closed-form pricers
+//!
+//! A contract prices itself from `Inputs` generic over `Scalar`. Spot,
+//! volatility, elapsed time, rate, dividend yield and any jump intensity are
+//! then seeded together on a `MultiDual`, so price and every first-order Greek
+//! come out of one evaluation, and gamma is exact from a second pass with spot
+//! seeded on a `HyperDual`.
+//!
+//! Models quoted on a forward run through the same pass, with the forward in
+//! place of spot and no dividend yield.
+
+use crate::ad::{HyperDual, MultiDual, Scalar};
+use crate::pricing::black_scholes::BlackScholesParams;
+use crate::types::Greeks;
+
+/// Gradient slots used when seeding inputs for a single pricing pass
+pub(crate) const SPOT: usize = 0;
+pub(crate) const VOL: usize = 1;
+pub(crate) const TIME: usize = 2;
+pub(crate) const RATE: usize = 3;
+pub(crate) const DIVIDEND: usize = 4;
+pub(crate) const INTENSITY: usize = 5;
+
+/// Market inputs lifted to AD numbers so that any of them can be seeded
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Inputs<T> {
+    pub(crate) s: T,
+    pub(crate) k: f64,
+    pub(crate) t: T,
+    pub(crate) sigma: T,
+    pub(crate) r: T,
+    pub(crate) q: T,
+    /// Calendar time elapsed, zero but for theta
+    pub(crate) elapsed: T,
+    /// Jump intensity, zero but for jump-diffusions
+    pub(crate) intensity: T,
+}
+
+impl<T: Scalar> Inputs<T> {
+    pub(crate) fn new(s: T, k: f64, t: T, sigma: T, r: T, q: T) -> Self {
+        Self {
+            s,
+            k,
+            t,
+            sigma,
+            r,
+            q,
+            elapsed: T::constant(0.0),
+            intensity: T::constant(0.0),
+        }
+    }
+
+    /// All inputs held constant
+    pub(crate) fn constant(params: &BlackScholesParams) -> Self {
+        Self::new(
+            T::constant(params.spot),
+            params.strike,
+            T::constant(params.time_to_maturity),
+            T::constant(params.volatility),
+            T::constant(params.risk_free_rate),
+            T::constant(params.dividend_yield),
+        )
+    }
+}
+
+/// Contract with a closed form generic over the number type
+pub(crate) trait ClosedForm {
+    fn price<T: Scalar>(&self, inputs: &Inputs<T>) -> T;
+
+    /// Jump intensity priced at, for contracts on a jump-diffusion
+    fn intensity(&self) -> f64 {
+        0.0
+    }
+
+    /// Greeks once no time remains: by default the price, with every sensitivity zero
+    fn at_expiry(&self, params: &BlackScholesParams) -> Greeks {
+        let price = self.price(&Inputs::<f64>::constant(params));
+        Greeks::new(price, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0)
+    }
+}
+
+/// Price and Greeks of `contract`
+///
+/// Elapsed time is seeded rather than maturity, so any date a contract measures
+/// from now through `elapsed` ages along with it; otherwise theta is the usual
+/// decay in time to maturity.
+pub(crate) fn greeks<C: ClosedForm>(params: &BlackScholesParams, contract: &C) -> Greeks {
+    greeks_and_intensity(params, contract).0
+}
+
+/// Price and Greeks of `contract`, with its sensitivity to the jump intensity
+pub(crate) fn greeks_and_intensity<C: ClosedForm>(params: &BlackScholesParams, contract: &C) -> (Greeks, f64) {
+    if params.time_to_maturity <= 0.0 {
+        return (contract.at_expiry(params), 0.0);
+    }
+
+    let elapsed = MultiDual::<6>::variable(0.0, TIME);
+    let result = contract.price(&Inputs {
+        s: MultiDual::variable(params.spot, SPOT),
+        sigma: MultiDual::variable(params.volatility, VOL),
+        t: -elapsed + params.time_to_maturity,
+        r: MultiDual::variable(params.risk_free_rate, RATE),
+        q: MultiDual::variable(params.dividend_yield, DIVIDEND),
+        elapsed,
+        intensity: MultiDual::variable(contract.intensity(), INTENSITY),
+        ..Inputs::constant(params)
+    });
+
+    let gamma = contract
+        .price(&Inputs {
+            s: HyperDual::variable(params.spot),
+            intensity: HyperDual::constant(contract.intensity()),
+            ..Inputs::constant(params)
+        })
+        .second_deriv();
+
+    let greeks = Greeks::new(
+        result.value,
+        result.partial(SPOT),
+        gamma,
+        result.partial(VOL),
+        result.partial(TIME),
+        result.partial(RATE),
+        result.partial(DIVIDEND),
+    );
+    (greeks, result.partial(INTENSITY))
+}
+
+/// Price and Greeks of `contract` on a forward
+///
+/// The forward is seeded in the spot slot, so delta and gamma are taken with
+/// respect to it. There is no dividend yield, so phi is zero.
+pub(crate) fn forward_greeks<C: ClosedForm>(
+    forward: f64,
+    strike: f64,
+    t: f64,
+    vol: f64,
+    discount_rate: f64,
+    contract: &C,
+) -> Greeks {
+    let params = BlackScholesParams::new(forward, strike, t, vol, discount_rate, 0.0);
+    greeks(&params, contract)
+}
@@ -0,0 +1,380 @@
+//! Digital and gap options
+//!
+//! Cash-or-nothing, asset-or-nothing and gap payoffs all decompose into two
+//! call digitals, one paying the asset and one paying cash, with puts from
+//! parity. Under a flat volatility these are S e^(-qT) N(d1) and e^(-rT) N(d2).
+//!
+//! A digital is the negative strike derivative of a call, so its value also
+//! depends on the skew: -∂C/∂K = e^(-rT) N(d2) - vega dσ/dK. The smile-consistent
+//! prices read that skew off a `VolatilitySurface` by replicating the cash
+//! digital with a tight call spread, each leg at its own implied volatility.
+
+use crate::ad::{norm_cdf, Scalar};
+use crate::pricing::black_scholes::{self, BlackScholesParams};
+use crate::pricing::closed_form::{self, ClosedForm, Inputs};
+use crate::pricing::PricingError;
+use crate::types::{Greeks, OptionType};
+use crate::volatility::VolatilitySurface;
+
+/// Payoff paid when the option finishes beyond the strike
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DigitalPayoff {
+    /// A fixed amount of cash
+    CashOrNothing { cash: f64 },
+    /// One unit of the underlying
+    AssetOrNothing,
+    /// S_T - payment_strike for a call, payment_strike - S_T for a put, which
+    /// may be negative; the option strike acts as the trigger
+    Gap { payment_strike: f64 },
+}
+
+impl DigitalPayoff {
+    /// Check the cash amount or payment strike
+    pub fn validate(&self) -> Result<(), PricingError> {
+        match *self {
+            DigitalPayoff::CashOrNothing { cash } if !cash.is_finite() => Err(PricingError::NonFinite("cash")),
+            DigitalPayoff::Gap { payment_strike } if !payment_strike.is_finite() => {
+                Err(PricingError::NonFinite("payment_strike"))
+            }
+            DigitalPayoff::Gap { payment_strike } if payment_strike <= 0.0 => {
+                Err(PricingError::NonPositiveStrike(payment_strike))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Combine the asset and unit cash digitals of the chosen side
+    fn combine<T: Scalar>(&self, asset: T, cash: T, option_type: OptionType) -> T {
+        let sign = match option_type {
+            OptionType::Call => 1.0,
+            OptionType::Put => -1.0,
+        };
+        match *self {
+            DigitalPayoff::CashOrNothing { cash: amount } => cash * amount,
+            DigitalPayoff::AssetOrNothing => asset,
+            DigitalPayoff::Gap { payment_strike } => (asset - cash * payment_strike) * sign,
+        }
+    }
+}
+
+/// Call-spread half width, relative to the strike
+const SPREAD_WIDTH: f64 = 1e-4;
+
+/// Price a digital or gap option under a flat volatility
+///
+/// Generic over `Scalar`, so seeding any input on an AD number type yields its
+/// sensitivity. With zero volatility or time the outcome is certain and pays in
+/// full when the forward is beyond the strike, or half at the strike.
+#[allow(clippy::too_many_arguments)]
+pub fn price<T: Scalar>(s: T, k: f64, t: T, sigma: T, r: T, q: T, option_type: OptionType, payoff: &DigitalPayoff) -> T {
+    Digital { option_type, payoff, skew: None }.price(&Inputs::new(s, k, t, sigma, r, q))
+}
+
+/// Calculate price and Greeks under a flat volatility
+///
+/// First-order Greeks come from one `MultiDual` pass and gamma from a
+/// `HyperDual` pass, as for vanillas.
+pub fn calculate_greeks(params: &BlackScholesParams, option_type: OptionType, payoff: &DigitalPayoff) -> Greeks {
+    closed_form::greeks(params, &Digital { option_type, payoff, skew: None })
+}
+
+/// Calculate flat-volatility price and Greeks after validating the inputs
+pub fn try_calculate_greeks(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    payoff: &DigitalPayoff,
+) -> Result<Greeks, PricingError> {
+    params.validate()?;
+    payoff.validate()?;
+    Ok(calculate_greeks(params, option_type, payoff))
+}
+
+/// Price consistently with the smile of `surface`
+///
+/// The volatility in `params` is replaced by the surface's implied volatility
+/// at the strike. Returns `None` where the surface has no volatility to offer.
+pub fn smile_price(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    payoff: &DigitalPayoff,
+    surface: &VolatilitySurface,
+) -> Option<f64> {
+    let skew = Skew::from_surface(params, surface)?;
+    let inputs = Inputs::<f64> {
+        sigma: skew.at,
+        ..Inputs::constant(params)
+    };
+    Some(Digital { option_type, payoff, skew: Some(skew) }.price(&inputs))
+}
+
+/// Calculate smile-consistent price and Greeks
+///
+/// Greeks hold each leg's implied volatility fixed as spot and time move
+/// (sticky strike); vega is the sensitivity to a parallel shift of the smile.
+pub fn calculate_smile_greeks(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    payoff: &DigitalPayoff,
+    surface: &VolatilitySurface,
+) -> Option<Greeks> {
+    let skew = Skew::from_surface(params, surface)?;
+    let params = BlackScholesParams {
+        volatility: skew.at,
+        ..*params
+    };
+    Some(closed_form::greeks(&params, &Digital { option_type, payoff, skew: Some(skew) }))
+}
+
+/// Calculate smile-consistent price and Greeks after validating the inputs
+pub fn try_calculate_smile_greeks(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    payoff: &DigitalPayoff,
+    surface: &VolatilitySurface,
+) -> Result<Greeks, PricingError> {
+    // Any positive volatility passes; the surface supplies the one used
+    BlackScholesParams { volatility: 1.0, ..*params }.validate()?;
+    payoff.validate()?;
+    calculate_smile_greeks(params, option_type, payoff, surface).ok_or(PricingError::MissingVolatility {
+        strike: params.strike,
+        time_to_maturity: params.time_to_maturity,
+    })
+}
+
+/// Digital contract, with the smile around the strike when priced off a surface
+struct Digital<'a> {
+    option_type: OptionType,
+    payoff: &'a DigitalPayoff,
+    skew: Option<Skew>,
+}
+
+impl ClosedForm for Digital<'_> {
+    fn price<T: Scalar>(&self, inputs: &Inputs<T>) -> T {
+        let asset_forward = inputs.s * (-inputs.q * inputs.t).exp();
+        let discount = (-inputs.r * inputs.t).exp();
+        let (asset, cash) = match self.skew {
+            Some(skew) if inputs.t.value() > 0.0 => inputs.replicated_digitals(&skew),
+            _ => inputs.flat_digitals(),
+        };
+        let (asset, cash) = match self.option_type {
+            OptionType::Call => (asset, cash),
+            OptionType::Put => (asset_forward - asset, discount - cash),
+        };
+        self.payoff.combine(asset, cash, self.option_type)
+    }
+}
+
+/// Implied volatility at the strike, and the offsets to it either side
+///
+/// The pricing inputs carry the volatility at the strike, so seeding it shifts
+/// every leg together and vega is a parallel shift of the smile.
+#[derive(Debug, Clone, Copy)]
+struct Skew {
+    at: f64,
+    below: f64,
+    above: f64,
+}
+
+impl Skew {
+    fn from_surface(params: &BlackScholesParams, surface: &VolatilitySurface) -> Option<Self> {
+        let vol = |strike: f64| {
+            surface
+                .get_implied_volatility(strike, params.spot, params.time_to_maturity)
+                .filter(|vol| vol.is_finite() && *vol > 0.0)
+        };
+        let width = SPREAD_WIDTH * params.strike;
+        let at = vol(params.strike)?;
+        Some(Self {
+            at,
+            below: vol(params.strike - width)? - at,
+            above: vol(params.strike + width)? - at,
+        })
+    }
+}
+
+impl<T: Scalar> Inputs<T> {
+    /// Asset and unit cash call digitals, S e^(-qT) N(d1) and e^(-rT) N(d2)
+    fn flat_digitals(&self) -> (T, T) {
+        let asset_forward = self.s * (-self.q * self.t).exp();
+        let discount = (-self.r * self.t).exp();
+        let (s, k, t, sigma) = (self.s, self.k, self.t, self.sigma);
+        if sigma.value() == 0.0 || t.value() == 0.0 {
+            let forward = s * ((self.r - self.q) * t).exp();
+            let weight = match forward.value().partial_cmp(&k) {
+                Some(std::cmp::Ordering::Greater) => 1.0,
+                Some(std::cmp::Ordering::Equal) => 0.5,
+                _ => 0.0,
+            };
+            return (asset_forward * weight, discount * weight);
+        }
+        let std_dev = sigma * t.sqrt();
+        let d1 = ((s / k).ln() + (self.r - self.q) * t) / std_dev + std_dev * 0.5;
+        (asset_forward * norm_cdf(d1), discount * norm_cdf(d1 - std_dev))
+    }
+
+    /// Call digitals from the call spread (C(K - h) - C(K + h)) / 2h, and
+    /// S_T 1{S_T > K} = (S_T - K)⁺ + K 1{S_T > K}
+    fn replicated_digitals(&self, skew: &Skew) -> (T, T) {
+        let width = SPREAD_WIDTH * self.k;
+        let call = |strike: f64, sigma: T| black_scholes::call_price(self.s, strike, self.t, sigma, self.r, self.q);
+        let cash = (call(self.k - width, self.sigma + skew.below) - call(self.k + width, self.sigma + skew.above))
+            / (2.0 * width);
+        (call(self.k, self.sigma) + cash * self.k, cash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ad::Dual;
+    use crate::volatility::SVIParams;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_reference_prices() {
+        // Haug, The Complete Guide to Option Pricing Formulas (2007)
+        let gap = DigitalPayoff::Gap { payment_strike: 57.0 };
+        assert_relative_eq!(price(50.0, 50.0, 0.5, 0.2, 0.09, 0.0, OptionType::Call, &gap), -0.005252489258782735, epsilon = 1e-14);
+        let cash = DigitalPayoff::CashOrNothing { cash: 10.0 };
+        assert_relative_eq!(price(100.0, 80.0, 0.75, 0.35, 0.06, 0.06, OptionType::Put, &cash), 2.671045684461347, epsilon = 1e-13);
+        let asset = DigitalPayoff::AssetOrNothing;
+        assert_relative_eq!(price(70.0, 65.0, 0.5, 0.27, 0.07, 0.05, OptionType::Put, &asset), 20.20694729836854, epsilon = 1e-13);
+
+        // A gap option paying at its trigger is the vanilla
+        for &option_type in &[OptionType::Call, OptionType::Put] {
+            let params = BlackScholesParams::new(100.0, 95.0, 1.2, 0.25, 0.04, 0.01);
+            let vanilla = black_scholes::calculate_greeks(&params, option_type);
+            let gap = calculate_greeks(&params, option_type, &DigitalPayoff::Gap { payment_strike: 95.0 });
+            assert_relative_eq!(gap.price, vanilla.price, epsilon = 1e-13);
+            assert_relative_eq!(gap.delta, vanilla.delta, epsilon = 1e-13);
+            assert_relative_eq!(gap.gamma, vanilla.gamma, epsilon = 1e-13);
+            assert_relative_eq!(gap.vega, vanilla.vega, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_greeks_against_bumps() {
+        let params = BlackScholesParams::new(100.0, 105.0, 0.75, 0.3, 0.05, 0.02);
+        let payoffs = [
+            DigitalPayoff::CashOrNothing { cash: 10.0 },
+            DigitalPayoff::AssetOrNothing,
+            DigitalPayoff::Gap { payment_strike: 110.0 },
+        ];
+        for payoff in &payoffs {
+            for &option_type in &[OptionType::Call, OptionType::Put] {
+                let greeks = calculate_greeks(&params, option_type, payoff);
+                let value = |p: &BlackScholesParams| {
+                    price(p.spot, p.strike, p.time_to_maturity, p.volatility, p.risk_free_rate, p.dividend_yield, option_type, payoff)
+                };
+                let bumped = |f: &dyn Fn(&mut BlackScholesParams, f64), h: f64| {
+                    let (mut up, mut down) = (params, params);
+                    f(&mut up, h);
+                    f(&mut down, -h);
+                    (value(&up) - value(&down)) / (2.0 * h)
+                };
+                assert_relative_eq!(greeks.price, value(&params), epsilon = 1e-13);
+                assert_relative_eq!(greeks.delta, bumped(&|p, h| p.spot += h, 1e-4), epsilon = 1e-7);
+                assert_relative_eq!(greeks.vega, bumped(&|p, h| p.volatility += h, 1e-6), epsilon = 1e-6);
+                assert_relative_eq!(greeks.theta, -bumped(&|p, h| p.time_to_maturity += h, 1e-6), epsilon = 1e-6);
+                assert_relative_eq!(greeks.rho, bumped(&|p, h| p.risk_free_rate += h, 1e-6), epsilon = 1e-6);
+                assert_relative_eq!(greeks.phi, bumped(&|p, h| p.dividend_yield += h, 1e-6), epsilon = 1e-6);
+                let h = 1e-3;
+                let (mut up, mut down) = (params, params);
+                up.spot += h;
+                down.spot -= h;
+                assert_relative_eq!(greeks.gamma, (value(&up) - 2.0 * greeks.price + value(&down)) / (h * h), epsilon = 1e-5);
+
+                // The generic pricer differentiates directly on a Dual
+                let delta = price(Dual::variable(100.0), 105.0, Dual::constant(0.75), Dual::constant(0.3), Dual::constant(0.05), Dual::constant(0.02), option_type, payoff).deriv;
+                assert_relative_eq!(delta, greeks.delta, epsilon = 1e-14);
+            }
+        }
+    }
+
+    #[test]
+    fn test_smile_consistent_prices() {
+        let params = BlackScholesParams::new(100.0, 110.0, 1.0, 0.2, 0.03, 0.01);
+        let cash = DigitalPayoff::CashOrNothing { cash: 1.0 };
+
+        // A flat smile reproduces the flat-volatility prices
+        let mut flat = VolatilitySurface::new();
+        flat.add_slice(1.0, SVIParams::new(0.04, 0.0, 0.0, 0.0, 0.1));
+        for payoff in [cash, DigitalPayoff::AssetOrNothing, DigitalPayoff::Gap { payment_strike: 100.0 }] {
+            for &option_type in &[OptionType::Call, OptionType::Put] {
+                let expected = calculate_greeks(&params, option_type, &payoff);
+                let smile = calculate_smile_greeks(&params, option_type, &payoff, &flat).unwrap();
+                assert_relative_eq!(smile.price, expected.price, epsilon = 1e-8, max_relative = 1e-6);
+                assert_relative_eq!(smile.delta, expected.delta, epsilon = 1e-8, max_relative = 1e-6);
+                assert_relative_eq!(smile.vega, expected.vega, epsilon = 1e-7, max_relative = 1e-6);
+            }
+        }
+
+        // Under a downward skew the cash call digital is worth e^(-rT) N(d2) - vega dσ/dK,
+        // more than at the flat strike volatility
+        let mut skewed = VolatilitySurface::new();
+        skewed.add_slice(1.0, SVIParams::new(0.03, 0.12, -0.6, 0.0, 0.2));
+        let vol = |strike: f64| skewed.get_implied_volatility(strike, 100.0, 1.0).unwrap();
+        let at_strike = BlackScholesParams { volatility: vol(110.0), ..params };
+        let slope = (vol(110.01) - vol(109.99)) / 0.02;
+        let vega = black_scholes::calculate_greeks(&at_strike, OptionType::Call).vega;
+        let flat_digital = calculate_greeks(&at_strike, OptionType::Call, &cash).price;
+        let smile_digital = smile_price(&params, OptionType::Call, &cash, &skewed).unwrap();
+        assert!(slope < 0.0);
+        assert_relative_eq!(smile_digital, flat_digital - vega * slope, epsilon = 1e-7);
+
+        // Calls and puts still add up to the discounted cash
+        let put = smile_price(&params, OptionType::Put, &cash, &skewed).unwrap();
+        assert_relative_eq!(smile_digital + put, (-0.03_f64).exp(), epsilon = 1e-14);
+
+        assert!(smile_price(&params, OptionType::Call, &cash, &VolatilitySurface::new()).is_none());
+    }
+
+    #[test]
+    fn test_smile_greeks_sticky_strike() {
+        let mut surface = VolatilitySurface::new();
+        surface.add_slice(0.5, SVIParams::new(0.015, 0.08, -0.5, 0.0, 0.2));
+        let params = BlackScholesParams::new(100.0, 95.0, 0.5, 0.2, 0.04, 0.0);
+        let payoff = DigitalPayoff::Gap { payment_strike: 90.0 };
+        let greeks = calculate_smile_greeks(&params, OptionType::Put, &payoff, &surface).unwrap();
+
+        // Bump inputs with every leg's volatility frozen
+        let skew = Skew::from_surface(&params, &surface).unwrap();
+        let digital = Digital { option_type: OptionType::Put, payoff: &payoff, skew: Some(skew) };
+        let value = |p: &BlackScholesParams, shift: f64| digital.price(&Inputs { sigma: skew.at + shift, ..Inputs::constant(p) });
+        let bumped = |f: &dyn Fn(&mut BlackScholesParams, f64), h: f64| {
+            let (mut up, mut down) = (params, params);
+            f(&mut up, h);
+            f(&mut down, -h);
+            (value(&up, 0.0) - value(&down, 0.0)) / (2.0 * h)
+        };
+        assert_relative_eq!(greeks.price, value(&params, 0.0), epsilon = 1e-13);
+        assert_relative_eq!(greeks.delta, bumped(&|p, h| p.spot += h, 1e-3), epsilon = 1e-6);
+        assert_relative_eq!(greeks.vega, (value(&params, 1e-6) - value(&params, -1e-6)) / 2e-6, epsilon = 1e-5);
+        assert_relative_eq!(greeks.theta, -bumped(&|p, h| p.time_to_maturity += h, 1e-6), epsilon = 1e-5);
+        assert_relative_eq!(greeks.rho, bumped(&|p, h| p.risk_free_rate += h, 1e-6), epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_expiry_and_validation() {
+        let expired = BlackScholesParams::new(104.0, 100.0, 0.0, 0.2, 0.03, 0.0);
+        let gap = DigitalPayoff::Gap { payment_strike: 110.0 };
+        assert_relative_eq!(calculate_greeks(&expired, OptionType::Call, &gap).price, -6.0);
+        assert_relative_eq!(calculate_greeks(&expired, OptionType::Put, &gap).price, 0.0);
+        let cash = DigitalPayoff::CashOrNothing { cash: 5.0 };
+        assert_relative_eq!(calculate_greeks(&expired, OptionType::Call, &cash).price, 5.0);
+
+        let params = BlackScholesParams::new(100.0, 100.0, 1.0, 0.2, 0.03, 0.0);
+        assert_eq!(
+            try_calculate_greeks(&params, OptionType::Call, &DigitalPayoff::Gap { payment_strike: -5.0 }).unwrap_err(),
+            PricingError::NonPositiveStrike(-5.0)
+        );
+        assert_eq!(
+            try_calculate_greeks(&params, OptionType::Call, &DigitalPayoff::CashOrNothing { cash: f64::NAN }).unwrap_err(),
+            PricingError::NonFinite("cash")
+        );
+        assert_eq!(
+            try_calculate_smile_greeks(&params, OptionType::Call, &cash, &VolatilitySurface::new()).unwrap_err(),
+            PricingError::MissingVolatility { strike: 100.0, time_to_maturity: 1.0 }
+        );
+    }
+}
@@ -0,0 +1,66 @@
+//! Error type for the pricing API
+
+use std::fmt;
+
+/// Reasons a set of pricing inputs is rejected
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PricingError {
+    /// Spot price must be strictly positive
+    NonPositiveSpot(f64),
+    /// Forward price must be strictly positive
+    NonPositiveForward(f64),
+    /// Strike price must be strictly positive
+    NonPositiveStrike(f64),
+    /// Time to maturity must not be negative
+    NegativeMaturity(f64),
+    /// Volatility must be strictly positive
+    NonPositiveVolatility(f64),
+    /// The named input is NaN or infinite
+    NonFinite(&'static str),
+    /// Option price outside the open no-arbitrage interval (lower, upper)
+    ArbitrageViolation { price: f64, lower: f64, upper: f64 },
+    /// Implied volatility solver failed to converge
+    ImpliedVolNotFound,
+    /// No strike has the requested delta under the chosen convention
+    DeltaOutOfRange(f64),
+    /// Exercise dates must be increasing and lie in (0, maturity]
+    InvalidExerciseSchedule(f64),
+    /// A model parameter lies outside its admissible range
+    InvalidParameter { name: &'static str, value: f64 },
+    /// Too few market quotes to calibrate the model
+    InsufficientQuotes { required: usize, provided: usize },
+    /// The volatility surface has no usable implied volatility at this strike
+    MissingVolatility { strike: f64, time_to_maturity: f64 },
+}
+
+impl fmt::Display for PricingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PricingError::NonPositiveSpot(v) => write!(f, "spot must be positive, got {}", v),
+            PricingError::NonPositiveForward(v) => write!(f, "forward must be positive, got {}", v),
+            PricingError::NonPositiveStrike(v) => write!(f, "strike must be positive, got {}", v),
+            PricingError::NegativeMaturity(v) => write!(f, "time to maturity must not be negative, got {}", v),
+            PricingError::NonPositiveVolatility(v) => write!(f, "volatility must be positive, got {}", v),
+            PricingError::NonFinite(name) => write!(f, "{} must be finite", name),
+            PricingError::ArbitrageViolation { price, lower, upper } => write!(
+                f,
+                "price {} violates no-arbitrage bounds, must lie strictly within ({}, {})",
+                price, lower, upper
+            ),
+            PricingError::ImpliedVolNotFound => write!(f, "implied volatility solver did not converge"),
+            PricingError::DeltaOutOfRange(v) => write!(f, "no strike has delta {}", v),
+            PricingError::InvalidExerciseSchedule(v) => {
+                write!(f, "exercise dates must be increasing and within (0, maturity], got {}", v)
+            }
+            PricingError::InvalidParameter { name, value } => write!(f, "{} out of range, got {}", name, value),
+            PricingError::InsufficientQuotes { required, provided } => {
+                write!(f, "calibration needs at least {} quotes, got {}", required, provided)
+            }
+            PricingError::MissingVolatility { strike, time_to_maturity } => {
+                write!(f, "no implied volatility at strike {} and maturity {}", strike, time_to_maturity)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PricingError {}
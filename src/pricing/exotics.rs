@@ -0,0 +1,657 @@
+//! Closed-form lookback, chooser and compound options under Black-Scholes
+//!
+//! Floating-strike lookbacks follow Goldman, Sosin and Gatto (1979) and
+//! fixed-strike lookbacks Conze and Viswanathan (1991), both continuously
+//! monitored from a known running extreme. Choosers follow Rubinstein (1991):
+//! the simple chooser picks between a call and a put on the same terms, the
+//! complex one between a call and a put with their own strikes and maturities.
+//! Compound options are Geske's (1979) options on options.
+//!
+//! The chooser and compound formulas depend on a critical spot at the choice or
+//! exercise date, found by Newton's method and then held fixed: the value is
+//! stationary in it, so first derivatives and gamma are unaffected. All prices
+//! are generic over `Scalar`. The Greeks age every date together, so theta is
+//! the decay over calendar time.
+
+use crate::ad::ops::GAUSS_LEGENDRE_20;
+use crate::ad::{bivariate_norm_cdf, norm_cdf, norm_pdf, Dual, Scalar};
+use crate::pricing::black_scholes::{self, BlackScholesParams};
+use crate::pricing::closed_form::{self, ClosedForm, Inputs};
+use crate::pricing::PricingError;
+use crate::types::{Greeks, OptionType};
+
+/// Strike convention of a lookback option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LookbackType {
+    /// Call pays S_T - min S, put pays max S - S_T
+    FloatingStrike,
+    /// Call pays (max S - K)⁺, put pays (K - min S)⁺
+    FixedStrike,
+}
+
+/// Lookback option
+#[derive(Debug, Clone, Copy)]
+pub struct Lookback {
+    pub lookback_type: LookbackType,
+    /// Extreme of the spot observed so far: the minimum for floating-strike calls
+    /// and fixed-strike puts, the maximum otherwise; the spot for a new option
+    pub extreme: f64,
+}
+
+impl Lookback {
+    pub fn new(lookback_type: LookbackType, extreme: f64) -> Self {
+        Self { lookback_type, extreme }
+    }
+
+    /// Check the running extreme
+    pub fn validate(&self) -> Result<(), PricingError> {
+        if !self.extreme.is_finite() {
+            return Err(PricingError::NonFinite("extreme"));
+        }
+        if self.extreme <= 0.0 {
+            return Err(PricingError::InvalidParameter { name: "extreme", value: self.extreme });
+        }
+        Ok(())
+    }
+
+    /// Whether the payoff depends on the running minimum rather than the maximum
+    fn tracks_minimum(&self, option_type: OptionType) -> bool {
+        (self.lookback_type == LookbackType::FloatingStrike) == (option_type == OptionType::Call)
+    }
+}
+
+/// Chooser option: at the choice date the holder takes the option's call or a put
+#[derive(Debug, Clone, Copy)]
+pub struct Chooser {
+    /// Time from now to the choice date
+    pub choice_time: f64,
+    /// Strike of the put; the call has the option's own
+    pub put_strike: f64,
+    /// Time to maturity of the put; the call matures with the option
+    pub put_maturity: f64,
+}
+
+impl Chooser {
+    /// Complex chooser between the option's call and a put on its own terms
+    pub fn new(choice_time: f64, put_strike: f64, put_maturity: f64) -> Self {
+        Self {
+            choice_time,
+            put_strike,
+            put_maturity,
+        }
+    }
+
+    /// Simple chooser: the put shares the call's strike and maturity
+    pub fn simple(choice_time: f64, params: &BlackScholesParams) -> Self {
+        Self::new(choice_time, params.strike, params.time_to_maturity)
+    }
+
+    /// Check the choice date and the put's terms
+    pub fn validate(&self) -> Result<(), PricingError> {
+        if !self.choice_time.is_finite() {
+            return Err(PricingError::NonFinite("choice_time"));
+        }
+        if !self.put_strike.is_finite() {
+            return Err(PricingError::NonFinite("put_strike"));
+        }
+        if !self.put_maturity.is_finite() {
+            return Err(PricingError::NonFinite("put_maturity"));
+        }
+        if self.choice_time < 0.0 {
+            return Err(PricingError::InvalidParameter { name: "choice_time", value: self.choice_time });
+        }
+        if self.put_strike <= 0.0 {
+            return Err(PricingError::NonPositiveStrike(self.put_strike));
+        }
+        if self.put_maturity < self.choice_time {
+            return Err(PricingError::InvalidParameter { name: "put_maturity", value: self.put_maturity });
+        }
+        Ok(())
+    }
+}
+
+/// Compound option on the option described by the pricing inputs
+#[derive(Debug, Clone, Copy)]
+pub struct Compound {
+    /// Call or put on the underlying option
+    pub option_type: OptionType,
+    /// Premium paid or received for the underlying option on exercise
+    pub strike: f64,
+    /// Time to the compound's expiry, no later than the underlying's maturity
+    pub expiry: f64,
+}
+
+impl Compound {
+    pub fn new(option_type: OptionType, strike: f64, expiry: f64) -> Self {
+        Self {
+            option_type,
+            strike,
+            expiry,
+        }
+    }
+
+    /// Check the premium and expiry
+    pub fn validate(&self) -> Result<(), PricingError> {
+        if !self.strike.is_finite() {
+            return Err(PricingError::NonFinite("strike"));
+        }
+        if !self.expiry.is_finite() {
+            return Err(PricingError::NonFinite("expiry"));
+        }
+        if self.strike <= 0.0 {
+            return Err(PricingError::NonPositiveStrike(self.strike));
+        }
+        if self.expiry < 0.0 {
+            return Err(PricingError::InvalidParameter { name: "expiry", value: self.expiry });
+        }
+        Ok(())
+    }
+}
+
+/// Price a lookback option
+///
+/// Generic over `Scalar`, so seeding any input on an AD number type yields its sensitivity.
+#[allow(clippy::too_many_arguments)]
+pub fn lookback_price<T: Scalar>(s: T, k: f64, t: T, sigma: T, r: T, q: T, option_type: OptionType, lookback: &Lookback) -> T {
+    (lookback, option_type).price(&Inputs::new(s, k, t, sigma, r, q))
+}
+
+/// Price a chooser on a call struck at `k` maturing at `t`
+///
+/// Generic over `Scalar`; the chooser's dates stay where they are as `t` moves.
+pub fn chooser_price<T: Scalar>(s: T, k: f64, t: T, sigma: T, r: T, q: T, chooser: &Chooser) -> T {
+    chooser.price(&Inputs::new(s, k, t, sigma, r, q))
+}
+
+/// Price a compound option on the option of type `underlying_type` struck at `k` maturing at `t`
+///
+/// Generic over `Scalar`; the compound's expiry stays where it is as `t` moves.
+#[allow(clippy::too_many_arguments)]
+pub fn compound_price<T: Scalar>(
+    s: T,
+    k: f64,
+    t: T,
+    sigma: T,
+    r: T,
+    q: T,
+    underlying_type: OptionType,
+    compound: &Compound,
+) -> T {
+    (compound, underlying_type).price(&Inputs::new(s, k, t, sigma, r, q))
+}
+
+/// Calculate lookback price and Greeks
+pub fn calculate_lookback_greeks(params: &BlackScholesParams, option_type: OptionType, lookback: &Lookback) -> Greeks {
+    closed_form::greeks(params, &(lookback, option_type))
+}
+
+/// Calculate lookback price and Greeks after validating the inputs
+///
+/// The running extreme must lie on the right side of spot.
+pub fn try_calculate_lookback_greeks(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    lookback: &Lookback,
+) -> Result<Greeks, PricingError> {
+    params.validate()?;
+    lookback.validate()?;
+    let consistent = if lookback.tracks_minimum(option_type) {
+        lookback.extreme <= params.spot
+    } else {
+        lookback.extreme >= params.spot
+    };
+    if !consistent {
+        return Err(PricingError::InvalidParameter { name: "extreme", value: lookback.extreme });
+    }
+    Ok(calculate_lookback_greeks(params, option_type, lookback))
+}
+
+/// Calculate chooser price and Greeks
+pub fn calculate_chooser_greeks(params: &BlackScholesParams, chooser: &Chooser) -> Greeks {
+    closed_form::greeks(params, chooser)
+}
+
+/// Calculate chooser price and Greeks after validating the inputs
+pub fn try_calculate_chooser_greeks(params: &BlackScholesParams, chooser: &Chooser) -> Result<Greeks, PricingError> {
+    params.validate()?;
+    chooser.validate()?;
+    if chooser.choice_time > params.time_to_maturity {
+        return Err(PricingError::InvalidParameter { name: "choice_time", value: chooser.choice_time });
+    }
+    Ok(calculate_chooser_greeks(params, chooser))
+}
+
+/// Calculate compound price and Greeks
+pub fn calculate_compound_greeks(params: &BlackScholesParams, underlying_type: OptionType, compound: &Compound) -> Greeks {
+    closed_form::greeks(params, &(compound, underlying_type))
+}
+
+/// Calculate compound price and Greeks after validating the inputs
+pub fn try_calculate_compound_greeks(
+    params: &BlackScholesParams,
+    underlying_type: OptionType,
+    compound: &Compound,
+) -> Result<Greeks, PricingError> {
+    params.validate()?;
+    compound.validate()?;
+    if compound.expiry > params.time_to_maturity {
+        return Err(PricingError::InvalidParameter { name: "expiry", value: compound.expiry });
+    }
+    Ok(calculate_compound_greeks(params, underlying_type, compound))
+}
+
+impl<T: Scalar> Inputs<T> {
+    /// Time to a date given as time from now
+    fn date(&self, time: f64) -> T {
+        -self.elapsed + time
+    }
+
+    fn vanilla(&self, option_type: OptionType, s: T, k: f64, t: T) -> T {
+        match option_type {
+            OptionType::Call => black_scholes::call_price(s, k, t, self.sigma, self.r, self.q),
+            OptionType::Put => black_scholes::put_price(s, k, t, self.sigma, self.r, self.q),
+        }
+    }
+
+    /// Value of a vanilla at some spot once `time` has passed, as plain numbers
+    fn forward_vanilla(&self, option_type: OptionType, k: f64, time: f64) -> impl Fn(f64) -> (f64, f64) + '_ {
+        let (sigma, r, q) = (Dual::constant(self.sigma.value()), Dual::constant(self.r.value()), Dual::constant(self.q.value()));
+        move |spot| {
+            let s = Dual::variable(spot);
+            let t = Dual::constant(time);
+            let value = match option_type {
+                OptionType::Call => black_scholes::call_price(s, k, t, sigma, r, q),
+                OptionType::Put => black_scholes::put_price(s, k, t, sigma, r, q),
+            };
+            (value.value, value.deriv)
+        }
+    }
+
+    /// S e^(-rT) σ²/2b [η (S/H)^(-2b/σ²) N(η(2b√T/σ - a1)) - η e^(bT) N(-η a1)], the
+    /// term by which a lookback on the running extreme H exceeds a vanilla struck at H
+    ///
+    /// The bracket vanishes with b, so near b = 0 it is integrated from its
+    /// derivative in b rather than divided.
+    fn reflection(&self, level: f64, eta: f64) -> T {
+        let carry = self.r - self.q;
+        let variance = self.sigma.powi2();
+        let std_dev = self.sigma * self.t.sqrt();
+        let log_moneyness = (self.s / level).ln();
+        // ∂a1/∂b = √T/σ
+        let slope_a1 = self.t / std_dev;
+        let exponent = log_moneyness * 2.0 / variance;
+        let a1 = |beta: T| (log_moneyness + (beta + variance * 0.5) * self.t) / std_dev;
+
+        let span = carry.value().abs() * (exponent.value().abs() + self.t.value());
+        let ratio = if span > 0.5 {
+            let a1 = a1(carry);
+            ((-exponent * carry).exp() * norm_cdf((carry * 2.0 * slope_a1 - a1) * eta)
+                - (carry * self.t).exp() * norm_cdf(-a1 * eta))
+                * eta
+                / carry
+        } else {
+            let derivative = |beta: T| {
+                let a1 = a1(beta);
+                let c = beta * 2.0 * slope_a1 - a1;
+                (-exponent * beta).exp() * (-exponent * norm_cdf(c * eta) + slope_a1 * norm_pdf(c) * eta)
+                    - (beta * self.t).exp() * (self.t * norm_cdf(-a1 * eta) - slope_a1 * norm_pdf(a1) * eta)
+            };
+            let integral = GAUSS_LEGENDRE_20.iter().fold(T::constant(0.0), |sum, &(x, w)| {
+                sum + (derivative(carry * (0.5 * (1.0 - x))) + derivative(carry * (0.5 * (1.0 + x)))) * (0.5 * w)
+            });
+            integral * eta
+        };
+        self.s * (-self.r * self.t).exp() * variance * 0.5 * ratio
+    }
+}
+
+impl ClosedForm for (&Lookback, OptionType) {
+    fn price<T: Scalar>(&self, x: &Inputs<T>) -> T {
+        let (lookback, option_type) = *self;
+        let minimum = lookback.tracks_minimum(option_type);
+        if x.t.value() <= 0.0 {
+            let extreme = if minimum {
+                x.s.min(T::constant(lookback.extreme))
+            } else {
+                x.s.max(T::constant(lookback.extreme))
+            };
+            return match (lookback.lookback_type, option_type) {
+                (LookbackType::FloatingStrike, OptionType::Call) => x.s - extreme,
+                (LookbackType::FloatingStrike, OptionType::Put) => extreme - x.s,
+                (LookbackType::FixedStrike, OptionType::Call) => (extreme - x.k).max(T::constant(0.0)),
+                (LookbackType::FixedStrike, OptionType::Put) => (-extreme + x.k).max(T::constant(0.0)),
+            };
+        }
+
+        // A fixed strike already passed by the extreme locks in the difference
+        let (level, locked_in) = match (lookback.lookback_type, option_type) {
+            (LookbackType::FloatingStrike, _) => (lookback.extreme, 0.0),
+            (LookbackType::FixedStrike, OptionType::Call) => {
+                let level = x.k.max(lookback.extreme);
+                (level, level - x.k)
+            }
+            (LookbackType::FixedStrike, OptionType::Put) => {
+                let level = x.k.min(lookback.extreme);
+                (level, x.k - level)
+            }
+        };
+        let eta = if minimum { 1.0 } else { -1.0 };
+        (-x.r * x.t).exp() * locked_in + x.vanilla(option_type, x.s, level, x.t) + x.reflection(level, eta)
+    }
+}
+
+impl ClosedForm for Chooser {
+    fn price<T: Scalar>(&self, x: &Inputs<T>) -> T {
+        let choice = x.date(self.choice_time);
+        let put_maturity = x.date(self.put_maturity);
+        if choice.value() <= 0.0 {
+            let call = x.vanilla(OptionType::Call, x.s, x.k, x.t);
+            return call.max(x.vanilla(OptionType::Put, x.s, self.put_strike, put_maturity));
+        }
+
+        let carry = x.r - x.q;
+        let variance = x.sigma.powi2();
+        let log_moneyness = |strike: f64| (x.s / strike).ln();
+        let vol = |time: T| x.sigma * time.sqrt();
+
+        if self.put_strike == x.k && put_maturity.value() == x.t.value() {
+            // The put is a call plus K e^(-rT) - S e^(-qT), so the choice is a put
+            // maturing at the choice date on the strike discounted to it
+            let d = (log_moneyness(x.k) + (carry + variance * 0.5) * x.t) / vol(x.t);
+            let y = (log_moneyness(x.k) + carry * x.t + variance * choice * 0.5) / vol(choice);
+            return x.s * (-x.q * x.t).exp() * (norm_cdf(d) - norm_cdf(-y))
+                - (-x.r * x.t).exp() * (norm_cdf(d - vol(x.t)) - norm_cdf(-y + vol(choice))) * x.k;
+        }
+
+        // Spot at the choice date where the call and the put are worth the same
+        let call = x.forward_vanilla(OptionType::Call, x.k, (x.t - choice).value());
+        let put = x.forward_vanilla(OptionType::Put, self.put_strike, (put_maturity - choice).value());
+        let critical = critical_spot(
+            |spot| {
+                let ((c, dc), (p, dp)) = (call(spot), put(spot));
+                (c - p, dc - dp)
+            },
+            true,
+            0.5 * (x.k + self.put_strike),
+        );
+
+        let d1 = ((x.s / critical).ln() + (carry + variance * 0.5) * choice) / vol(choice);
+        let d2 = d1 - vol(choice);
+        let y1 = (log_moneyness(x.k) + (carry + variance * 0.5) * x.t) / vol(x.t);
+        let y2 = (log_moneyness(self.put_strike) + (carry + variance * 0.5) * put_maturity) / vol(put_maturity);
+        let rho_call = (choice / x.t).sqrt();
+        let rho_put = (choice / put_maturity).sqrt();
+        x.s * (-x.q * x.t).exp() * bivariate_norm_cdf(d1, y1, rho_call)
+            - (-x.r * x.t).exp() * bivariate_norm_cdf(d2, y1 - vol(x.t), rho_call) * x.k
+            - x.s * (-x.q * put_maturity).exp() * bivariate_norm_cdf(-d1, -y2, rho_put)
+            + (-x.r * put_maturity).exp() * bivariate_norm_cdf(-d2, -y2 + vol(put_maturity), rho_put) * self.put_strike
+    }
+}
+
+impl ClosedForm for (&Compound, OptionType) {
+    fn price<T: Scalar>(&self, x: &Inputs<T>) -> T {
+        let (compound, underlying_type) = *self;
+        let expiry = x.date(compound.expiry);
+        let premium = compound.strike;
+        // +1 for a call, -1 for a put, on the compound and on the underlying option
+        let psi = if compound.option_type == OptionType::Call { 1.0 } else { -1.0 };
+        let omega = if underlying_type == OptionType::Call { 1.0 } else { -1.0 };
+
+        if expiry.value() <= 0.0 {
+            let underlying = x.vanilla(underlying_type, x.s, x.k, x.t);
+            return ((underlying - premium) * psi).max(T::constant(0.0));
+        }
+
+        let remaining = (x.t - expiry).value();
+        let discounted_premium = (-x.r * expiry).exp() * premium;
+        // A put is worth at most its discounted strike, so a premium above that is never paid
+        if underlying_type == OptionType::Put && premium >= x.k * (-x.r.value() * remaining).exp() {
+            return match compound.option_type {
+                OptionType::Call => T::constant(0.0),
+                OptionType::Put => discounted_premium - x.vanilla(underlying_type, x.s, x.k, x.t),
+            };
+        }
+
+        // Spot at expiry where the underlying option is worth the premium
+        let underlying = x.forward_vanilla(underlying_type, x.k, remaining);
+        let critical = critical_spot(
+            |spot| {
+                let (value, slope) = underlying(spot);
+                (value - premium, slope)
+            },
+            underlying_type == OptionType::Call,
+            x.k,
+        );
+
+        let drift = x.r - x.q + x.sigma.powi2() * 0.5;
+        let vol_expiry = x.sigma * expiry.sqrt();
+        let vol_maturity = x.sigma * x.t.sqrt();
+        let y1 = ((x.s / critical).ln() + drift * expiry) / vol_expiry;
+        let y2 = y1 - vol_expiry;
+        let z1 = ((x.s / x.k).ln() + drift * x.t) / vol_maturity;
+        let z2 = z1 - vol_maturity;
+        let rho = (expiry / x.t).sqrt() * psi;
+        let sign = omega * psi;
+        (x.s * (-x.q * x.t).exp() * bivariate_norm_cdf(z1 * omega, y1 * sign, rho)
+            - (-x.r * x.t).exp() * bivariate_norm_cdf(z2 * omega, y2 * sign, rho) * x.k)
+            * sign
+            - discounted_premium * norm_cdf(y2 * sign) * psi
+    }
+}
+
+/// Newton iterations allowed when solving for a critical spot
+const MAX_ITERATIONS: usize = 100;
+
+/// Spot where a monotone function, given with its slope, crosses zero
+///
+/// Newton steps that leave the bracket found so far are replaced by bisection,
+/// or by doubling while no upper bound is known.
+fn critical_spot(f: impl Fn(f64) -> (f64, f64), increasing: bool, guess: f64) -> f64 {
+    let (mut low, mut high) = (0.0, f64::INFINITY);
+    let mut spot = guess;
+    for _ in 0..MAX_ITERATIONS {
+        let (value, slope) = f(spot);
+        if value == 0.0 {
+            return spot;
+        }
+        if (value > 0.0) == increasing {
+            high = spot;
+        } else {
+            low = spot;
+        }
+        let newton = spot - value / slope;
+        let next = if newton > low && newton < high {
+            newton
+        } else if high.is_finite() {
+            0.5 * (low + high)
+        } else {
+            2.0 * spot
+        };
+        if (next - spot).abs() <= 1e-14 * spot {
+            return next;
+        }
+        spot = next;
+    }
+    spot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_lookback_reference_prices() {
+        // Haug, The Complete Guide to Option Pricing Formulas (2007), with b = 0.04
+        let floating = |extreme| Lookback::new(LookbackType::FloatingStrike, extreme);
+        let fixed = |extreme| Lookback::new(LookbackType::FixedStrike, extreme);
+        let cases = [
+            (120.0, 0.0, 0.5, 0.3, 0.1, 0.06, OptionType::Call, floating(100.0), 25.3533552718102),
+            (100.0, 0.0, 0.75, 0.25, 0.05, 0.02, OptionType::Put, floating(110.0), 18.63451598300171),
+            (100.0, 95.0, 0.5, 0.3, 0.1, 0.0, OptionType::Call, fixed(100.0), 24.98576014032538),
+            (100.0, 95.0, 1.0, 0.2, 0.04, 0.03, OptionType::Put, fixed(90.0), 11.03854575912557),
+            // No carry, where the closed form is 0/0
+            (100.0, 0.0, 1.0, 0.2, 0.03, 0.03, OptionType::Call, floating(95.0), 14.97916730186887),
+        ];
+        for &(s, k, t, sigma, r, q, option_type, lookback, expected) in &cases {
+            assert_relative_eq!(lookback_price(s, k, t, sigma, r, q, option_type, &lookback), expected, max_relative = 1e-13);
+            // Continuous across the switch between the closed form and the integral
+            for carry in [-0.6, -1e-9, 1e-9, 0.6] {
+                let below = lookback_price(s, k, t, sigma, r, q - carry, option_type, &lookback);
+                let above = lookback_price(s, k, t, sigma, r, q - carry * (1.0 + 1e-9), option_type, &lookback);
+                assert_relative_eq!(below, above, max_relative = 1e-8);
+            }
+        }
+
+        // A fixed-strike call on a fresh maximum is a floating-strike put plus a forward
+        let params = BlackScholesParams::new(100.0, 90.0, 0.8, 0.25, 0.05, 0.01);
+        let fixed_call = calculate_lookback_greeks(&params, OptionType::Call, &fixed(100.0)).price;
+        let floating_put = calculate_lookback_greeks(&params, OptionType::Put, &floating(100.0)).price;
+        let forward = 100.0 * (-0.01_f64 * 0.8).exp() - 90.0 * (-0.05_f64 * 0.8).exp();
+        assert_relative_eq!(fixed_call, floating_put + forward, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_chooser_reference_prices() {
+        // Haug (2007): simple chooser with b = r, complex chooser with b = 0.05
+        assert_relative_eq!(chooser_price(50.0, 50.0, 0.5, 0.25, 0.08, 0.0, &Chooser::new(0.25, 50.0, 0.5)), 6.107077498162338, epsilon = 1e-13);
+        let complex = Chooser::new(0.25, 48.0, 0.5833);
+        assert_relative_eq!(chooser_price(50.0, 55.0, 0.5, 0.35, 0.1, 0.05, &complex), 6.050719028266359, epsilon = 1e-12);
+
+        // The complex formula reproduces the simple one on shared terms
+        let params = BlackScholesParams::new(100.0, 105.0, 1.0, 0.3, 0.04, 0.02);
+        let simple = calculate_chooser_greeks(&params, &Chooser::simple(0.4, &params));
+        let nearly = calculate_chooser_greeks(&params, &Chooser::new(0.4, 105.0 * (1.0 + 1e-10), 1.0));
+        assert_relative_eq!(simple.price, nearly.price, epsilon = 1e-8);
+        assert_relative_eq!(simple.delta, nearly.delta, epsilon = 1e-8);
+        assert_relative_eq!(simple.vega, nearly.vega, epsilon = 1e-7);
+
+        // Choosing now takes the better of the two
+        let now = calculate_chooser_greeks(&params, &Chooser::new(0.0, 110.0, 1.5));
+        let put = black_scholes::put_price(100.0, 110.0, 1.5, 0.3, 0.04, 0.02);
+        assert_relative_eq!(now.price, put.max(black_scholes::call_price(100.0, 105.0, 1.0, 0.3, 0.04, 0.02)));
+    }
+
+    #[test]
+    fn test_compound_reference_prices() {
+        // Haug (2007): put on call with b = 0.05
+        let put_on_call = Compound::new(OptionType::Put, 50.0, 0.25);
+        assert_relative_eq!(compound_price(500.0, 520.0, 0.5, 0.35, 0.08, 0.03, OptionType::Call, &put_on_call), 21.19635039435238, epsilon = 1e-11);
+        let call_on_put = Compound::new(OptionType::Call, 4.0, 0.5);
+        assert_relative_eq!(compound_price(100.0, 95.0, 1.5, 0.3, 0.05, 0.02, OptionType::Put, &call_on_put), 6.011774693956166, epsilon = 1e-12);
+
+        // Call minus put on the same option is the option less the discounted premium
+        let params = BlackScholesParams::new(100.0, 100.0, 1.0, 0.25, 0.05, 0.02);
+        for &underlying_type in &[OptionType::Call, OptionType::Put] {
+            let underlying = black_scholes::calculate_greeks(&params, underlying_type);
+            for premium in [3.0, 8.0] {
+                let call = calculate_compound_greeks(&params, underlying_type, &Compound::new(OptionType::Call, premium, 0.4));
+                let put = calculate_compound_greeks(&params, underlying_type, &Compound::new(OptionType::Put, premium, 0.4));
+                let discount = (-0.05_f64 * 0.4).exp();
+                assert_relative_eq!(call.price - put.price, underlying.price - premium * discount, epsilon = 1e-12);
+                assert_relative_eq!(call.delta - put.delta, underlying.delta, epsilon = 1e-12);
+                assert_relative_eq!(call.vega - put.vega, underlying.vega, epsilon = 1e-11);
+            }
+        }
+
+        // A premium above what the put could ever be worth is never paid
+        let never = calculate_compound_greeks(&params, OptionType::Put, &Compound::new(OptionType::Call, 99.0, 0.4));
+        assert_eq!(never.price, 0.0);
+    }
+
+    #[test]
+    fn test_greeks_against_bumps() {
+        type Pricer<'a> = &'a dyn Fn(&BlackScholesParams, f64) -> f64;
+        let lookback = Lookback::new(LookbackType::FloatingStrike, 92.0);
+        let fixed = Lookback::new(LookbackType::FixedStrike, 108.0);
+        let chooser = Chooser::new(0.3, 95.0, 1.2);
+        let compound = Compound::new(OptionType::Call, 6.0, 0.35);
+
+        let params = BlackScholesParams::new(100.0, 100.0, 0.9, 0.28, 0.05, 0.02);
+        // Each pricer takes the time elapsed, which ages every date
+        let cases: [(Greeks, Pricer); 5] = [
+            (
+                calculate_lookback_greeks(&params, OptionType::Call, &lookback),
+                &|p, h| lookback_price(p.spot, p.strike, p.time_to_maturity - h, p.volatility, p.risk_free_rate, p.dividend_yield, OptionType::Call, &lookback),
+            ),
+            (
+                calculate_lookback_greeks(&params, OptionType::Call, &fixed),
+                &|p, h| lookback_price(p.spot, p.strike, p.time_to_maturity - h, p.volatility, p.risk_free_rate, p.dividend_yield, OptionType::Call, &fixed),
+            ),
+            (
+                calculate_chooser_greeks(&params, &chooser),
+                &|p, h| {
+                    let aged = Chooser::new(chooser.choice_time - h, chooser.put_strike, chooser.put_maturity - h);
+                    chooser_price(p.spot, p.strike, p.time_to_maturity - h, p.volatility, p.risk_free_rate, p.dividend_yield, &aged)
+                },
+            ),
+            (
+                calculate_chooser_greeks(&params, &Chooser::simple(0.3, &params)),
+                &|p, h| {
+                    let aged = Chooser::new(0.3 - h, p.strike, p.time_to_maturity - h);
+                    chooser_price(p.spot, p.strike, p.time_to_maturity - h, p.volatility, p.risk_free_rate, p.dividend_yield, &aged)
+                },
+            ),
+            (
+                calculate_compound_greeks(&params, OptionType::Put, &compound),
+                &|p, h| {
+                    let aged = Compound { expiry: compound.expiry - h, ..compound };
+                    compound_price(p.spot, p.strike, p.time_to_maturity - h, p.volatility, p.risk_free_rate, p.dividend_yield, OptionType::Put, &aged)
+                },
+            ),
+        ];
+        for (greeks, value) in cases.iter() {
+            let bumped = |f: &dyn Fn(&mut BlackScholesParams, f64), h: f64| {
+                let (mut up, mut down) = (params, params);
+                f(&mut up, h);
+                f(&mut down, -h);
+                (value(&up, 0.0) - value(&down, 0.0)) / (2.0 * h)
+            };
+            assert_relative_eq!(greeks.price, value(&params, 0.0), epsilon = 1e-13);
+            assert_relative_eq!(greeks.delta, bumped(&|p, h| p.spot += h, 1e-4), epsilon = 1e-7);
+            assert_relative_eq!(greeks.vega, bumped(&|p, h| p.volatility += h, 1e-6), epsilon = 1e-6);
+            assert_relative_eq!(greeks.theta, (value(&params, 1e-6) - value(&params, -1e-6)) / 2e-6, epsilon = 1e-6);
+            assert_relative_eq!(greeks.rho, bumped(&|p, h| p.risk_free_rate += h, 1e-6), epsilon = 1e-6);
+            assert_relative_eq!(greeks.phi, bumped(&|p, h| p.dividend_yield += h, 1e-6), epsilon = 1e-6);
+            let h = 1e-3;
+            let (mut up, mut down) = (params, params);
+            up.spot += h;
+            down.spot -= h;
+            assert_relative_eq!(greeks.gamma, (value(&up, 0.0) - 2.0 * greeks.price + value(&down, 0.0)) / (h * h), epsilon = 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_expiry_and_validation() {
+        let expired = BlackScholesParams::new(104.0, 100.0, 0.0, 0.2, 0.03, 0.0);
+        let floating = Lookback::new(LookbackType::FloatingStrike, 90.0);
+        assert_relative_eq!(calculate_lookback_greeks(&expired, OptionType::Call, &floating).price, 14.0);
+        let fixed = Lookback::new(LookbackType::FixedStrike, 112.0);
+        assert_relative_eq!(calculate_lookback_greeks(&expired, OptionType::Call, &fixed).price, 12.0);
+        let compound = Compound::new(OptionType::Put, 7.0, 0.0);
+        assert_relative_eq!(calculate_compound_greeks(&expired, OptionType::Call, &compound).price, 3.0);
+
+        let params = BlackScholesParams::new(100.0, 100.0, 1.0, 0.2, 0.03, 0.0);
+        assert_eq!(
+            try_calculate_lookback_greeks(&params, OptionType::Call, &Lookback::new(LookbackType::FloatingStrike, 105.0)).unwrap_err(),
+            PricingError::InvalidParameter { name: "extreme", value: 105.0 }
+        );
+        assert!(try_calculate_lookback_greeks(&params, OptionType::Put, &Lookback::new(LookbackType::FloatingStrike, 105.0)).is_ok());
+        assert_eq!(
+            try_calculate_chooser_greeks(&params, &Chooser::new(1.5, 100.0, 2.0)).unwrap_err(),
+            PricingError::InvalidParameter { name: "choice_time", value: 1.5 }
+        );
+        assert_eq!(
+            try_calculate_chooser_greeks(&params, &Chooser::new(0.5, 100.0, 0.25)).unwrap_err(),
+            PricingError::InvalidParameter { name: "put_maturity", value: 0.25 }
+        );
+        assert_eq!(
+            try_calculate_compound_greeks(&params, OptionType::Call, &Compound::new(OptionType::Call, -1.0, 0.5)).unwrap_err(),
+            PricingError::NonPositiveStrike(-1.0)
+        );
+        assert_eq!(
+            try_calculate_compound_greeks(&params, OptionType::Call, &Compound::new(OptionType::Call, 5.0, 1.5)).unwrap_err(),
+            PricingError::InvalidParameter { name: "expiry", value: 1.5 }
+        );
+    }
+}
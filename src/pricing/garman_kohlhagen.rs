@@ -0,0 +1,383 @@
+//! Garman-Kohlhagen pricing of FX options with the market's delta conventions
+//!
+//! The spot is quoted as units of domestic currency per unit of foreign, and the
+//! foreign interest rate plays the role of a continuous dividend yield, so the
+//! prices and Greeks come from the Black-Scholes AD core. What is FX specific is
+//! how delta is quoted: against spot or forward, and with or without the premium
+//! (paid in foreign currency) included in the hedge.
+
+use crate::ad::{norm_cdf, norm_pdf, Dual};
+use crate::pricing::black_scholes::{self, call_price, put_price, BlackScholesParams};
+use crate::pricing::lets_be_rational::inverse_norm_cdf;
+use crate::pricing::PricingError;
+use crate::types::{Greeks, OptionType};
+
+/// Garman-Kohlhagen pricing parameters
+#[derive(Debug, Clone, Copy)]
+pub struct GarmanKohlhagenParams {
+    /// Domestic currency units per unit of foreign currency
+    pub spot: f64,
+    pub strike: f64,
+    pub time_to_maturity: f64,
+    pub volatility: f64,
+    pub domestic_rate: f64,
+    pub foreign_rate: f64,
+}
+
+impl GarmanKohlhagenParams {
+    pub fn new(
+        spot: f64,
+        strike: f64,
+        time_to_maturity: f64,
+        volatility: f64,
+        domestic_rate: f64,
+        foreign_rate: f64,
+    ) -> Self {
+        Self {
+            spot,
+            strike,
+            time_to_maturity,
+            volatility,
+            domestic_rate,
+            foreign_rate,
+        }
+    }
+
+    /// Create parameters, rejecting inputs the model cannot price
+    pub fn try_new(
+        spot: f64,
+        strike: f64,
+        time_to_maturity: f64,
+        volatility: f64,
+        domestic_rate: f64,
+        foreign_rate: f64,
+    ) -> Result<Self, PricingError> {
+        let params = Self::new(spot, strike, time_to_maturity, volatility, domestic_rate, foreign_rate);
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Check that every input is finite and within the model's domain
+    pub fn validate(&self) -> Result<(), PricingError> {
+        let fields = [
+            ("spot", self.spot),
+            ("strike", self.strike),
+            ("time_to_maturity", self.time_to_maturity),
+            ("volatility", self.volatility),
+            ("domestic_rate", self.domestic_rate),
+            ("foreign_rate", self.foreign_rate),
+        ];
+        if let Some(&(name, _)) = fields.iter().find(|(_, v)| !v.is_finite()) {
+            return Err(PricingError::NonFinite(name));
+        }
+        self.black_scholes().validate()
+    }
+
+    /// Outright forward F = S e^((r_d - r_f) T)
+    pub fn forward(&self) -> f64 {
+        self.spot * ((self.domestic_rate - self.foreign_rate) * self.time_to_maturity).exp()
+    }
+
+    /// The same option expressed with the foreign rate as a dividend yield
+    fn black_scholes(&self) -> BlackScholesParams {
+        BlackScholesParams::new(
+            self.spot,
+            self.strike,
+            self.time_to_maturity,
+            self.volatility,
+            self.domestic_rate,
+            self.foreign_rate,
+        )
+    }
+}
+
+/// How an FX delta is quoted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeltaConvention {
+    /// ∂V/∂S = θ e^(-r_f T) N(θ d1)
+    Spot,
+    /// Hedge in the outright forward: θ N(θ d1)
+    Forward,
+    /// Spot delta less the premium in foreign currency: θ e^(-r_f T) (K/F) N(θ d2)
+    PremiumAdjustedSpot,
+    /// Forward delta less the premium in foreign currency: θ (K/F) N(θ d2)
+    PremiumAdjustedForward,
+}
+
+impl DeltaConvention {
+    fn is_premium_adjusted(self) -> bool {
+        matches!(self, DeltaConvention::PremiumAdjustedSpot | DeltaConvention::PremiumAdjustedForward)
+    }
+
+    fn is_spot(self) -> bool {
+        matches!(self, DeltaConvention::Spot | DeltaConvention::PremiumAdjustedSpot)
+    }
+}
+
+/// Calculate price (in domestic currency per unit of foreign notional) and Greeks
+///
+/// Delta is the spot delta; rho is the sensitivity to the domestic rate and phi
+/// to the foreign rate.
+pub fn calculate_greeks(params: &GarmanKohlhagenParams, option_type: OptionType) -> Greeks {
+    black_scholes::calculate_greeks(&params.black_scholes(), option_type)
+}
+
+/// Calculate price and Greeks after validating the inputs
+pub fn try_calculate_greeks(params: &GarmanKohlhagenParams, option_type: OptionType) -> Result<Greeks, PricingError> {
+    params.validate()?;
+    Ok(calculate_greeks(params, option_type))
+}
+
+/// Delta under the given market convention
+///
+/// The spot delta and premium come from one pricing pass with spot seeded on a
+/// `Dual`; the other conventions rescale them.
+pub fn delta(params: &GarmanKohlhagenParams, option_type: OptionType, convention: DeltaConvention) -> f64 {
+    let s = Dual::variable(params.spot);
+    let t = Dual::constant(params.time_to_maturity);
+    let sigma = Dual::constant(params.volatility);
+    let rd = Dual::constant(params.domestic_rate);
+    let rf = Dual::constant(params.foreign_rate);
+    let result = match option_type {
+        OptionType::Call => call_price(s, params.strike, t, sigma, rd, rf),
+        OptionType::Put => put_price(s, params.strike, t, sigma, rd, rf),
+    };
+
+    let mut delta = result.deriv;
+    if convention.is_premium_adjusted() {
+        // Premium paid in foreign currency, per unit of foreign notional
+        delta -= result.value / params.spot;
+    }
+    if !convention.is_spot() {
+        delta *= (params.foreign_rate * params.time_to_maturity).exp();
+    }
+    delta
+}
+
+/// Strike whose delta under `convention` equals `target_delta`
+///
+/// Quotes such as "25-delta put" carry the sign of the option, so put deltas are
+/// negative. Unadjusted deltas invert in closed form. Premium-adjusted deltas are
+/// solved by bisection in log strike; for calls the adjusted delta is not
+/// monotonic in strike, and the market convention takes the strike above the
+/// delta's maximum.
+#[allow(clippy::too_many_arguments)]
+pub fn strike_from_delta(
+    target_delta: f64,
+    spot: f64,
+    time_to_maturity: f64,
+    volatility: f64,
+    domestic_rate: f64,
+    foreign_rate: f64,
+    option_type: OptionType,
+    convention: DeltaConvention,
+) -> Result<f64, PricingError> {
+    let params = GarmanKohlhagenParams::try_new(spot, spot, time_to_maturity, volatility, domestic_rate, foreign_rate)?;
+    if !target_delta.is_finite() {
+        return Err(PricingError::NonFinite("target_delta"));
+    }
+    if time_to_maturity == 0.0 {
+        return Err(PricingError::DeltaOutOfRange(target_delta));
+    }
+
+    let sign = match option_type {
+        OptionType::Call => 1.0,
+        OptionType::Put => -1.0,
+    };
+    // Unadjusted forward deltas lie in (0, 1) for calls and (-1, 0) for puts
+    let forward_delta = if convention.is_spot() {
+        target_delta * (foreign_rate * time_to_maturity).exp()
+    } else {
+        target_delta
+    };
+    if sign * forward_delta <= 0.0 || (!convention.is_premium_adjusted() && sign * forward_delta >= 1.0) {
+        return Err(PricingError::DeltaOutOfRange(target_delta));
+    }
+
+    let forward = params.forward();
+    let total_vol = volatility * time_to_maturity.sqrt();
+    // Strike for a given d1: ln K = ln F - d1 σ√T + σ²T/2
+    let strike_for_d1 = |d1: f64| forward * (-d1 * total_vol + 0.5 * total_vol * total_vol).exp();
+    if !convention.is_premium_adjusted() {
+        return Ok(strike_for_d1(sign * inverse_norm_cdf(sign * forward_delta)));
+    }
+
+    // Past the call's peak, and everywhere for puts, the adjusted delta falls as the strike
+    // rises, so this is increasing in strike on the branch we solve on
+    let excess = |strike: f64| target_delta - delta(&GarmanKohlhagenParams { strike, ..params }, option_type, convention);
+
+    let (mut lo, mut hi) = match option_type {
+        OptionType::Call => {
+            // Adjusted call delta peaks where σ√T N(d2) = φ(d2) and falls away on either side
+            let peak = strike_for_d1(peak_d2(total_vol) + total_vol);
+            if excess(peak) > 0.0 {
+                return Err(PricingError::DeltaOutOfRange(target_delta));
+            }
+            let mut hi = 2.0 * peak;
+            while excess(hi) <= 0.0 {
+                hi *= 2.0;
+            }
+            (peak, hi)
+        }
+        OptionType::Put => {
+            let (mut lo, mut hi) = (forward, forward);
+            while excess(hi) <= 0.0 {
+                hi *= 2.0;
+            }
+            while excess(lo) >= 0.0 {
+                lo *= 0.5;
+            }
+            (lo, hi)
+        }
+    };
+
+    for _ in 0..MAX_BISECTIONS {
+        let mid = (lo * hi).sqrt();
+        if hi - lo <= STRIKE_TOLERANCE * mid {
+            return Ok(mid);
+        }
+        if excess(mid) < 0.0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Ok((lo * hi).sqrt())
+}
+
+const MAX_BISECTIONS: usize = 200;
+const STRIKE_TOLERANCE: f64 = 4.0 * f64::EPSILON;
+
+/// d2 at which the premium-adjusted call delta (K/F) N(d2) is largest,
+/// i.e. the root of σ√T N(d2) = φ(d2), found by bisection
+fn peak_d2(total_vol: f64) -> f64 {
+    let g = |d2: f64| total_vol * norm_cdf(d2) - norm_pdf(d2);
+    // g < 0 at -σ√T by the Mills ratio bound, and g → σ√T > 0 as d2 grows
+    let mut lo = -total_vol;
+    let mut hi = 1.0;
+    while g(hi) <= 0.0 {
+        hi *= 2.0;
+    }
+    for _ in 0..MAX_BISECTIONS {
+        let mid = 0.5 * (lo + hi);
+        if hi - lo <= f64::EPSILON * mid.abs().max(1.0) {
+            break;
+        }
+        if g(mid) < 0.0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    0.5 * (lo + hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn eur_usd() -> GarmanKohlhagenParams {
+        GarmanKohlhagenParams::new(1.10, 1.15, 0.75, 0.12, 0.045, 0.03)
+    }
+
+    #[test]
+    fn test_delta_conventions_match_closed_form() {
+        let params = eur_usd();
+        let GarmanKohlhagenParams { strike, time_to_maturity: t, volatility: vol, foreign_rate: rf, .. } = params;
+        let forward = params.forward();
+        let total_vol = vol * t.sqrt();
+        let d1 = (forward / strike).ln() / total_vol + 0.5 * total_vol;
+        let d2 = d1 - total_vol;
+        let df_f = (-rf * t).exp();
+
+        for &(option_type, sign) in &[(OptionType::Call, 1.0), (OptionType::Put, -1.0)] {
+            let n1 = norm_cdf(sign * d1);
+            let n2 = norm_cdf(sign * d2);
+            let k_over_f = strike / forward;
+
+            assert_relative_eq!(delta(&params, option_type, DeltaConvention::Spot), sign * df_f * n1, epsilon = 1e-14);
+            assert_relative_eq!(delta(&params, option_type, DeltaConvention::Forward), sign * n1, epsilon = 1e-14);
+            assert_relative_eq!(
+                delta(&params, option_type, DeltaConvention::PremiumAdjustedSpot),
+                sign * df_f * k_over_f * n2,
+                epsilon = 1e-14
+            );
+            assert_relative_eq!(
+                delta(&params, option_type, DeltaConvention::PremiumAdjustedForward),
+                sign * k_over_f * n2,
+                epsilon = 1e-14
+            );
+            assert_relative_eq!(calculate_greeks(&params, option_type).delta, sign * df_f * n1, epsilon = 1e-14);
+        }
+    }
+
+    #[test]
+    fn test_foreign_rate_sensitivity() {
+        // Phi is the foreign-rate rho: -S T e^(-r_f T) N(d1) for a call
+        let params = eur_usd();
+        let greeks = calculate_greeks(&params, OptionType::Call);
+        let spot_delta = delta(&params, OptionType::Call, DeltaConvention::Spot);
+        assert_relative_eq!(greeks.phi, -params.spot * params.time_to_maturity * spot_delta, epsilon = 1e-14);
+    }
+
+    #[test]
+    fn test_strike_from_delta_round_trip() {
+        let conventions = [
+            DeltaConvention::Spot,
+            DeltaConvention::Forward,
+            DeltaConvention::PremiumAdjustedSpot,
+            DeltaConvention::PremiumAdjustedForward,
+        ];
+        for &(t, vol) in &[(1.0 / 52.0, 0.08), (0.75, 0.12), (5.0, 0.25)] {
+            for &convention in &conventions {
+                for &(option_type, target) in &[
+                    (OptionType::Call, 0.25),
+                    (OptionType::Call, 0.10),
+                    (OptionType::Put, -0.25),
+                    (OptionType::Put, -0.10),
+                    (OptionType::Put, -0.75),
+                ] {
+                    let strike = strike_from_delta(target, 1.10, t, vol, 0.045, 0.03, option_type, convention).unwrap();
+                    let params = GarmanKohlhagenParams::new(1.10, strike, t, vol, 0.045, 0.03);
+                    assert_relative_eq!(delta(&params, option_type, convention), target, epsilon = 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_premium_adjusted_call_takes_upper_strike() {
+        // Long-dated, high-vol calls reach a maximum adjusted delta well below one
+        let (t, vol) = (5.0, 0.4);
+        let strike =
+            strike_from_delta(0.25, 1.10, t, vol, 0.045, 0.03, OptionType::Call, DeltaConvention::PremiumAdjustedForward)
+                .unwrap();
+
+        let params = GarmanKohlhagenParams::new(1.10, strike, t, vol, 0.045, 0.03);
+        let forward = params.forward();
+        let peak = forward * (-peak_d2(vol * t.sqrt()) * vol * t.sqrt() - 0.5 * vol * vol * t).exp();
+        assert!(strike > peak);
+
+        // A delta above the peak has no solution
+        assert_eq!(
+            strike_from_delta(0.9, 1.10, t, vol, 0.045, 0.03, OptionType::Call, DeltaConvention::PremiumAdjustedForward)
+                .unwrap_err(),
+            PricingError::DeltaOutOfRange(0.9)
+        );
+    }
+
+    #[test]
+    fn test_atm_delta_neutral_strike() {
+        // A 50-delta forward call strikes at F e^(σ²T/2)
+        let params = eur_usd();
+        let strike = strike_from_delta(0.5, 1.10, 0.75, 0.12, 0.045, 0.03, OptionType::Call, DeltaConvention::Forward)
+            .unwrap();
+        assert_relative_eq!(strike, params.forward() * (0.5 * 0.12_f64 * 0.12 * 0.75).exp(), epsilon = 1e-14);
+
+        assert_eq!(
+            strike_from_delta(-0.25, 1.10, 0.75, 0.12, 0.045, 0.03, OptionType::Call, DeltaConvention::Spot).unwrap_err(),
+            PricingError::DeltaOutOfRange(-0.25)
+        );
+    }
+}
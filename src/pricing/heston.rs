@@ -0,0 +1,633 @@
+//! Heston stochastic volatility model
+//!
+//! dS/S = (r - q) dt + √v dW₁,  dv = κ(θ - v) dt + σ√v dW₂,  dW₁ dW₂ = ρ dt
+//!
+//! European options are priced with Lewis's (2001) single-integral formula,
+//! using the characteristic function in the "little trap" form of Albrecher
+//! et al. (2007), which stays on the principal branch of the logarithm for all
+//! maturities. The integral is taken relative to Black-Scholes at the mean
+//! expected variance, which removes the bulk of the integrand, and evaluated by
+//! Gauss-Laguerre quadrature scaled to that variance.
+//!
+//! The characteristic function is written over `Complex<T>` for any `Scalar`,
+//! so market Greeks and sensitivities to the five model parameters come from
+//! AD. The quadratic-exponential (QE) scheme of Andersen (2008) gives an
+//! independent Monte Carlo price.
+
+use crate::ad::{Complex, HyperDual, MultiDual, Scalar};
+use crate::pricing::black_scholes::{expired_greeks, BlackScholesParams};
+use crate::pricing::fourier;
+use crate::pricing::lets_be_rational::inverse_norm_cdf;
+use crate::pricing::monte_carlo::{MonteCarloSettings, PriceEstimate, RngStream};
+use crate::pricing::PricingError;
+use crate::types::{Greeks, OptionData, OptionType};
+use rayon::prelude::*;
+
+/// Heston model and market parameters
+#[derive(Debug, Clone, Copy)]
+pub struct HestonParams {
+    pub spot: f64,
+    pub risk_free_rate: f64,
+    pub dividend_yield: f64,
+    /// Instantaneous variance v₀
+    pub initial_variance: f64,
+    /// Speed of mean reversion κ
+    pub mean_reversion: f64,
+    /// Long-run variance θ
+    pub long_run_variance: f64,
+    /// Volatility of variance σ
+    pub vol_of_vol: f64,
+    /// Correlation ρ between spot and variance shocks
+    pub correlation: f64,
+}
+
+impl HestonParams {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        spot: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        initial_variance: f64,
+        mean_reversion: f64,
+        long_run_variance: f64,
+        vol_of_vol: f64,
+        correlation: f64,
+    ) -> Self {
+        Self {
+            spot,
+            risk_free_rate,
+            dividend_yield,
+            initial_variance,
+            mean_reversion,
+            long_run_variance,
+            vol_of_vol,
+            correlation,
+        }
+    }
+
+    /// Create parameters, rejecting inputs the model cannot price
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        spot: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        initial_variance: f64,
+        mean_reversion: f64,
+        long_run_variance: f64,
+        vol_of_vol: f64,
+        correlation: f64,
+    ) -> Result<Self, PricingError> {
+        let params = Self::new(
+            spot,
+            risk_free_rate,
+            dividend_yield,
+            initial_variance,
+            mean_reversion,
+            long_run_variance,
+            vol_of_vol,
+            correlation,
+        );
+        params.validate()?;
+        Ok(params)
+    }
+
+    /// Check that every input is finite and within the model's domain
+    pub fn validate(&self) -> Result<(), PricingError> {
+        let fields = [
+            ("spot", self.spot),
+            ("risk_free_rate", self.risk_free_rate),
+            ("dividend_yield", self.dividend_yield),
+            ("initial_variance", self.initial_variance),
+            ("mean_reversion", self.mean_reversion),
+            ("long_run_variance", self.long_run_variance),
+            ("vol_of_vol", self.vol_of_vol),
+            ("correlation", self.correlation),
+        ];
+        if let Some(&(name, _)) = fields.iter().find(|(_, v)| !v.is_finite()) {
+            return Err(PricingError::NonFinite(name));
+        }
+
+        if self.spot <= 0.0 {
+            return Err(PricingError::NonPositiveSpot(self.spot));
+        }
+        let invalid = |name, value| Err(PricingError::InvalidParameter { name, value });
+        if self.initial_variance < 0.0 {
+            return invalid("initial_variance", self.initial_variance);
+        }
+        if self.mean_reversion <= 0.0 {
+            return invalid("mean_reversion", self.mean_reversion);
+        }
+        if self.long_run_variance <= 0.0 {
+            return invalid("long_run_variance", self.long_run_variance);
+        }
+        if self.vol_of_vol <= 0.0 {
+            return invalid("vol_of_vol", self.vol_of_vol);
+        }
+        if self.correlation.abs() > 1.0 {
+            return invalid("correlation", self.correlation);
+        }
+
+        Ok(())
+    }
+
+    /// Feller condition 2κθ ≥ σ², under which variance never reaches zero
+    pub fn satisfies_feller(&self) -> bool {
+        2.0 * self.mean_reversion * self.long_run_variance >= self.vol_of_vol * self.vol_of_vol
+    }
+
+    /// Expected variance averaged over [0, T]
+    fn mean_variance(&self, time_to_maturity: f64) -> f64 {
+        let decay = self.mean_reversion * time_to_maturity;
+        let weight = if decay > 1e-8 { (1.0 - (-decay).exp()) / decay } else { 1.0 };
+        self.long_run_variance + (self.initial_variance - self.long_run_variance) * weight
+    }
+
+    /// Black-Scholes parameters at intrinsic value, for expired options
+    fn expired(&self, strike: f64) -> BlackScholesParams {
+        BlackScholesParams::new(self.spot, strike, 0.0, 1.0, self.risk_free_rate, self.dividend_yield)
+    }
+}
+
+/// Gradient slots for the market Greeks
+const SPOT: usize = 0;
+const VOL: usize = 1;
+const TIME: usize = 2;
+const RATE: usize = 3;
+const DIVIDEND: usize = 4;
+
+/// Gradient slots for the model parameters, in the order returned by `parameter_gradient`
+const INITIAL_VARIANCE: usize = 0;
+const MEAN_REVERSION: usize = 1;
+const LONG_RUN_VARIANCE: usize = 2;
+const VOL_OF_VOL: usize = 3;
+const CORRELATION: usize = 4;
+
+/// Price a European option
+pub fn price(params: &HestonParams, strike: f64, time_to_maturity: f64, option_type: OptionType) -> f64 {
+    if time_to_maturity <= 0.0 {
+        return expired_greeks(&params.expired(strike), option_type).price;
+    }
+    Inputs::<f64>::constant(params, strike, time_to_maturity).price(option_type)
+}
+
+/// Price every option of a chain, in parallel
+///
+/// Only strike, maturity and option type are read; the quoted implied
+/// volatilities are ignored.
+pub fn price_options(params: &HestonParams, options: &[OptionData]) -> Vec<f64> {
+    options
+        .par_iter()
+        .map(|option| price(params, option.strike, option.time_to_maturity, option.option_type))
+        .collect()
+}
+
+/// Calculate option price and Greeks
+///
+/// Vega is the sensitivity to the initial volatility √v₀; theta, rho and phi
+/// follow the Black-Scholes conventions. Gamma comes from a second pass on a
+/// `HyperDual`.
+pub fn calculate_greeks(
+    params: &HestonParams,
+    strike: f64,
+    time_to_maturity: f64,
+    option_type: OptionType,
+) -> Greeks {
+    if time_to_maturity <= 0.0 {
+        return expired_greeks(&params.expired(strike), option_type);
+    }
+
+    let initial_vol = MultiDual::<5>::variable(params.initial_variance.sqrt(), VOL);
+    let result = Inputs {
+        s: MultiDual::variable(params.spot, SPOT),
+        t: MultiDual::variable(time_to_maturity, TIME),
+        r: MultiDual::variable(params.risk_free_rate, RATE),
+        q: MultiDual::variable(params.dividend_yield, DIVIDEND),
+        v0: initial_vol.powi2(),
+        ..Inputs::constant(params, strike, time_to_maturity)
+    }
+    .price(option_type);
+
+    let gamma = Inputs {
+        s: HyperDual::variable(params.spot),
+        ..Inputs::constant(params, strike, time_to_maturity)
+    }
+    .price(option_type)
+    .second_deriv();
+
+    Greeks::new(
+        result.value,
+        result.partial(SPOT),
+        gamma,
+        result.partial(VOL),
+        -result.partial(TIME),
+        result.partial(RATE),
+        result.partial(DIVIDEND),
+    )
+}
+
+/// Calculate option price and Greeks after validating the inputs
+pub fn try_calculate_greeks(
+    params: &HestonParams,
+    strike: f64,
+    time_to_maturity: f64,
+    option_type: OptionType,
+) -> Result<Greeks, PricingError> {
+    params.validate()?;
+    if !strike.is_finite() || !time_to_maturity.is_finite() {
+        return Err(PricingError::NonFinite(if strike.is_finite() { "time_to_maturity" } else { "strike" }));
+    }
+    if strike <= 0.0 {
+        return Err(PricingError::NonPositiveStrike(strike));
+    }
+    if time_to_maturity < 0.0 {
+        return Err(PricingError::NegativeMaturity(time_to_maturity));
+    }
+    Ok(calculate_greeks(params, strike, time_to_maturity, option_type))
+}
+
+/// Price and its gradient with respect to (v₀, κ, θ, σ, ρ)
+pub fn parameter_gradient(
+    params: &HestonParams,
+    strike: f64,
+    time_to_maturity: f64,
+    option_type: OptionType,
+) -> (f64, [f64; 5]) {
+    if time_to_maturity <= 0.0 {
+        return (price(params, strike, time_to_maturity, option_type), [0.0; 5]);
+    }
+
+    let result = Inputs {
+        v0: MultiDual::<5>::variable(params.initial_variance, INITIAL_VARIANCE),
+        kappa: MultiDual::variable(params.mean_reversion, MEAN_REVERSION),
+        theta: MultiDual::variable(params.long_run_variance, LONG_RUN_VARIANCE),
+        sigma: MultiDual::variable(params.vol_of_vol, VOL_OF_VOL),
+        rho: MultiDual::variable(params.correlation, CORRELATION),
+        ..Inputs::constant(params, strike, time_to_maturity)
+    }
+    .price(option_type);
+
+    (result.value, std::array::from_fn(|i| result.partial(i)))
+}
+
+/// Samples simulated per parallel task in the QE scheme
+const CHUNK_SIZE: usize = 1024;
+
+/// Switching level of ψ = s²/m² between the quadratic and exponential branches of QE
+const QE_SWITCH: f64 = 1.5;
+
+/// Price a European option by Monte Carlo with the QE discretisation
+///
+/// `settings.time_steps` sets the number of QE steps to maturity. With
+/// `settings.control_variate` the discounted terminal spot, whose expectation
+/// is known, serves as control.
+pub fn monte_carlo_price(
+    params: &HestonParams,
+    strike: f64,
+    time_to_maturity: f64,
+    option_type: OptionType,
+    settings: &MonteCarloSettings,
+) -> PriceEstimate {
+    if time_to_maturity <= 0.0 {
+        return PriceEstimate {
+            price: price(params, strike, time_to_maturity, option_type),
+            standard_error: 0.0,
+            samples: 0,
+        };
+    }
+
+    let steps = settings.time_steps.max(1);
+    let paths_per_sample = if settings.antithetic { 2 } else { 1 };
+    let samples = (settings.paths / paths_per_sample).max(2);
+    let scheme = QuadraticExponential::new(params, time_to_maturity / steps as f64);
+    let discount = (-params.risk_free_rate * time_to_maturity).exp();
+    let payoff = |spot: f64| match option_type {
+        OptionType::Call => discount * (spot - strike).max(0.0),
+        OptionType::Put => discount * (strike - spot).max(0.0),
+    };
+
+    // Sums of payoff x, control y and their products, combined in order
+    let chunks: Vec<[f64; 5]> = (0..samples.div_ceil(CHUNK_SIZE))
+        .into_par_iter()
+        .map(|chunk| {
+            let mut sums = [0.0; 5];
+            let mut uniforms = vec![(0.0, 0.0); steps];
+            for sample in chunk * CHUNK_SIZE..((chunk + 1) * CHUNK_SIZE).min(samples) {
+                let mut rng = RngStream::new(settings.seed, sample as u64);
+                uniforms
+                    .iter_mut()
+                    .for_each(|u| *u = (rng.next_uniform(), rng.next_uniform()));
+                let terminal = scheme.terminal_spot(params, &uniforms, false);
+                let (mut x, mut y) = (payoff(terminal), discount * terminal);
+                if settings.antithetic {
+                    let mirror = scheme.terminal_spot(params, &uniforms, true);
+                    x = 0.5 * (x + payoff(mirror));
+                    y = 0.5 * (y + discount * mirror);
+                }
+                for (sum, term) in sums.iter_mut().zip([x, y, x * x, y * y, x * y]) {
+                    *sum += term;
+                }
+            }
+            sums
+        })
+        .collect();
+    let mut sums = [0.0; 5];
+    for chunk in &chunks {
+        sums.iter_mut().zip(chunk).for_each(|(sum, c)| *sum += c);
+    }
+
+    if !settings.control_variate {
+        return PriceEstimate::from_sums(sums[0], sums[2], samples);
+    }
+    let n = samples as f64;
+    let [mean_x, mean_y] = [sums[0] / n, sums[1] / n];
+    let var_x = (sums[2] / n - mean_x * mean_x).max(0.0);
+    let var_y = (sums[3] / n - mean_y * mean_y).max(0.0);
+    let cov = sums[4] / n - mean_x * mean_y;
+    let beta = if var_y > 0.0 { cov / var_y } else { 0.0 };
+    let forward_value = params.spot * (-params.dividend_yield * time_to_maturity).exp();
+    PriceEstimate {
+        price: mean_x - beta * (mean_y - forward_value),
+        standard_error: ((var_x - beta * cov).max(0.0) / (n - 1.0)).sqrt(),
+        samples,
+    }
+}
+
+/// Step constants of the QE scheme with central weighting γ₁ = γ₂ = ½
+struct QuadraticExponential {
+    decay: f64,
+    drift: f64,
+    k0: f64,
+    k1: f64,
+    k2: f64,
+    k3: f64,
+}
+
+impl QuadraticExponential {
+    fn new(params: &HestonParams, dt: f64) -> Self {
+        let (kappa, theta, sigma, rho) = (
+            params.mean_reversion,
+            params.long_run_variance,
+            params.vol_of_vol,
+            params.correlation,
+        );
+        Self {
+            decay: (-kappa * dt).exp(),
+            drift: (params.risk_free_rate - params.dividend_yield) * dt,
+            k0: -rho * kappa * theta * dt / sigma,
+            k1: 0.5 * dt * (kappa * rho / sigma - 0.5) - rho / sigma,
+            k2: 0.5 * dt * (kappa * rho / sigma - 0.5) + rho / sigma,
+            k3: 0.5 * dt * (1.0 - rho * rho),
+        }
+    }
+
+    /// Spot at maturity driven by one (variance, spot) pair of uniforms per step;
+    /// `mirror` replaces every uniform u by 1 - u
+    fn terminal_spot(&self, params: &HestonParams, uniforms: &[(f64, f64)], mirror: bool) -> f64 {
+        let (kappa, theta, sigma) = (params.mean_reversion, params.long_run_variance, params.vol_of_vol);
+        let mut v = params.initial_variance;
+        let mut log_spot = params.spot.ln();
+        for &(u_v, u_s) in uniforms {
+            let (u_v, u_s) = if mirror { (1.0 - u_v, 1.0 - u_s) } else { (u_v, u_s) };
+
+            let m = theta + (v - theta) * self.decay;
+            let s2 = v * sigma * sigma * self.decay * (1.0 - self.decay) / kappa
+                + theta * sigma * sigma * (1.0 - self.decay).powi(2) / (2.0 * kappa);
+            let psi = s2 / (m * m);
+            let next = if psi <= QE_SWITCH {
+                let b2 = 2.0 / psi - 1.0 + (2.0 / psi).sqrt() * (2.0 / psi - 1.0).sqrt();
+                let a = m / (1.0 + b2);
+                a * (b2.sqrt() + inverse_norm_cdf(u_v)).powi(2)
+            } else {
+                let p = (psi - 1.0) / (psi + 1.0);
+                let beta = (1.0 - p) / m;
+                if u_v <= p { 0.0 } else { ((1.0 - p) / (1.0 - u_v)).ln() / beta }
+            };
+
+            log_spot += self.drift
+                + self.k0
+                + self.k1 * v
+                + self.k2 * next
+                + (self.k3 * (v + next)).sqrt() * inverse_norm_cdf(u_s);
+            v = next;
+        }
+        log_spot.exp()
+    }
+}
+
+/// Market and model inputs lifted to AD numbers so that any of them can be seeded
+#[derive(Debug, Clone, Copy)]
+struct Inputs<T> {
+    s: T,
+    k: f64,
+    t: T,
+    r: T,
+    q: T,
+    v0: T,
+    kappa: T,
+    theta: T,
+    sigma: T,
+    rho: T,
+    /// Black-Scholes volatility of the control, √ of the mean expected variance
+    control_vol: f64,
+}
+
+impl<T: Scalar> Inputs<T> {
+    /// All inputs held constant
+    fn constant(params: &HestonParams, strike: f64, time_to_maturity: f64) -> Self {
+        Self {
+            s: T::constant(params.spot),
+            k: strike,
+            t: T::constant(time_to_maturity),
+            r: T::constant(params.risk_free_rate),
+            q: T::constant(params.dividend_yield),
+            v0: T::constant(params.initial_variance),
+            kappa: T::constant(params.mean_reversion),
+            theta: T::constant(params.long_run_variance),
+            sigma: T::constant(params.vol_of_vol),
+            rho: T::constant(params.correlation),
+            control_vol: params.mean_variance(time_to_maturity).sqrt(),
+        }
+    }
+
+    fn price(&self, option_type: OptionType) -> T {
+        let call = fourier::lewis_call(self.s, self.k, self.t, self.r, self.q, self.control_vol, |u| {
+            self.characteristic_function(u)
+        });
+        match option_type {
+            OptionType::Call => call,
+            OptionType::Put => call - (-self.r * self.t).exp() * (self.s * ((self.r - self.q) * self.t).exp() - self.k),
+        }
+    }
+
+    /// E[exp(i z ln(S_T / F))] at z = u - i/2, little trap form
+    fn characteristic_function(&self, u: f64) -> Complex<T> {
+        let one = Complex::real(T::constant(1.0));
+        let sigma2 = self.sigma.powi2();
+        // κ - ρσ i z with i z = ½ + i u
+        let beta = Complex::new(
+            self.kappa - self.rho * self.sigma * 0.5,
+            -(self.rho * self.sigma * u),
+        );
+        let d = (beta * beta + Complex::real(sigma2 * (u * u + 0.25))).sqrt();
+        let g = (beta - d) / (beta + d);
+        let decay = (-(d * self.t)).exp();
+        let c = ((beta - d) * self.t - ((one - g * decay) / (one - g)).ln() * T::constant(2.0))
+            * (self.kappa * self.theta / sigma2);
+        let dv = (beta - d) / Complex::real(sigma2) * ((one - decay) / (one - g * decay));
+        (c + dv * self.v0).exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::black_scholes;
+    use approx::assert_relative_eq;
+
+    fn params() -> HestonParams {
+        HestonParams::new(100.0, 0.03, 0.01, 0.04, 2.0, 0.05, 0.6, -0.7)
+    }
+
+    #[test]
+    fn test_reference_prices() {
+        // Fang and Oosterlee (2008)
+        let fo = HestonParams::new(100.0, 0.0, 0.0, 0.0175, 1.5768, 0.0398, 0.5751, -0.5711);
+        assert_relative_eq!(price(&fo, 100.0, 1.0, OptionType::Call), 5.785155434376189, epsilon = 1e-10);
+
+        // Adaptive high-precision quadrature of the Lewis integral
+        let cases = [
+            (params(), 80.0, 0.25, 20.5998789779687),
+            (params(), 100.0, 0.25, 4.083963040386712),
+            (params(), 120.0, 0.25, 0.024678180739038),
+            (params(), 80.0, 2.0, 26.141366531164644),
+            (params(), 100.0, 2.0, 12.873368087025313),
+            (params(), 130.0, 2.0, 2.23624523913382),
+            (params(), 100.0, 0.02, 1.143511652343866),
+            // Feller condition badly violated, long dated
+            (HestonParams::new(100.0, 0.02, 0.0, 0.09, 0.5, 0.04, 1.5, -0.9), 60.0, 10.0, 53.81028517896298),
+            (HestonParams::new(100.0, 0.02, 0.0, 0.09, 0.5, 0.04, 1.5, -0.9), 150.0, 10.0, 1.331174124382765),
+            (HestonParams::new(100.0, 0.0, 0.0, 0.01, 5.0, 0.09, 1.0, 0.5), 100.0, 0.1, 1.936703927870835),
+            (HestonParams::new(100.0, 0.05, 0.02, 0.04, 1.0, 0.04, 0.3, 0.0), 140.0, 1.0, 0.761286875525169),
+            (HestonParams::new(100.0, 0.05, 0.02, 0.04, 1.0, 0.04, 0.3, 0.0), 70.0, 1.0, 31.69604721646752),
+        ];
+        for (params, strike, t, expected) in cases {
+            assert_relative_eq!(price(&params, strike, t, OptionType::Call), expected, epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_black_scholes_limit() {
+        // As vol of vol vanishes the variance becomes deterministic, and the
+        // option is Black-Scholes at the mean expected variance; with zero
+        // correlation the difference is second order in σ
+        let mut params = params();
+        params.vol_of_vol = 1e-4;
+        params.correlation = 0.0;
+        for &(strike, t) in &[(90.0, 0.5), (100.0, 1.0), (125.0, 3.0)] {
+            let vol = params.mean_variance(t).sqrt();
+            let bs = BlackScholesParams::new(100.0, strike, t, vol, 0.03, 0.01);
+            for &option_type in &[OptionType::Call, OptionType::Put] {
+                let expected = black_scholes::calculate_greeks(&bs, option_type).price;
+                assert_relative_eq!(price(&params, strike, t, option_type), expected, epsilon = 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_greeks_against_bumps() {
+        let params = params();
+        let (strike, t) = (105.0, 0.75);
+        let greeks = calculate_greeks(&params, strike, t, OptionType::Put);
+        let bumped = |f: &dyn Fn(&mut HestonParams, f64) -> f64, h: f64| {
+            let value = |sign: f64| {
+                let mut p = params;
+                let t = f(&mut p, sign * h);
+                price(&p, strike, t, OptionType::Put)
+            };
+            (value(1.0) - value(-1.0)) / (2.0 * h)
+        };
+
+        assert_relative_eq!(greeks.price, price(&params, strike, t, OptionType::Put), epsilon = 1e-12);
+        assert_relative_eq!(greeks.delta, bumped(&|p, h| { p.spot += h; t }, 1e-3), epsilon = 1e-7);
+        assert_relative_eq!(greeks.vega, bumped(&|p, h| { p.initial_variance = (0.2 + h).powi(2); t }, 1e-5), epsilon = 1e-6);
+        assert_relative_eq!(greeks.theta, -bumped(&|_, h| t + h, 1e-5), epsilon = 1e-6);
+        assert_relative_eq!(greeks.rho, bumped(&|p, h| { p.risk_free_rate += h; t }, 1e-5), epsilon = 1e-6);
+        assert_relative_eq!(greeks.phi, bumped(&|p, h| { p.dividend_yield += h; t }, 1e-5), epsilon = 1e-6);
+        let h = 1e-2;
+        let second = |bump: f64| {
+            let mut p = params;
+            p.spot += bump;
+            price(&p, strike, t, OptionType::Put)
+        };
+        let gamma = (second(h) - 2.0 * greeks.price + second(-h)) / (h * h);
+        assert_relative_eq!(greeks.gamma, gamma, epsilon = 1e-6);
+
+        let (value, gradient) = parameter_gradient(&params, strike, t, OptionType::Put);
+        assert_relative_eq!(value, greeks.price, epsilon = 1e-12);
+        let expected = [
+            bumped(&|p, h| { p.initial_variance += h; t }, 1e-6),
+            bumped(&|p, h| { p.mean_reversion += h; t }, 1e-6),
+            bumped(&|p, h| { p.long_run_variance += h; t }, 1e-6),
+            bumped(&|p, h| { p.vol_of_vol += h; t }, 1e-6),
+            bumped(&|p, h| { p.correlation += h; t }, 1e-6),
+        ];
+        for (ad, fd) in gradient.iter().zip(expected) {
+            assert_relative_eq!(*ad, fd, epsilon = 1e-5, max_relative = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_quadratic_exponential_monte_carlo() {
+        // Feller condition violated, so variance regularly reaches the exponential branch
+        let params = params();
+        assert!(!params.satisfies_feller());
+        let settings = MonteCarloSettings { paths: 100_000, time_steps: 50, ..MonteCarloSettings::default() };
+        for &(strike, option_type) in &[(90.0, OptionType::Put), (100.0, OptionType::Call), (115.0, OptionType::Call)] {
+            let estimate = monte_carlo_price(&params, strike, 1.0, option_type, &settings);
+            let exact = price(&params, strike, 1.0, option_type);
+            assert!(
+                (estimate.price - exact).abs() < 4.0 * estimate.standard_error + 0.01,
+                "{} vs {} ± {}",
+                estimate.price,
+                exact,
+                estimate.standard_error
+            );
+        }
+    }
+
+    #[test]
+    fn test_option_chain_and_validation() {
+        let chain: Vec<OptionData> = [(90.0, 0.5, OptionType::Put), (100.0, 1.0, OptionType::Call), (110.0, 0.0, OptionType::Put)]
+            .iter()
+            .map(|&(strike, time_to_maturity, option_type)| OptionData {
+                strike,
+                time_to_maturity,
+                implied_volatility: 0.2,
+                option_type,
+            })
+            .collect();
+        let prices = price_options(&params(), &chain);
+        for (option, &value) in chain.iter().zip(&prices) {
+            assert_eq!(value, price(&params(), option.strike, option.time_to_maturity, option.option_type));
+        }
+        assert_eq!(prices[2], 10.0);
+
+        // Put-call parity holds by construction
+        let call = price(&params(), 95.0, 1.5, OptionType::Call);
+        let put = price(&params(), 95.0, 1.5, OptionType::Put);
+        assert_relative_eq!(call - put, 100.0 * (-0.015f64).exp() - 95.0 * (-0.045f64).exp(), epsilon = 1e-12);
+
+        let mut invalid = params();
+        invalid.correlation = -1.2;
+        assert_eq!(
+            invalid.validate(),
+            Err(PricingError::InvalidParameter { name: "correlation", value: -1.2 })
+        );
+        invalid.vol_of_vol = f64::NAN;
+        assert_eq!(invalid.validate(), Err(PricingError::NonFinite("vol_of_vol")));
+        assert_eq!(
+            try_calculate_greeks(&params(), -1.0, 1.0, OptionType::Call).unwrap_err(),
+            PricingError::NonPositiveStrike(-1.0)
+        );
+    }
+}
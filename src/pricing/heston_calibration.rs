@@ -0,0 +1,402 @@
+//! Calibration of the Heston model to implied volatility quotes
+//!
+//! The five model parameters are fitted by bounded Levenberg-Marquardt. Every
+//! Jacobian row is exact: the model price and its gradient with respect to
+//! (v₀, κ, θ, σ, ρ) come from a single `MultiDual` pass through the
+//! characteristic function. Quotes are read from a list of `OptionData` or
+//! sampled from a `VolatilitySurface`.
+
+use crate::pricing::black_scholes::{self, BlackScholesParams};
+use crate::pricing::heston::{self, HestonParams};
+use crate::pricing::implied_vol::implied_vol;
+use crate::pricing::least_squares::levenberg_marquardt;
+use crate::pricing::PricingError;
+use crate::types::{OptionData, OptionType};
+use crate::volatility::VolatilitySurface;
+use rayon::prelude::*;
+
+/// Residual minimised for each quote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CalibrationWeighting {
+    /// Model price minus market price
+    Price,
+    /// Price error divided by the Black-Scholes vega of the quote, a first-order
+    /// implied volatility error that needs no inversion
+    #[default]
+    VegaWeighted,
+    /// Model implied volatility minus market implied volatility
+    ImpliedVolatility,
+}
+
+/// Treatment of the Feller condition 2κθ ≥ σ²
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FellerCondition {
+    /// Leave the fit unconstrained
+    #[default]
+    Ignore,
+    /// Add a residual of the given weight times max(σ² - 2κθ, 0)
+    Penalize(f64),
+    /// Cap σ at √(2κθ) after every step
+    Enforce,
+}
+
+/// Box bounds on (v₀, κ, θ, σ, ρ)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HestonBounds {
+    pub lower: [f64; 5],
+    pub upper: [f64; 5],
+}
+
+impl Default for HestonBounds {
+    fn default() -> Self {
+        Self {
+            lower: [1e-4, 1e-2, 1e-4, 1e-2, -0.999],
+            upper: [1.0, 20.0, 1.0, 5.0, 0.999],
+        }
+    }
+}
+
+/// Calibration settings
+#[derive(Debug, Clone, Copy)]
+pub struct HestonCalibrationSettings {
+    pub weighting: CalibrationWeighting,
+    pub bounds: HestonBounds,
+    pub feller: FellerCondition,
+    pub max_iterations: usize,
+    /// Relative reduction in cost, or relative step, below which the fit stops
+    pub tolerance: f64,
+}
+
+impl HestonCalibrationSettings {
+    pub fn new(weighting: CalibrationWeighting, feller: FellerCondition) -> Self {
+        Self {
+            weighting,
+            feller,
+            bounds: HestonBounds::default(),
+            max_iterations: 100,
+            tolerance: 1e-10,
+        }
+    }
+}
+
+impl Default for HestonCalibrationSettings {
+    fn default() -> Self {
+        Self::new(CalibrationWeighting::VegaWeighted, FellerCondition::Ignore)
+    }
+}
+
+/// Fit report
+#[derive(Debug, Clone)]
+pub struct HestonCalibration {
+    pub params: HestonParams,
+    /// Residual of each quote under the chosen weighting, in input order
+    pub residuals: Vec<f64>,
+    /// Root mean square of `residuals`
+    pub rmse: f64,
+    pub iterations: usize,
+    /// Whether the fit stopped on the tolerance, rather than on the iteration
+    /// limit or stalled with no step left that lowers the cost
+    pub converged: bool,
+}
+
+/// Free parameters in the fit
+const PARAMETERS: usize = 5;
+
+/// Calibrate to a list of quotes
+///
+/// Spot, rates and the starting point for the five model parameters are taken
+/// from `initial`. Each quote is priced as the option type it carries.
+pub fn calibrate(
+    options: &[OptionData],
+    initial: &HestonParams,
+    settings: &HestonCalibrationSettings,
+) -> Result<HestonCalibration, PricingError> {
+    initial.validate()?;
+    if options.len() < PARAMETERS {
+        return Err(PricingError::InsufficientQuotes { required: PARAMETERS, provided: options.len() });
+    }
+    let quotes = options
+        .iter()
+        .map(|option| Quote::new(option, initial))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let bounds = settings.bounds;
+    let project = |x: &mut [f64; PARAMETERS]| {
+        if settings.feller == FellerCondition::Enforce {
+            x[3] = x[3].min((2.0 * x[1] * x[2]).sqrt()).max(bounds.lower[3]);
+        }
+    };
+    let evaluate = |x: &[f64; PARAMETERS]| {
+        let params = with_parameters(initial, x);
+        let (mut residuals, mut jacobian): (Vec<f64>, Vec<[f64; PARAMETERS]>) = quotes
+            .par_iter()
+            .map(|quote| quote.residual(&params, settings.weighting))
+            .unzip();
+        if let FellerCondition::Penalize(weight) = settings.feller {
+            let (kappa, theta, sigma) = (x[1], x[2], x[3]);
+            let excess = sigma * sigma - 2.0 * kappa * theta;
+            if excess > 0.0 {
+                residuals.push(weight * excess);
+                jacobian.push([0.0, -2.0 * weight * theta, -2.0 * weight * kappa, 2.0 * weight * sigma, 0.0]);
+            } else {
+                residuals.push(0.0);
+                jacobian.push([0.0; PARAMETERS]);
+            }
+        }
+        (residuals, jacobian)
+    };
+
+    let start = [
+        initial.initial_variance,
+        initial.mean_reversion,
+        initial.long_run_variance,
+        initial.vol_of_vol,
+        initial.correlation,
+    ];
+    let fit = levenberg_marquardt(start, bounds.lower, bounds.upper, project, evaluate, settings.max_iterations, settings.tolerance);
+
+    let params = with_parameters(initial, &fit.x);
+    let residuals: Vec<f64> = quotes
+        .par_iter()
+        .map(|quote| quote.residual(&params, settings.weighting).0)
+        .collect();
+    let rmse = (residuals.iter().map(|r| r * r).sum::<f64>() / residuals.len() as f64).sqrt();
+    Ok(HestonCalibration {
+        params,
+        residuals,
+        rmse,
+        iterations: fit.iterations,
+        converged: fit.converged,
+    })
+}
+
+/// Calibrate to implied volatilities read off a surface
+///
+/// Every slice maturity is sampled at each of `strikes`, quoting calls at and
+/// above the forward and puts below it.
+pub fn calibrate_to_surface(
+    surface: &VolatilitySurface,
+    strikes: &[f64],
+    initial: &HestonParams,
+    settings: &HestonCalibrationSettings,
+) -> Result<HestonCalibration, PricingError> {
+    let mut options = Vec::with_capacity(surface.num_slices() * strikes.len());
+    for time_to_maturity in surface.maturities() {
+        let forward = initial.spot * ((initial.risk_free_rate - initial.dividend_yield) * time_to_maturity).exp();
+        for &strike in strikes {
+            if let Some(implied_volatility) = surface.get_implied_volatility(strike, initial.spot, time_to_maturity) {
+                let option_type = if strike >= forward { OptionType::Call } else { OptionType::Put };
+                options.push(OptionData { strike, time_to_maturity, implied_volatility, option_type });
+            }
+        }
+    }
+    calibrate(&options, initial, settings)
+}
+
+/// `base` with (v₀, κ, θ, σ, ρ) replaced
+fn with_parameters(base: &HestonParams, x: &[f64; PARAMETERS]) -> HestonParams {
+    HestonParams {
+        initial_variance: x[0],
+        mean_reversion: x[1],
+        long_run_variance: x[2],
+        vol_of_vol: x[3],
+        correlation: x[4],
+        ..*base
+    }
+}
+
+/// Market quote with its Black-Scholes price and vega
+struct Quote {
+    option: OptionData,
+    market: BlackScholesParams,
+    price: f64,
+    vega: f64,
+}
+
+impl Quote {
+    fn new(option: &OptionData, params: &HestonParams) -> Result<Self, PricingError> {
+        // An expired quote has no vega to weight by and carries no information on the model
+        if option.time_to_maturity <= 0.0 {
+            return Err(PricingError::InvalidParameter { name: "time_to_maturity", value: option.time_to_maturity });
+        }
+        let market = BlackScholesParams::try_new(
+            params.spot,
+            option.strike,
+            option.time_to_maturity,
+            option.implied_volatility,
+            params.risk_free_rate,
+            params.dividend_yield,
+        )?;
+        let greeks = black_scholes::calculate_greeks(&market, option.option_type);
+        Ok(Self {
+            option: *option,
+            market,
+            price: greeks.price,
+            // Keeps far-wing quotes from dominating the fit
+            vega: greeks.vega.max(1e-4 * params.spot * option.time_to_maturity.sqrt()),
+        })
+    }
+
+    /// Residual and its gradient with respect to (v₀, κ, θ, σ, ρ)
+    fn residual(&self, params: &HestonParams, weighting: CalibrationWeighting) -> (f64, [f64; PARAMETERS]) {
+        let option = &self.option;
+        let (price, gradient) =
+            heston::parameter_gradient(params, option.strike, option.time_to_maturity, option.option_type);
+        let scaled = |error: f64, scale: f64| (error / scale, gradient.map(|g| g / scale));
+        match weighting {
+            CalibrationWeighting::Price => (price - self.price, gradient),
+            CalibrationWeighting::VegaWeighted => scaled(price - self.price, self.vega),
+            CalibrationWeighting::ImpliedVolatility => {
+                let market = &self.market;
+                match implied_vol(
+                    price,
+                    market.spot,
+                    market.strike,
+                    market.time_to_maturity,
+                    market.risk_free_rate,
+                    market.dividend_yield,
+                    option.option_type,
+                ) {
+                    Ok(vol) => {
+                        // d(vol)/dθ = (dP/dθ) / vega at the model volatility
+                        let model = BlackScholesParams { volatility: vol, ..*market };
+                        let vega = black_scholes::calculate_greeks(&model, option.option_type).vega.max(self.vega);
+                        (vol - option.implied_volatility, gradient.map(|g| g / vega))
+                    }
+                    // Model price outside the no-arbitrage bounds: fall back to first order
+                    Err(_) => scaled(price - self.price, self.vega),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::volatility::SVIParams;
+    use approx::assert_relative_eq;
+
+    fn market_params() -> HestonParams {
+        HestonParams::new(100.0, 0.02, 0.01, 0.05, 1.8, 0.06, 0.5, -0.65)
+    }
+
+    /// Quotes generated by the Heston model itself
+    fn model_quotes(params: &HestonParams) -> Vec<OptionData> {
+        let mut options = Vec::new();
+        for &t in &[0.25, 0.5, 1.0, 2.0] {
+            for &strike in &[80.0, 90.0, 100.0, 110.0, 125.0] {
+                let option_type = if strike >= 100.0 { OptionType::Call } else { OptionType::Put };
+                let price = heston::price(params, strike, t, option_type);
+                let vol = implied_vol(price, 100.0, strike, t, 0.02, 0.01, option_type).unwrap();
+                options.push(OptionData { strike, time_to_maturity: t, implied_volatility: vol, option_type });
+            }
+        }
+        options
+    }
+
+    fn starting_point() -> HestonParams {
+        HestonParams { initial_variance: 0.03, mean_reversion: 1.0, long_run_variance: 0.04, vol_of_vol: 0.3, correlation: -0.3, ..market_params() }
+    }
+
+    #[test]
+    fn test_recovers_model_parameters() {
+        let truth = market_params();
+        let quotes = model_quotes(&truth);
+        for &weighting in &[CalibrationWeighting::VegaWeighted, CalibrationWeighting::ImpliedVolatility, CalibrationWeighting::Price] {
+            let settings = HestonCalibrationSettings::new(weighting, FellerCondition::Ignore);
+            let fit = calibrate(&quotes, &starting_point(), &settings).unwrap();
+
+            assert!(fit.converged);
+            assert!(fit.iterations > 0);
+            assert_eq!(fit.residuals.len(), quotes.len());
+            assert!(fit.rmse < 1e-7, "{:?}: rmse {}", weighting, fit.rmse);
+            assert_relative_eq!(fit.params.initial_variance, truth.initial_variance, max_relative = 1e-4);
+            assert_relative_eq!(fit.params.mean_reversion, truth.mean_reversion, max_relative = 1e-3);
+            assert_relative_eq!(fit.params.long_run_variance, truth.long_run_variance, max_relative = 1e-3);
+            assert_relative_eq!(fit.params.vol_of_vol, truth.vol_of_vol, max_relative = 1e-3);
+            assert_relative_eq!(fit.params.correlation, truth.correlation, max_relative = 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_default_settings_fit() {
+        let truth = market_params();
+        let settings = HestonCalibrationSettings::default();
+        assert_eq!(settings.max_iterations, 100);
+        assert!(settings.tolerance > 0.0);
+
+        let fit = calibrate(&model_quotes(&truth), &starting_point(), &Default::default()).unwrap();
+        assert!(fit.converged);
+        assert!(fit.rmse < 1e-7, "rmse {}", fit.rmse);
+        assert_relative_eq!(fit.params.initial_variance, truth.initial_variance, max_relative = 1e-4);
+    }
+
+    #[test]
+    fn test_feller_condition_and_bounds() {
+        // The generating parameters violate Feller: 2κθ = 0.216 < σ² = 0.81
+        let truth = HestonParams { vol_of_vol: 0.9, ..market_params() };
+        let quotes = model_quotes(&truth);
+
+        let free = calibrate(&quotes, &starting_point(), &HestonCalibrationSettings::new(Default::default(), FellerCondition::Ignore)).unwrap();
+        assert!(!free.params.satisfies_feller());
+
+        let enforced = calibrate(&quotes, &starting_point(), &HestonCalibrationSettings::new(Default::default(), FellerCondition::Enforce)).unwrap();
+        // Enforce caps σ at √(2κθ), so the condition holds up to rounding
+        let p = enforced.params;
+        assert!(2.0 * p.mean_reversion * p.long_run_variance - p.vol_of_vol.powi(2) >= -1e-12);
+        assert!(enforced.rmse > free.rmse);
+
+        let penalized = calibrate(&quotes, &starting_point(), &HestonCalibrationSettings::new(Default::default(), FellerCondition::Penalize(10.0))).unwrap();
+        let violation = |p: HestonParams| p.vol_of_vol.powi(2) - 2.0 * p.mean_reversion * p.long_run_variance;
+        assert!(violation(penalized.params) < violation(free.params));
+        // The softer penalty still fits, and better than the hard cap
+        assert!(penalized.rmse < 0.02, "rmse {}", penalized.rmse);
+        assert!(penalized.rmse < enforced.rmse);
+
+        // A tight box keeps every parameter inside
+        let mut settings = HestonCalibrationSettings::new(Default::default(), FellerCondition::Ignore);
+        settings.bounds.upper[3] = 0.4;
+        let bounded = calibrate(&quotes, &starting_point(), &settings).unwrap();
+        assert!(bounded.params.vol_of_vol <= 0.4);
+    }
+
+    #[test]
+    fn test_calibrate_to_surface() {
+        // Equity-like skew that flattens with maturity
+        let mut surface = VolatilitySurface::new();
+        surface.add_slice(0.5, SVIParams::new(0.008, 0.06, -0.6, 0.02, 0.15));
+        surface.add_slice(1.0, SVIParams::new(0.02, 0.08, -0.55, 0.03, 0.2));
+        surface.add_slice(2.0, SVIParams::new(0.05, 0.1, -0.5, 0.05, 0.25));
+        let strikes = [75.0, 85.0, 95.0, 100.0, 105.0, 115.0, 130.0];
+
+        let settings = HestonCalibrationSettings::new(CalibrationWeighting::ImpliedVolatility, FellerCondition::Ignore);
+        let fit = calibrate_to_surface(&surface, &strikes, &starting_point(), &settings).unwrap();
+        assert_eq!(fit.residuals.len(), 21);
+        // Heston cannot match SVI exactly, but gets within a vol point
+        assert!(fit.rmse < 0.01, "rmse {}", fit.rmse);
+        assert!(fit.params.correlation < 0.0);
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        let quotes = model_quotes(&market_params());
+        let settings = HestonCalibrationSettings::new(Default::default(), Default::default());
+        assert_eq!(
+            calibrate(&quotes[..3], &starting_point(), &settings).unwrap_err(),
+            PricingError::InsufficientQuotes { required: 5, provided: 3 }
+        );
+        let mut bad = quotes.clone();
+        bad[2].implied_volatility = -0.1;
+        assert_eq!(
+            calibrate(&bad, &starting_point(), &settings).unwrap_err(),
+            PricingError::NonPositiveVolatility(-0.1)
+        );
+        let mut expired = quotes.clone();
+        expired[4].time_to_maturity = 0.0;
+        assert_eq!(
+            calibrate(&expired, &starting_point(), &settings).unwrap_err(),
+            PricingError::InvalidParameter { name: "time_to_maturity", value: 0.0 }
+        );
+    }
+}
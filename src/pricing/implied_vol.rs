@@ -0,0 +1,282 @@
+//! Implied volatility inversion of the Black-Scholes price
+//!
+//! Starts from the Corrado-Miller rational approximation, refines with Halley
+//! steps whose vega and volga come from a single `HyperDual` pricing pass, and
+//! falls back to bisection whenever a step would leave the current bracket.
+//! Jäckel's "Let's Be Rational" is available through `ImpliedVolMethod` for
+//! quotes that need full double precision, such as tiny-premium wings.
+
+use crate::ad::HyperDual;
+use crate::pricing::black_scholes::{call_price, put_price};
+use crate::pricing::lets_be_rational::implied_black_volatility;
+use crate::pricing::PricingError;
+use crate::types::OptionType;
+use std::f64::consts::PI;
+
+const MAX_ITERATIONS: usize = 100;
+const VOL_TOLERANCE: f64 = 1e-14;
+/// Largest volatility the bracket is allowed to grow to
+const MAX_VOL: f64 = 100.0;
+
+/// Root-finding scheme used to invert the Black-Scholes price
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImpliedVolMethod {
+    /// Corrado-Miller guess refined by AD Halley steps with a bisection safeguard
+    #[default]
+    Halley,
+    /// Jäckel's "Let's Be Rational": full double precision in two iterations
+    LetsBeRational,
+}
+
+/// Solve for the Black-Scholes volatility that reproduces `price`
+///
+/// Returns `PricingError::ArbitrageViolation` when the price is not strictly
+/// between the discounted intrinsic value and the no-arbitrage upper bound
+/// (S e^(-qT) for calls, K e^(-rT) for puts).
+pub fn implied_vol(
+    price: f64,
+    spot: f64,
+    strike: f64,
+    time_to_maturity: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    option_type: OptionType,
+) -> Result<f64, PricingError> {
+    implied_vol_with_method(
+        price,
+        spot,
+        strike,
+        time_to_maturity,
+        risk_free_rate,
+        dividend_yield,
+        option_type,
+        ImpliedVolMethod::default(),
+    )
+}
+
+/// Solve for the Black-Scholes volatility with an explicit root-finding scheme
+#[allow(clippy::too_many_arguments)]
+pub fn implied_vol_with_method(
+    price: f64,
+    spot: f64,
+    strike: f64,
+    time_to_maturity: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    option_type: OptionType,
+    method: ImpliedVolMethod,
+) -> Result<f64, PricingError> {
+    let fields = [
+        ("price", price),
+        ("spot", spot),
+        ("strike", strike),
+        ("time_to_maturity", time_to_maturity),
+        ("risk_free_rate", risk_free_rate),
+        ("dividend_yield", dividend_yield),
+    ];
+    if let Some(&(name, _)) = fields.iter().find(|(_, v)| !v.is_finite()) {
+        return Err(PricingError::NonFinite(name));
+    }
+    if spot <= 0.0 {
+        return Err(PricingError::NonPositiveSpot(spot));
+    }
+    if strike <= 0.0 {
+        return Err(PricingError::NonPositiveStrike(strike));
+    }
+    if time_to_maturity < 0.0 {
+        return Err(PricingError::NegativeMaturity(time_to_maturity));
+    }
+
+    let forward_spot = spot * (-dividend_yield * time_to_maturity).exp();
+    let discounted_strike = strike * (-risk_free_rate * time_to_maturity).exp();
+    let (lower, upper) = match option_type {
+        OptionType::Call => ((forward_spot - discounted_strike).max(0.0), forward_spot),
+        OptionType::Put => ((discounted_strike - forward_spot).max(0.0), discounted_strike),
+    };
+    if price <= lower || price >= upper {
+        return Err(PricingError::ArbitrageViolation { price, lower, upper });
+    }
+    // Without time to expiry the price carries no information about volatility
+    if time_to_maturity == 0.0 {
+        return Err(PricingError::ImpliedVolNotFound);
+    }
+
+    if method == ImpliedVolMethod::LetsBeRational {
+        // Undiscounted Black on the forward F = S e^((r-q)T)
+        let discount = (-risk_free_rate * time_to_maturity).exp();
+        return implied_black_volatility(
+            price / discount,
+            forward_spot / discount,
+            strike,
+            time_to_maturity,
+            option_type,
+        );
+    }
+
+    // Price, vega and volga at a trial volatility
+    let evaluate = |sigma: f64| {
+        let s = HyperDual::constant(spot);
+        let t = HyperDual::constant(time_to_maturity);
+        let vol = HyperDual::variable(sigma);
+        let r = HyperDual::constant(risk_free_rate);
+        let q = HyperDual::constant(dividend_yield);
+        let result = match option_type {
+            OptionType::Call => call_price(s, strike, t, vol, r, q),
+            OptionType::Put => put_price(s, strike, t, vol, r, q),
+        };
+        (result.value - price, result.eps1, result.eps12)
+    };
+
+    let guess = initial_guess(price, forward_spot, discounted_strike, time_to_maturity, option_type);
+
+    // Bracket the root: the price is increasing in volatility and equals `lower` at zero
+    let mut lo = 0.0;
+    let mut hi = guess.max(1.0);
+    while evaluate(hi).0 < 0.0 {
+        lo = hi;
+        hi *= 2.0;
+        if hi > MAX_VOL {
+            return Err(PricingError::ImpliedVolNotFound);
+        }
+    }
+
+    let mut sigma = if guess > lo && guess < hi { guess } else { 0.5 * (lo + hi) };
+
+    for _ in 0..MAX_ITERATIONS {
+        let (diff, vega, volga) = evaluate(sigma);
+        if diff == 0.0 {
+            return Ok(sigma);
+        }
+        if diff < 0.0 {
+            lo = sigma;
+        } else {
+            hi = sigma;
+        }
+
+        // Halley step, reducing to Newton when the curvature correction is unreliable
+        let newton = diff / vega;
+        let correction = 1.0 - 0.5 * newton * volga / vega;
+        let step = if correction > 0.5 { newton / correction } else { newton };
+        let mut next = sigma - step;
+
+        if !next.is_finite() || next <= lo || next >= hi {
+            next = 0.5 * (lo + hi);
+        }
+
+        if (next - sigma).abs() <= VOL_TOLERANCE * sigma.max(1.0) {
+            return Ok(next);
+        }
+        sigma = next;
+    }
+
+    Err(PricingError::ImpliedVolNotFound)
+}
+
+/// Corrado-Miller rational approximation of σ from the call price
+///
+/// σ√T ≈ √(2π)/(S' + K') · [C - (S' - K')/2 + √((C - (S' - K')/2)² - (S' - K')²/π)]
+/// with S' = S e^(-qT) and K' = K e^(-rT); puts are mapped through put-call parity.
+fn initial_guess(
+    price: f64,
+    forward_spot: f64,
+    discounted_strike: f64,
+    time_to_maturity: f64,
+    option_type: OptionType,
+) -> f64 {
+    let call = match option_type {
+        OptionType::Call => price,
+        OptionType::Put => price + forward_spot - discounted_strike,
+    };
+
+    let half_moneyness = 0.5 * (forward_spot - discounted_strike);
+    let excess = call - half_moneyness;
+    let discriminant = (excess * excess - 4.0 * half_moneyness * half_moneyness / PI).max(0.0);
+    let total_vol = (2.0 * PI).sqrt() / (forward_spot + discounted_strike) * (excess + discriminant.sqrt());
+
+    (total_vol / time_to_maturity.sqrt()).max(1e-4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::lets_be_rational::black_price;
+    use crate::pricing::{calculate_greeks, BlackScholesParams};
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_round_trip() {
+        for &t in &[1.0 / 52.0, 0.25, 1.0, 5.0] {
+            for &strike in &[50.0, 80.0, 100.0, 125.0, 200.0] {
+                for &vol in &[0.05, 0.2, 0.6, 1.5] {
+                    for &option_type in &[OptionType::Call, OptionType::Put] {
+                        let params = BlackScholesParams::new(100.0, strike, t, vol, 0.03, 0.01);
+                        let price = calculate_greeks(&params, option_type).price;
+
+                        // Skip quotes with no time value left to invert in double precision
+                        let intrinsic = match option_type {
+                            OptionType::Call => (100.0 * (-0.01 * t).exp() - strike * (-0.03 * t).exp()).max(0.0),
+                            OptionType::Put => (strike * (-0.03 * t).exp() - 100.0 * (-0.01 * t).exp()).max(0.0),
+                        };
+                        if price - intrinsic < 1e-10 {
+                            continue;
+                        }
+
+                        let solved = implied_vol(price, 100.0, strike, t, 0.03, 0.01, option_type).unwrap();
+                        let repriced = BlackScholesParams { volatility: solved, ..params };
+                        assert_relative_eq!(calculate_greeks(&repriced, option_type).price, price, epsilon = 1e-12);
+                        // In-the-money quotes with ~1e-7 of time value only pin σ down to a few 1e-9
+                        assert_relative_eq!(solved, vol, max_relative = 1e-8);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lets_be_rational_matches_halley() {
+        // Discounted Black-Scholes quotes map onto undiscounted Black on F = S e^((r-q)T)
+        let discount = (-0.04_f64 * 0.5).exp();
+        let forward = 100.0 * ((0.04 - 0.02) * 0.5_f64).exp();
+        for &strike in &[60.0, 100.0, 150.0] {
+            for &option_type in &[OptionType::Call, OptionType::Put] {
+                let price = discount * black_price(forward, strike, 0.35, 0.5, option_type);
+                let rational = implied_vol_with_method(
+                    price,
+                    100.0,
+                    strike,
+                    0.5,
+                    0.04,
+                    0.02,
+                    option_type,
+                    ImpliedVolMethod::LetsBeRational,
+                )
+                .unwrap();
+                assert_relative_eq!(rational, 0.35, max_relative = 1e-13);
+
+                let halley = implied_vol(price, 100.0, strike, 0.5, 0.04, 0.02, option_type).unwrap();
+                assert_relative_eq!(rational, halley, max_relative = 1e-13);
+            }
+        }
+    }
+
+    #[test]
+    fn test_arbitrage_bounds() {
+        // Call below intrinsic
+        let err = implied_vol(9.0, 110.0, 100.0, 1.0, 0.0, 0.0, OptionType::Call).unwrap_err();
+        assert_eq!(err, PricingError::ArbitrageViolation { price: 9.0, lower: 10.0, upper: 110.0 });
+
+        // Put above the discounted strike
+        let err = implied_vol(101.0, 100.0, 100.0, 1.0, 0.0, 0.0, OptionType::Put).unwrap_err();
+        assert!(matches!(err, PricingError::ArbitrageViolation { .. }));
+
+        // Invalid inputs
+        assert_eq!(
+            implied_vol(5.0, -100.0, 100.0, 1.0, 0.0, 0.0, OptionType::Call).unwrap_err(),
+            PricingError::NonPositiveSpot(-100.0)
+        );
+        assert_eq!(
+            implied_vol(f64::NAN, 100.0, 100.0, 1.0, 0.0, 0.0, OptionType::Call).unwrap_err(),
+            PricingError::NonFinite("price")
+        );
+    }
+}
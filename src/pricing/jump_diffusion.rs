@@ -0,0 +1,428 @@
+//! Jump-diffusion models: Merton (1976) lognormal jumps and Kou (2002)
+//! double-exponential jumps
+//!
+//! dS/S = (r - q - λζ) dt + σ dW + (e^Y - 1) dN,  N Poisson with intensity λ,
+//! ζ = E[e^Y - 1]
+//!
+//! Merton options are priced with the Poisson-weighted series of Black-Scholes
+//! prices, one term per number of jumps. Kou options go through the Lewis
+//! integral in `fourier` with Black-Scholes at the total return variance as
+//! control. Both are generic over `Scalar`, so the market Greeks and the
+//! sensitivity to the jump intensity come from AD.
+
+use crate::ad::{Complex, Scalar};
+use crate::pricing::black_scholes::{self, expired_greeks, BlackScholesParams};
+use crate::pricing::closed_form::{self, ClosedForm, Inputs};
+use crate::pricing::fourier;
+use crate::pricing::PricingError;
+use crate::types::{Greeks, OptionType};
+
+/// Merton jumps: log jump sizes Y ~ N(mean, volatility²)
+#[derive(Debug, Clone, Copy)]
+pub struct MertonJumps {
+    /// λ: expected number of jumps per year
+    pub intensity: f64,
+    /// Mean of the log jump size
+    pub mean: f64,
+    /// Standard deviation of the log jump size
+    pub volatility: f64,
+}
+
+impl MertonJumps {
+    pub fn new(intensity: f64, mean: f64, volatility: f64) -> Self {
+        Self { intensity, mean, volatility }
+    }
+
+    /// Check every parameter is finite and in range
+    pub fn validate(&self) -> Result<(), PricingError> {
+        let fields = [("intensity", self.intensity), ("mean", self.mean), ("volatility", self.volatility)];
+        if let Some(&(name, _)) = fields.iter().find(|(_, v)| !v.is_finite()) {
+            return Err(PricingError::NonFinite(name));
+        }
+        if self.intensity < 0.0 {
+            return Err(PricingError::InvalidParameter { name: "intensity", value: self.intensity });
+        }
+        if self.volatility < 0.0 {
+            return Err(PricingError::InvalidParameter { name: "volatility", value: self.volatility });
+        }
+        Ok(())
+    }
+}
+
+/// Kou jumps: log jump sizes exponential with rate η₁ upwards, with probability
+/// p, and rate η₂ downwards otherwise
+#[derive(Debug, Clone, Copy)]
+pub struct KouJumps {
+    /// λ: expected number of jumps per year
+    pub intensity: f64,
+    /// p: probability that a jump is upwards
+    pub up_probability: f64,
+    /// η₁: rate of upward jumps, above 1 for E[e^Y] to be finite
+    pub up_rate: f64,
+    /// η₂: rate of downward jumps
+    pub down_rate: f64,
+}
+
+impl KouJumps {
+    pub fn new(intensity: f64, up_probability: f64, up_rate: f64, down_rate: f64) -> Self {
+        Self {
+            intensity,
+            up_probability,
+            up_rate,
+            down_rate,
+        }
+    }
+
+    /// Check every parameter is finite and in range
+    pub fn validate(&self) -> Result<(), PricingError> {
+        let fields = [
+            ("intensity", self.intensity),
+            ("up_probability", self.up_probability),
+            ("up_rate", self.up_rate),
+            ("down_rate", self.down_rate),
+        ];
+        if let Some(&(name, _)) = fields.iter().find(|(_, v)| !v.is_finite()) {
+            return Err(PricingError::NonFinite(name));
+        }
+        let invalid = |name, value| Err(PricingError::InvalidParameter { name, value });
+        if self.intensity < 0.0 {
+            return invalid("intensity", self.intensity);
+        }
+        if !(0.0..=1.0).contains(&self.up_probability) {
+            return invalid("up_probability", self.up_probability);
+        }
+        if self.up_rate <= 1.0 {
+            return invalid("up_rate", self.up_rate);
+        }
+        if self.down_rate <= 0.0 {
+            return invalid("down_rate", self.down_rate);
+        }
+        Ok(())
+    }
+
+    /// ζ = E[e^Y] - 1
+    fn mean_jump(&self) -> f64 {
+        let p = self.up_probability;
+        p * self.up_rate / (self.up_rate - 1.0) + (1.0 - p) * self.down_rate / (self.down_rate + 1.0) - 1.0
+    }
+
+    /// E[Y²]
+    fn second_moment(&self) -> f64 {
+        let p = self.up_probability;
+        2.0 * p / self.up_rate.powi(2) + 2.0 * (1.0 - p) / self.down_rate.powi(2)
+    }
+}
+
+/// Greeks of a jump-diffusion option
+#[derive(Debug, Clone, Copy)]
+pub struct JumpDiffusionGreeks {
+    /// Price and market Greeks; vega is the sensitivity to the diffusion volatility
+    pub greeks: Greeks,
+    /// ∂V/∂λ, sensitivity to the jump intensity
+    pub intensity: f64,
+}
+
+/// Price a European option under Merton's model
+pub fn merton_price(params: &BlackScholesParams, jumps: &MertonJumps, option_type: OptionType) -> f64 {
+    price(params, jumps, option_type)
+}
+
+/// Calculate price, Greeks and intensity sensitivity under Merton's model
+///
+/// All first-order sensitivities come from one `MultiDual` pass through the
+/// series and gamma from a second pass on a `HyperDual`, as for Black-Scholes.
+pub fn merton_greeks(params: &BlackScholesParams, jumps: &MertonJumps, option_type: OptionType) -> JumpDiffusionGreeks {
+    greeks(params, jumps, option_type)
+}
+
+/// Calculate Merton price and Greeks after validating the inputs
+pub fn try_merton_greeks(
+    params: &BlackScholesParams,
+    jumps: &MertonJumps,
+    option_type: OptionType,
+) -> Result<JumpDiffusionGreeks, PricingError> {
+    params.validate()?;
+    jumps.validate()?;
+    Ok(merton_greeks(params, jumps, option_type))
+}
+
+/// Price a European option under Kou's model
+pub fn kou_price(params: &BlackScholesParams, jumps: &KouJumps, option_type: OptionType) -> f64 {
+    price(params, jumps, option_type)
+}
+
+/// Calculate price, Greeks and intensity sensitivity under Kou's model
+pub fn kou_greeks(params: &BlackScholesParams, jumps: &KouJumps, option_type: OptionType) -> JumpDiffusionGreeks {
+    greeks(params, jumps, option_type)
+}
+
+/// Calculate Kou price and Greeks after validating the inputs
+pub fn try_kou_greeks(
+    params: &BlackScholesParams,
+    jumps: &KouJumps,
+    option_type: OptionType,
+) -> Result<JumpDiffusionGreeks, PricingError> {
+    params.validate()?;
+    jumps.validate()?;
+    Ok(kou_greeks(params, jumps, option_type))
+}
+
+/// Upper limit on the number of jumps summed in Merton's series
+const MAX_JUMPS: usize = 1000;
+
+/// Jump size distribution, priced generically over the number type
+trait JumpProcess {
+    fn intensity(&self) -> f64;
+
+    fn price<T: Scalar>(&self, inputs: &Inputs<T>, option_type: OptionType) -> T;
+}
+
+fn price<J: JumpProcess>(params: &BlackScholesParams, jumps: &J, option_type: OptionType) -> f64 {
+    if params.time_to_maturity <= 0.0 {
+        return expired_greeks(params, option_type).price;
+    }
+    let inputs = Inputs { intensity: jumps.intensity(), ..Inputs::constant(params) };
+    jumps.price(&inputs, option_type)
+}
+
+fn greeks<J: JumpProcess>(params: &BlackScholesParams, jumps: &J, option_type: OptionType) -> JumpDiffusionGreeks {
+    let contract = JumpOption { jumps, option_type };
+    let (greeks, intensity) = closed_form::greeks_and_intensity(params, &contract);
+    JumpDiffusionGreeks { greeks, intensity }
+}
+
+/// European option on a jump-diffusion
+struct JumpOption<'a, J> {
+    jumps: &'a J,
+    option_type: OptionType,
+}
+
+impl<J: JumpProcess> ClosedForm for JumpOption<'_, J> {
+    fn price<T: Scalar>(&self, inputs: &Inputs<T>) -> T {
+        self.jumps.price(inputs, self.option_type)
+    }
+
+    fn intensity(&self) -> f64 {
+        self.jumps.intensity()
+    }
+
+    fn at_expiry(&self, params: &BlackScholesParams) -> Greeks {
+        expired_greeks(params, self.option_type)
+    }
+}
+
+impl<T: Scalar> Inputs<T> {
+    /// Put from call by parity on the forward
+    fn with_parity(&self, call: T, option_type: OptionType) -> T {
+        match option_type {
+            OptionType::Call => call,
+            OptionType::Put => call - (self.s * (-self.q * self.t).exp() - (-self.r * self.t).exp() * self.k),
+        }
+    }
+}
+
+impl JumpProcess for MertonJumps {
+    fn intensity(&self) -> f64 {
+        self.intensity
+    }
+
+    /// Σₙ P(n; λ'T) BS(σₙ, rₙ) with λ' = λ(1 + ζ), σₙ² = σ² + nδ²/T and
+    /// rₙ = r - λζ + n ln(1 + ζ)/T
+    fn price<T: Scalar>(&self, inputs: &Inputs<T>, option_type: OptionType) -> T {
+        let log_growth = self.mean + 0.5 * self.volatility * self.volatility;
+        let zeta = log_growth.exp_m1();
+        let expected_jumps = inputs.intensity * inputs.t * (1.0 + zeta);
+        let variance = inputs.sigma.powi2();
+        let drift = inputs.r - inputs.intensity * zeta;
+
+        let mut weight = (-expected_jumps).exp();
+        let mut total = T::constant(0.0);
+        for n in 0..MAX_JUMPS {
+            let jumps = n as f64;
+            let sigma = (variance + T::constant(jumps * self.volatility * self.volatility) / inputs.t).sqrt();
+            let r = drift + T::constant(jumps * log_growth) / inputs.t;
+            let term = match option_type {
+                OptionType::Call => black_scholes::call_price(inputs.s, inputs.k, inputs.t, sigma, r, inputs.q),
+                OptionType::Put => black_scholes::put_price(inputs.s, inputs.k, inputs.t, sigma, r, inputs.q),
+            };
+            total = total + weight * term;
+            // Past the Poisson mode the weights fall geometrically
+            if jumps > expected_jumps.value() && weight.value() < 1e-18 {
+                break;
+            }
+            weight = weight * expected_jumps / (jumps + 1.0);
+        }
+        total
+    }
+}
+
+impl JumpProcess for KouJumps {
+    fn intensity(&self) -> f64 {
+        self.intensity
+    }
+
+    fn price<T: Scalar>(&self, inputs: &Inputs<T>, option_type: OptionType) -> T {
+        let zeta = self.mean_jump();
+        let control_vol = (inputs.sigma.value().powi(2) + inputs.intensity.value() * self.second_moment()).sqrt();
+        let (p, up, down) = (self.up_probability, self.up_rate, self.down_rate);
+        let jump_rate = inputs.intensity * inputs.t;
+        let diffusion = inputs.sigma.powi2() * inputs.t * 0.5;
+
+        // φ(u) = exp(-½σ²T(u² + ¼) + λT(E[e^(izY)] - 1 - iz ζ)) at z = u - i/2
+        let call = fourier::lewis_call(inputs.s, inputs.k, inputs.t, inputs.r, inputs.q, control_vol, |u| {
+            let c = |x: f64| T::constant(x);
+            let upward = Complex::real(c(p * up)) / Complex::new(c(up - 0.5), c(-u));
+            let downward = Complex::real(c((1.0 - p) * down)) / Complex::new(c(down + 0.5), c(u));
+            let compensator = Complex::new(c(0.5 * zeta + 1.0), c(u * zeta));
+            ((upward + downward - compensator) * jump_rate - diffusion * (u * u + 0.25)).exp()
+        });
+        inputs.with_parity(call, option_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn market(strike: f64, t: f64) -> BlackScholesParams {
+        BlackScholesParams::new(100.0, strike, t, 0.2, 0.05, 0.02)
+    }
+
+    #[test]
+    fn test_merton_reference_prices() {
+        // Series summed to 200 jumps at 30 digits; the 120 strike also agrees
+        // with the Lewis integral of Merton's characteristic function
+        let jumps = MertonJumps::new(1.0, -0.1, 0.15);
+        let cases = [
+            (80.0, 0.5, 22.051583674693386, 1.0713932620431936),
+            (100.0, 0.5, 7.841366641940914, 6.367374469857375),
+            (120.0, 0.5, 1.6141489614367253, 19.64635502991984),
+            (100.0, 2.0, 16.70001063056201, 11.104808518925645),
+            (100.0, 0.05, 2.122066056743365, 1.972328313151878),
+        ];
+        for &(strike, t, call, put) in &cases {
+            let params = market(strike, t);
+            assert_relative_eq!(merton_price(&params, &jumps, OptionType::Call), call, epsilon = 1e-12);
+            assert_relative_eq!(merton_price(&params, &jumps, OptionType::Put), put, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_kou_reference_prices() {
+        // Kou (2002): σ = 0.16, λ = 1, p = 0.4, η₁ = 10, η₂ = 5; the 98 strike
+        // is the paper's 9.14732. Others from adaptive quadrature at 30 digits.
+        let jumps = KouJumps::new(1.0, 0.4, 10.0, 5.0);
+        let cases = [
+            (80.0, 0.5, 23.24617813456136, 1.270971096827972),
+            (98.0, 0.5, 9.147317303936926, 4.727688682713528),
+            (120.0, 0.5, 1.491865822801118, 18.52905526620104),
+            (100.0, 2.0, 19.28780215589216, 9.771543959488116),
+            (100.0, 0.05, 1.861903230331244, 1.612215470077256),
+        ];
+        for &(strike, t, call, put) in &cases {
+            let params = BlackScholesParams::new(100.0, strike, t, 0.16, 0.05, 0.0);
+            assert_relative_eq!(kou_price(&params, &jumps, OptionType::Call), call, epsilon = 1e-9);
+            assert_relative_eq!(kou_price(&params, &jumps, OptionType::Put), put, epsilon = 1e-9);
+        }
+
+        let params = BlackScholesParams::new(100.0, 100.0, 1.0, 0.2, 0.03, 0.01);
+        let frequent = KouJumps::new(3.0, 0.3, 25.0, 10.0);
+        assert_relative_eq!(kou_price(&params, &frequent, OptionType::Call), 11.856735412795985, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_zero_intensity_is_black_scholes() {
+        let merton = MertonJumps::new(0.0, -0.1, 0.15);
+        let kou = KouJumps::new(0.0, 0.4, 10.0, 5.0);
+        for &(strike, t) in &[(85.0, 0.25), (100.0, 1.0), (130.0, 3.0)] {
+            let params = market(strike, t);
+            for &option_type in &[OptionType::Call, OptionType::Put] {
+                let expected = black_scholes::calculate_greeks(&params, option_type);
+                for result in [merton_greeks(&params, &merton, option_type), kou_greeks(&params, &kou, option_type)] {
+                    let greeks = result.greeks;
+                    assert_relative_eq!(greeks.price, expected.price, epsilon = 1e-9);
+                    assert_relative_eq!(greeks.delta, expected.delta, epsilon = 1e-9);
+                    assert_relative_eq!(greeks.gamma, expected.gamma, epsilon = 1e-9);
+                    assert_relative_eq!(greeks.vega, expected.vega, epsilon = 1e-8);
+                    assert_relative_eq!(greeks.theta, expected.theta, epsilon = 1e-8);
+                    assert_relative_eq!(greeks.rho, expected.rho, epsilon = 1e-8);
+                    assert_relative_eq!(greeks.phi, expected.phi, epsilon = 1e-8);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_greeks_against_bumps() {
+        let params = market(105.0, 0.75);
+        let merton = MertonJumps::new(0.8, -0.15, 0.2);
+        let kou = KouJumps::new(2.0, 0.35, 12.0, 6.0);
+        type Pricer<'a> = &'a dyn Fn(&BlackScholesParams, f64) -> f64;
+        let pricers: [(Pricer, JumpDiffusionGreeks, f64); 2] = [
+            (
+                &|p, intensity| merton_price(p, &MertonJumps { intensity, ..merton }, OptionType::Put),
+                merton_greeks(&params, &merton, OptionType::Put),
+                merton.intensity,
+            ),
+            (
+                &|p, intensity| kou_price(p, &KouJumps { intensity, ..kou }, OptionType::Put),
+                kou_greeks(&params, &kou, OptionType::Put),
+                kou.intensity,
+            ),
+        ];
+        for (pricer, result, intensity) in pricers {
+            let bumped = |f: &dyn Fn(&mut BlackScholesParams, f64), h: f64| {
+                let value = |sign: f64| {
+                    let mut p = params;
+                    f(&mut p, sign * h);
+                    pricer(&p, intensity)
+                };
+                (value(1.0) - value(-1.0)) / (2.0 * h)
+            };
+            let greeks = result.greeks;
+            assert_relative_eq!(greeks.price, pricer(&params, intensity), epsilon = 1e-12);
+            assert_relative_eq!(greeks.delta, bumped(&|p, h| p.spot += h, 1e-3), epsilon = 1e-7);
+            assert_relative_eq!(greeks.vega, bumped(&|p, h| p.volatility += h, 1e-5), epsilon = 1e-6);
+            assert_relative_eq!(greeks.theta, -bumped(&|p, h| p.time_to_maturity += h, 1e-5), epsilon = 1e-6);
+            assert_relative_eq!(greeks.rho, bumped(&|p, h| p.risk_free_rate += h, 1e-5), epsilon = 1e-6);
+            assert_relative_eq!(greeks.phi, bumped(&|p, h| p.dividend_yield += h, 1e-5), epsilon = 1e-6);
+
+            let h = 1e-3;
+            let gamma = (pricer(&BlackScholesParams { spot: 100.0 + h, ..params }, intensity) - 2.0 * greeks.price
+                + pricer(&BlackScholesParams { spot: 100.0 - h, ..params }, intensity))
+                / (h * h);
+            assert_relative_eq!(greeks.gamma, gamma, epsilon = 1e-5);
+
+            let h = 1e-5;
+            let expected = (pricer(&params, intensity + h) - pricer(&params, intensity - h)) / (2.0 * h);
+            assert_relative_eq!(result.intensity, expected, epsilon = 1e-6);
+            // Adding downward jumps makes the put dearer
+            assert!(result.intensity > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        let params = market(100.0, 1.0);
+        assert!(try_merton_greeks(&params, &MertonJumps::new(1.0, -0.1, 0.15), OptionType::Call).is_ok());
+        assert_eq!(
+            try_merton_greeks(&params, &MertonJumps::new(-1.0, -0.1, 0.15), OptionType::Call).unwrap_err(),
+            PricingError::InvalidParameter { name: "intensity", value: -1.0 }
+        );
+        assert_eq!(
+            try_kou_greeks(&params, &KouJumps::new(1.0, 0.4, 0.8, 5.0), OptionType::Call).unwrap_err(),
+            PricingError::InvalidParameter { name: "up_rate", value: 0.8 }
+        );
+        assert_eq!(
+            try_kou_greeks(&params, &KouJumps::new(1.0, f64::NAN, 10.0, 5.0), OptionType::Call).unwrap_err(),
+            PricingError::NonFinite("up_probability")
+        );
+
+        // Expired options are worth intrinsic value with no jump exposure
+        let expired = BlackScholesParams::new(110.0, 100.0, 0.0, 0.2, 0.05, 0.02);
+        let result = kou_greeks(&expired, &KouJumps::new(1.0, 0.4, 10.0, 5.0), OptionType::Call);
+        assert_relative_eq!(result.greeks.price, 10.0);
+        assert_relative_eq!(result.intensity, 0.0);
+    }
+}
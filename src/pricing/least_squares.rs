@@ -0,0 +1,170 @@
+//! Dense linear and nonlinear least squares shared by regression and calibration code
+
+/// Solve the square system `matrix * x = rhs` by Gaussian elimination with
+/// partial pivoting; `None` if the matrix is numerically singular
+pub(crate) fn solve_linear_system(mut matrix: Vec<f64>, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+    let n = rhs.len();
+    let scale = matrix.iter().fold(0.0_f64, |m, v| m.max(v.abs()));
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&a, &b| matrix[a * n + col].abs().total_cmp(&matrix[b * n + col].abs()))?;
+        if matrix[pivot * n + col].abs() <= 1e-14 * scale {
+            return None;
+        }
+        if pivot != col {
+            for k in 0..n {
+                matrix.swap(pivot * n + k, col * n + k);
+            }
+            rhs.swap(pivot, col);
+        }
+        for row in col + 1..n {
+            let factor = matrix[row * n + col] / matrix[col * n + col];
+            for k in col..n {
+                matrix[row * n + k] -= factor * matrix[col * n + k];
+            }
+            rhs[row] -= factor * rhs[col];
+        }
+    }
+    let mut solution = vec![0.0; n];
+    for row in (0..n).rev() {
+        let tail: f64 = (row + 1..n).map(|k| matrix[row * n + k] * solution[k]).sum();
+        solution[row] = (rhs[row] - tail) / matrix[row * n + row];
+    }
+    Some(solution)
+}
+
+/// Outcome of a Levenberg-Marquardt fit
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Fit<const N: usize> {
+    pub x: [f64; N],
+    pub iterations: usize,
+    /// Whether the fit stopped on the tolerance, rather than on the iteration
+    /// limit or for want of a step that lowers the cost
+    pub converged: bool,
+}
+
+/// Minimise ½‖r(x)‖² by Levenberg-Marquardt with Marquardt's diagonal scaling
+///
+/// `evaluate` returns the residuals and one Jacobian row per residual. Trial
+/// points are clamped to the box `[lower, upper]`, and parameters sitting on a
+/// bound that the gradient pushes against are held fixed for the step, so an
+/// active bound does not stall the search. `project` then maps each trial point
+/// onto any further constraint.
+pub(crate) fn levenberg_marquardt<const N: usize>(
+    initial: [f64; N],
+    lower: [f64; N],
+    upper: [f64; N],
+    project: impl Fn(&mut [f64; N]),
+    evaluate: impl Fn(&[f64; N]) -> (Vec<f64>, Vec<[f64; N]>),
+    max_iterations: usize,
+    tolerance: f64,
+) -> Fit<N> {
+    let clamp = |x: &mut [f64; N]| {
+        for (i, value) in x.iter_mut().enumerate() {
+            *value = value.clamp(lower[i], upper[i]);
+        }
+        project(x);
+    };
+    let mut x = initial;
+    clamp(&mut x);
+    let (mut residuals, mut jacobian) = evaluate(&x);
+    let mut cost = half_sum_of_squares(&residuals);
+    let mut damping = 1e-3;
+
+    for iteration in 1..=max_iterations {
+        // Normal equations JᵀJ δ = -Jᵀr
+        let mut normal_matrix = vec![0.0; N * N];
+        let mut gradient = vec![0.0; N];
+        for (r, row) in residuals.iter().zip(&jacobian) {
+            for a in 0..N {
+                gradient[a] += row[a] * r;
+                for b in 0..N {
+                    normal_matrix[a * N + b] += row[a] * row[b];
+                }
+            }
+        }
+        if cost <= f64::MIN_POSITIVE {
+            return Fit { x, iterations: iteration - 1, converged: true };
+        }
+        let active: Vec<bool> = (0..N)
+            .map(|i| (x[i] <= lower[i] && gradient[i] > 0.0) || (x[i] >= upper[i] && gradient[i] < 0.0))
+            .collect();
+
+        loop {
+            let mut damped = normal_matrix.clone();
+            let mut rhs: Vec<f64> = gradient.iter().map(|g| -g).collect();
+            for a in 0..N {
+                damped[a * N + a] += damping * normal_matrix[a * N + a].max(1e-12);
+                if active[a] {
+                    // Pin the parameter: zero its row and column, unit diagonal
+                    for b in 0..N {
+                        damped[a * N + b] = 0.0;
+                        damped[b * N + a] = 0.0;
+                    }
+                    damped[a * N + a] = 1.0;
+                    rhs[a] = 0.0;
+                }
+            }
+            let step = solve_linear_system(damped, rhs);
+
+            if let Some(step) = step {
+                let mut trial = x;
+                trial.iter_mut().zip(&step).for_each(|(value, delta)| *value += delta);
+                clamp(&mut trial);
+                let (trial_residuals, trial_jacobian) = evaluate(&trial);
+                let trial_cost = half_sum_of_squares(&trial_residuals);
+
+                if trial_cost < cost {
+                    let small_step = x
+                        .iter()
+                        .zip(&trial)
+                        .all(|(old, new)| (new - old).abs() <= tolerance * (old.abs() + tolerance));
+                    let small_reduction = cost - trial_cost <= tolerance * cost;
+                    x = trial;
+                    residuals = trial_residuals;
+                    jacobian = trial_jacobian;
+                    cost = trial_cost;
+                    damping = (damping / 3.0).max(1e-12);
+                    if small_step || small_reduction {
+                        return Fit { x, iterations: iteration, converged: true };
+                    }
+                    break;
+                }
+            }
+
+            damping *= 4.0;
+            if damping > 1e12 {
+                // No descent direction left at this point: stalled short of the tolerance
+                return Fit { x, iterations: iteration, converged: false };
+            }
+        }
+    }
+
+    Fit { x, iterations: max_iterations, converged: false }
+}
+
+fn half_sum_of_squares(residuals: &[f64]) -> f64 {
+    0.5 * residuals.iter().map(|r| r * r).sum::<f64>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_converges_and_reports_stall() {
+        // Rosenbrock as a least squares problem, r = (10 (y - x²), 1 - x)
+        let evaluate = |x: &[f64; 2]| (vec![10.0 * (x[1] - x[0] * x[0]), 1.0 - x[0]], vec![[-20.0 * x[0], 10.0], [-1.0, 0.0]]);
+        let fit = levenberg_marquardt([-1.2, 1.0], [-5.0; 2], [5.0; 2], |_| {}, evaluate, 200, 1e-12);
+        assert!(fit.converged);
+        assert_relative_eq!(fit.x[0], 1.0, epsilon = 1e-6);
+        assert_relative_eq!(fit.x[1], 1.0, epsilon = 1e-6);
+
+        // A Jacobian of the wrong sign never yields a descent step
+        let evaluate = |x: &[f64; 1]| (vec![x[0] - 1.0], vec![[-1.0]]);
+        let fit = levenberg_marquardt([0.0], [-5.0], [5.0], |_| {}, evaluate, 200, 1e-12);
+        assert!(!fit.converged);
+        assert!(fit.iterations < 200);
+        assert_eq!(fit.x, [0.0]);
+    }
+}
@@ -0,0 +1,705 @@
+//! Peter Jäckel's "Let's Be Rational" implied volatility
+//!
+//! Works on the normalised Black function b(x, s) = e^(x/2) N(x/s + s/2) - e^(-x/2) N(x/s - s/2),
+//! with x = ln(F/K) and s = σ√T. A rational cubic initial guess on one of four
+//! branches followed by two Householder(3) steps on a branch-specific objective
+//! recovers the volatility to within a few ulps of what the price itself conveys,
+//! from tiny-premium wings to near-intrinsic quotes.
+//!
+//! Reference: P. Jäckel, "Let's Be Rational", Wilmott (2015), 40-53.
+
+use crate::ad::ops::{erfc_value, erfcx_value};
+use crate::pricing::PricingError;
+use crate::types::OptionType;
+use std::f64::consts::PI;
+
+const SQRT_DBL_EPSILON: f64 = 1.4901161193847656e-8;
+const FOURTH_ROOT_DBL_EPSILON: f64 = 1.220703125e-4;
+const SIXTEENTH_ROOT_DBL_EPSILON: f64 = 0.10511205190671431;
+const SQRT_DBL_MIN: f64 = 1.4916681462400413e-154;
+const SQRT_DBL_MAX: f64 = 1.3407807929942596e154;
+
+const ONE_OVER_SQRT_TWO: f64 = std::f64::consts::FRAC_1_SQRT_2;
+const ONE_OVER_SQRT_TWO_PI: f64 = 0.3989422804014327;
+const SQRT_PI_OVER_TWO: f64 = 1.2533141373155003;
+const SQRT_THREE: f64 = 1.7320508075688772;
+const SQRT_ONE_OVER_THREE: f64 = 0.5773502691896258;
+const TWO_PI_OVER_SQRT_TWENTY_SEVEN: f64 = 1.2091995761561452;
+const PI_OVER_SIX: f64 = PI / 6.0;
+
+/// Below x = ASYMPTOTIC_THRESHOLD · s the asymptotic expansion of b is used
+const ASYMPTOTIC_THRESHOLD: f64 = -10.0;
+/// Below s/2 = SMALL_T_THRESHOLD the Taylor expansion in s is used
+const SMALL_T_THRESHOLD: f64 = 2.0 * SIXTEENTH_ROOT_DBL_EPSILON;
+
+/// Householder iterations after the initial guess
+const ITERATIONS: usize = 2;
+
+/// Bounds on the rational cubic control parameter
+const MIN_CONTROL: f64 = -(1.0 - SQRT_DBL_EPSILON);
+const MAX_CONTROL: f64 = 2.0 / (f64::EPSILON * f64::EPSILON);
+
+/// Black price on the forward, undiscounted
+pub fn black_price(forward: f64, strike: f64, volatility: f64, time_to_maturity: f64, option_type: OptionType) -> f64 {
+    let x = (forward / strike).ln();
+    let s = volatility * time_to_maturity.sqrt();
+    let b = match option_type {
+        OptionType::Call => normalised_black_call(x, s),
+        OptionType::Put => normalised_black_call(-x, s),
+    };
+    forward.sqrt() * strike.sqrt() * b
+}
+
+/// Solve the undiscounted Black price for volatility
+///
+/// Returns `PricingError::ArbitrageViolation` unless the price lies within
+/// [intrinsic, F) for calls or [intrinsic, K) for puts; a price equal to the
+/// intrinsic value implies zero volatility.
+pub fn implied_black_volatility(
+    price: f64,
+    forward: f64,
+    strike: f64,
+    time_to_maturity: f64,
+    option_type: OptionType,
+) -> Result<f64, PricingError> {
+    let (q, intrinsic, upper) = match option_type {
+        OptionType::Call => (1.0, (forward - strike).max(0.0), forward),
+        OptionType::Put => (-1.0, (strike - forward).max(0.0), strike),
+    };
+    if price < intrinsic || price >= upper {
+        return Err(PricingError::ArbitrageViolation { price, lower: intrinsic, upper });
+    }
+    if time_to_maturity <= 0.0 {
+        return Err(PricingError::ImpliedVolNotFound);
+    }
+
+    let x = (forward / strike).ln();
+    // Strip the intrinsic value so the in-the-money case becomes the out-of-the-money one
+    let (price, q) = if q * x > 0.0 { ((price - intrinsic).max(0.0), -q) } else { (price, q) };
+    let beta = price / (forward.sqrt() * strike.sqrt());
+    let s = normalised_implied_volatility(beta, x, q);
+
+    if s.is_finite() {
+        Ok(s / time_to_maturity.sqrt())
+    } else {
+        Err(PricingError::ImpliedVolNotFound)
+    }
+}
+
+/// Scaled complementary error function erfcx(x) = e^(x²) erfc(x)
+pub fn erfcx(x: f64) -> f64 {
+    erfcx_value(x)
+}
+
+/// Standard normal CDF accurate to full relative precision deep into the left tail
+fn norm_cdf(z: f64) -> f64 {
+    0.5 * erfc_value(-z * ONE_OVER_SQRT_TWO)
+}
+
+fn norm_pdf(z: f64) -> f64 {
+    ONE_OVER_SQRT_TWO_PI * (-0.5 * z * z).exp()
+}
+
+/// Inverse standard normal CDF, Wichura's algorithm AS241 (relative accuracy ~1e-16)
+pub(crate) fn inverse_norm_cdf(p: f64) -> f64 {
+    let q = p - 0.5;
+    if q.abs() <= 0.425 {
+        let r = 0.180625 - q * q;
+        let num = (((((((2.5090809287301227e3 * r + 3.343057558358813e4) * r
+            + 6.72657709270087e4)
+            * r
+            + 4.592195393154987e4)
+            * r
+            + 1.373169376550946e4)
+            * r
+            + 1.9715909503065513e3)
+            * r
+            + 1.3314166789178438e2)
+            * r
+            + 3.3871328727963665)
+            * q;
+        let den = ((((((5.226495278852854e3 * r + 2.8729085735721943e4) * r
+            + 3.930789580009271e4)
+            * r
+            + 2.1213794301586597e4)
+            * r
+            + 5.394196021424751e3)
+            * r
+            + 6.871870074920579e2)
+            * r
+            + 4.231333070160091e1)
+            * r
+            + 1.0;
+        return num / den;
+    }
+
+    let r = if q <= 0.0 { p } else { 1.0 - p };
+    let r = (-r.ln()).sqrt();
+    let x = if r <= 5.0 {
+        let r = r - 1.6;
+        let num = ((((((7.745450142783414e-4 * r + 2.272384498926918e-2) * r
+            + 2.417807251774506e-1)
+            * r
+            + 1.2704582524523684)
+            * r
+            + 3.6478483247632045)
+            * r
+            + 5.769497221460691)
+            * r
+            + 4.630337846156545)
+            * r
+            + 1.4234371107496835;
+        let den = ((((((1.0507500716444169e-9 * r + 5.475938084995345e-4) * r
+            + 1.5198666563616457e-2)
+            * r
+            + 1.4810397642748008e-1)
+            * r
+            + 6.897673349851e-1)
+            * r
+            + 1.6763848301838038)
+            * r
+            + 2.053191626637759)
+            * r
+            + 1.0;
+        num / den
+    } else {
+        let r = r - 5.0;
+        let num = ((((((2.0103343992922884e-7 * r + 2.7115555687434876e-5) * r
+            + 1.2426609473880784e-3)
+            * r
+            + 2.653218952657612e-2)
+            * r
+            + 2.965605718285049e-1)
+            * r
+            + 1.7848265399172913)
+            * r
+            + 5.463784911164114)
+            * r
+            + 6.657904643501103;
+        let den = ((((((2.0442631033899397e-15 * r + 1.421511758316446e-7) * r
+            + 1.8463183175100545e-5)
+            * r
+            + 7.868691311456133e-4)
+            * r
+            + 1.4875361290850615e-2)
+            * r
+            + 1.369298809227358e-1)
+            * r
+            + 5.99832206555888e-1)
+            * r
+            + 1.0;
+        num / den
+    };
+    if q < 0.0 { -x } else { x }
+}
+
+/// Normalised intrinsic value of a call (q = 1) or put (q = -1): max(q (e^(x/2) - e^(-x/2)), 0)
+fn normalised_intrinsic(x: f64, q: f64) -> f64 {
+    if q * x <= 0.0 {
+        return 0.0;
+    }
+    let x2 = x * x;
+    // 2 sinh(x/2) by its Taylor series where the difference of exponentials would cancel
+    let value = if x2 < 98.0 * FOURTH_ROOT_DBL_EPSILON {
+        x * (1.0 + x2 * (1.0 / 24.0 + x2 * (1.0 / 1920.0 + x2 * (1.0 / 322560.0 + x2 / 92897280.0))))
+    } else {
+        let b = (0.5 * x).exp();
+        b - 1.0 / b
+    };
+    (q * value).max(0.0)
+}
+
+/// Normalised Black call b(x, s) = e^(x/2) N(x/s + s/2) - e^(-x/2) N(x/s - s/2)
+///
+/// Accurate to a few ulps everywhere it is representable, including far out of
+/// the money where the two terms cancel almost completely. Puts follow from b(-x, s).
+pub fn normalised_black_call(x: f64, s: f64) -> f64 {
+    if x > 0.0 {
+        // In-out duality keeps the evaluation on the out-of-the-money side
+        return normalised_intrinsic(x, 1.0) + normalised_black_call(-x, s);
+    }
+    if s <= 0.0 {
+        return normalised_intrinsic(x, 1.0);
+    }
+    let h = x / s;
+    let t = 0.5 * s;
+    if x < s * ASYMPTOTIC_THRESHOLD && 0.5 * s * s + x < s * (SMALL_T_THRESHOLD + ASYMPTOTIC_THRESHOLD) {
+        asymptotic_expansion(h, t)
+    } else if t < SMALL_T_THRESHOLD {
+        small_t_expansion(h, t)
+    } else if x + 0.5 * s * s > s * 0.85 {
+        // Both cumulative normals are well away from the left tail
+        let b = (0.5 * x).exp();
+        (norm_cdf(h + t) * b - norm_cdf(h - t) / b).max(0.0)
+    } else {
+        0.5 * (-0.5 * (h * h + t * t)).exp()
+            * (erfcx(-ONE_OVER_SQRT_TWO * (h + t)) - erfcx(-ONE_OVER_SQRT_TWO * (h - t))).max(0.0)
+    }
+}
+
+/// Asymptotic expansion of b for h = x/s → -∞
+///
+/// b = φ(√(h² + t²)) (t/r) Σₙ cₙ qⁿ with r = (h + t)(h - t), q = (h/r)² and
+/// cₙ = 2 (-1)ⁿ (2n - 1)!! Σⱼ C(2n + 1, 2j + 1) (t/h)^(2j).
+fn asymptotic_expansion(h: f64, t: f64) -> f64 {
+    let e = (t / h) * (t / h);
+    let r = (h + t) * (h - t);
+    let q = (h / r) * (h / r);
+
+    let mut sum = 0.0;
+    let mut q_power = 1.0;
+    let mut double_factorial = 1.0;
+    for n in 0..17 {
+        let m = 2 * n + 1;
+        // Σⱼ C(m, 2j + 1) eʲ, with the binomial coefficients built incrementally
+        let mut poly = 0.0;
+        let mut e_power = 1.0;
+        let mut binomial = m as f64;
+        for j in 0..=n {
+            poly += binomial * e_power;
+            e_power *= e;
+            let k = 2 * j + 1;
+            binomial *= (m - k) as f64 * (m as f64 - k as f64 - 1.0) / ((k + 1) * (k + 2)) as f64;
+        }
+        let sign = if n % 2 == 0 { 2.0 } else { -2.0 };
+        sum += sign * double_factorial * poly * q_power;
+        q_power *= q;
+        double_factorial *= m as f64;
+    }
+
+    (ONE_OVER_SQRT_TWO_PI * (-0.5 * (h * h + t * t)).exp() * (t / r) * sum).max(0.0)
+}
+
+/// Taylor expansion of b in t = s/2 for small total volatility
+///
+/// Uses the Mills-ratio recursion Y₀ = √(π/2) erfcx(-h/√2), Y₁ = 1 + h Y₀,
+/// Yₙ = h Yₙ₋₁ + (n - 1) Yₙ₋₂, giving b = 2 φ(√(h² + t²)) Σ_{k odd} Yₖ tᵏ / k!.
+fn small_t_expansion(h: f64, t: f64) -> f64 {
+    let mut y = [0.0; 14];
+    y[0] = SQRT_PI_OVER_TWO * erfcx(-ONE_OVER_SQRT_TWO * h);
+    y[1] = 1.0 + h * y[0];
+    for n in 2..14 {
+        y[n] = h * y[n - 1] + (n - 1) as f64 * y[n - 2];
+    }
+
+    let t2 = t * t;
+    let mut sum = 0.0;
+    let mut term = t;
+    for k in (1..14).step_by(2) {
+        sum += y[k] * term;
+        term *= t2 / ((k + 1) * (k + 2)) as f64;
+    }
+
+    (2.0 * ONE_OVER_SQRT_TWO_PI * (-0.5 * (h * h + t * t)).exp() * sum).max(0.0)
+}
+
+/// ∂b/∂s = φ(x/s + s/2) e^(x/2)
+fn normalised_vega(x: f64, s: f64) -> f64 {
+    if x == 0.0 {
+        ONE_OVER_SQRT_TWO_PI * (-0.125 * s * s).exp()
+    } else if s <= x.abs() * SQRT_DBL_MIN {
+        0.0
+    } else {
+        let h = x / s;
+        ONE_OVER_SQRT_TWO_PI * (-0.5 * (h * h + 0.25 * s * s)).exp()
+    }
+}
+
+fn is_below_horizon(x: f64) -> bool {
+    x.abs() < f64::MIN_POSITIVE
+}
+
+/// Rational cubic interpolation through (x_l, y_l), (x_r, y_r) with end slopes d_l, d_r
+///
+/// The control parameter r blends between a cubic Hermite spline (r = 3) and
+/// the straight line (r → ∞).
+#[allow(clippy::too_many_arguments)]
+fn rational_cubic_interpolation(x: f64, x_l: f64, x_r: f64, y_l: f64, y_r: f64, d_l: f64, d_r: f64, r: f64) -> f64 {
+    let h = x_r - x_l;
+    if h.abs() <= 0.0 {
+        return 0.5 * (y_l + y_r);
+    }
+    let t = (x - x_l) / h;
+    if r < MAX_CONTROL {
+        let omt = 1.0 - t;
+        let t2 = t * t;
+        let omt2 = omt * omt;
+        (y_r * t2 * t + (r * y_r - h * d_r) * t2 * omt + (r * y_l + h * d_l) * t * omt2 + y_l * omt2 * omt)
+            / (1.0 + (r - 3.0) * t * omt)
+    } else {
+        y_r * t + y_l * (1.0 - t)
+    }
+}
+
+/// Smallest control parameter that keeps the interpolant monotone and convex (or concave)
+fn minimum_control_parameter(d_l: f64, d_r: f64, slope: f64, prefer_shape_preservation: bool) -> f64 {
+    let monotonic = d_l * slope >= 0.0 && d_r * slope >= 0.0;
+    let convex = d_l <= slope && slope <= d_r;
+    let concave = d_l >= slope && slope >= d_r;
+    if !monotonic && !convex && !concave {
+        return MIN_CONTROL;
+    }
+
+    let d_r_m_d_l = d_r - d_l;
+    let d_r_m_s = d_r - slope;
+    let s_m_d_l = slope - d_l;
+    let mut r1 = f64::MIN;
+    let mut r2 = f64::MIN;
+    if monotonic {
+        if !is_below_horizon(slope) {
+            r1 = (d_r + d_l) / slope;
+        } else if prefer_shape_preservation {
+            r1 = MAX_CONTROL;
+        }
+    }
+    if convex || concave {
+        if !(is_below_horizon(s_m_d_l) || is_below_horizon(d_r_m_s)) {
+            r2 = (d_r_m_d_l / d_r_m_s).abs().max((d_r_m_d_l / s_m_d_l).abs());
+        } else if prefer_shape_preservation {
+            r2 = MAX_CONTROL;
+        }
+    } else if monotonic && prefer_shape_preservation {
+        r2 = MAX_CONTROL;
+    }
+    MIN_CONTROL.max(r1.max(r2))
+}
+
+/// Control parameter matching a second derivative `d2` at one end of the interval
+#[allow(clippy::too_many_arguments)]
+fn convex_control_parameter(
+    x_l: f64,
+    x_r: f64,
+    y_l: f64,
+    y_r: f64,
+    d_l: f64,
+    d_r: f64,
+    d2: f64,
+    match_left: bool,
+    prefer_shape_preservation: bool,
+) -> f64 {
+    let h = x_r - x_l;
+    let slope = (y_r - y_l) / h;
+    let numerator = 0.5 * h * d2 + (d_r - d_l);
+    let r = if is_below_horizon(numerator) {
+        0.0
+    } else {
+        let denominator = if match_left { slope - d_l } else { d_r - slope };
+        if is_below_horizon(denominator) {
+            if numerator > 0.0 { MAX_CONTROL } else { MIN_CONTROL }
+        } else {
+            numerator / denominator
+        }
+    };
+    r.max(minimum_control_parameter(d_l, d_r, slope, prefer_shape_preservation))
+}
+
+/// Lower-branch transformation f(b) ≈ b for tiny premiums, with its first two derivatives in b
+fn lower_map(x: f64, s: f64) -> (f64, f64, f64) {
+    let ax = x.abs();
+    let z = SQRT_ONE_OVER_THREE * ax / s;
+    let y = z * z;
+    let s2 = s * s;
+    let phi_cdf = norm_cdf(-z);
+    let phi_pdf = norm_pdf(z);
+    let d2f = PI_OVER_SIX * y / (s2 * s)
+        * phi_cdf
+        * (8.0 * SQRT_THREE * s * ax + (3.0 * s2 * (s2 - 8.0) - 8.0 * x * x) * phi_cdf / phi_pdf)
+        * (2.0 * y + 0.25 * s2).exp();
+    if is_below_horizon(s) {
+        return (0.0, 1.0, d2f);
+    }
+    let phi2 = phi_cdf * phi_cdf;
+    let df = 2.0 * PI * y * phi2 * (y + 0.125 * s2).exp();
+    let f = if is_below_horizon(x) { 0.0 } else { TWO_PI_OVER_SQRT_TWENTY_SEVEN * ax * phi2 * phi_cdf };
+    (f, df, d2f)
+}
+
+fn inverse_lower_map(x: f64, f: f64) -> f64 {
+    if is_below_horizon(f) {
+        0.0
+    } else {
+        (x / (SQRT_THREE * inverse_norm_cdf((f / (TWO_PI_OVER_SQRT_TWENTY_SEVEN * x.abs())).cbrt()))).abs()
+    }
+}
+
+/// Upper-branch transformation f(b) = N(-s/2) with its first two derivatives in b
+fn upper_map(x: f64, s: f64) -> (f64, f64, f64) {
+    let f = norm_cdf(-0.5 * s);
+    if is_below_horizon(x) {
+        return (f, -0.5, 0.0);
+    }
+    let w = (x / s) * (x / s);
+    (f, -0.5 * (0.5 * w).exp(), SQRT_PI_OVER_TWO * (w + 0.125 * s * s).exp() * w / s)
+}
+
+fn inverse_upper_map(f: f64) -> f64 {
+    -2.0 * inverse_norm_cdf(f)
+}
+
+/// Householder(3) correction factor given the Newton step n, h₂ = f''/f' and h₃ = f'''/f'
+fn householder_factor(newton: f64, halley: f64, hh3: f64) -> f64 {
+    (1.0 + 0.5 * halley * newton) / (1.0 + newton * (halley + hh3 * newton / 6.0))
+}
+
+/// Safeguarded Householder(3) iterations
+///
+/// `step` maps (s, b(s), b'(s)) to the proposed update; iterates that leave the
+/// running bracket, or that oscillate, fall back to bisection.
+fn householder_iterations(
+    beta: f64,
+    x: f64,
+    mut s: f64,
+    mut s_left: f64,
+    mut s_right: f64,
+    step: impl Fn(f64, f64, f64, f64, f64) -> f64,
+) -> f64 {
+    let mut ds = f64::MIN;
+    let mut ds_previous = 0.0;
+    let mut reversals = 0;
+    for iteration in 0..ITERATIONS {
+        if ds.abs() <= f64::EPSILON * s {
+            break;
+        }
+        if ds * ds_previous < 0.0 {
+            reversals += 1;
+        }
+        if iteration > 0 && (reversals == 3 || !(s > s_left && s < s_right)) {
+            s = 0.5 * (s_left + s_right);
+            if s_right - s_left <= f64::EPSILON * s {
+                break;
+            }
+            reversals = 0;
+            ds = 0.0;
+        }
+        ds_previous = ds;
+
+        let b = normalised_black_call(x, s);
+        let vega = normalised_vega(x, s);
+        if b > beta && s < s_right {
+            s_right = s;
+        } else if b < beta && s > s_left {
+            s_left = s;
+        }
+        ds = step(s, b, vega, s_left, s_right).max(-0.5 * s);
+        s += ds;
+    }
+    s
+}
+
+/// Step on the plain objective b(s) - β
+fn central_step(beta: f64, x: f64) -> impl Fn(f64, f64, f64, f64, f64) -> f64 {
+    move |s, b, vega, _, _| {
+        let newton = (beta - b) / vega;
+        let halley = (x / s) * (x / s) / s - 0.25 * s;
+        let hh3 = halley * halley - 3.0 * (x / (s * s)) * (x / (s * s)) - 0.25;
+        newton * householder_factor(newton, halley, hh3)
+    }
+}
+
+/// Normalised implied volatility s = σ√T of an out-of-the-money normalised price β
+fn normalised_implied_volatility(beta: f64, x: f64, q: f64) -> f64 {
+    // Work with the out-of-the-money call: b_put(x) = b_call(-x)
+    let x = if q < 0.0 { -x } else { x };
+    if beta <= 0.0 {
+        return 0.0;
+    }
+    let b_max = (0.5 * x).exp();
+    if beta >= b_max {
+        return f64::INFINITY;
+    }
+
+    let s_c = (2.0 * x).abs().sqrt();
+    let b_c = normalised_black_call(x, s_c);
+    let v_c = normalised_vega(x, s_c);
+
+    if beta < b_c {
+        let s_l = s_c - b_c / v_c;
+        let b_l = normalised_black_call(x, s_l);
+        if beta < b_l {
+            // Lowest branch: interpolate in the transformed variable f, then iterate on ln(b)
+            let (f_l, df_l, d2f_l) = lower_map(x, s_l);
+            let r = convex_control_parameter(0.0, b_l, 0.0, f_l, 1.0, df_l, d2f_l, false, true);
+            let mut f = rational_cubic_interpolation(beta, 0.0, b_l, 0.0, f_l, 1.0, df_l, r);
+            if f <= 0.0 || f.is_nan() {
+                let t = beta / b_l;
+                f = (f_l * t + b_l * (1.0 - t)) * t;
+            }
+            let s = inverse_lower_map(x, f);
+            let ln_beta = beta.ln();
+            return householder_iterations(beta, x, s, f64::MIN_POSITIVE, s_l, move |s, b, vega, s_left, s_right| {
+                if b <= 0.0 || vega <= 0.0 {
+                    return 0.5 * (s_left + s_right) - s;
+                }
+                let ln_b = b.ln();
+                let vega_over_b = vega / b;
+                let h = x / s;
+                let b_halley = h * h / s - 0.25 * s;
+                let b_hh3 = b_halley * b_halley - 3.0 * (h / s) * (h / s) - 0.25;
+                let newton = (ln_beta - ln_b) * ln_b / ln_beta / vega_over_b;
+                let halley = b_halley - vega_over_b * (1.0 + 2.0 / ln_b);
+                let hh3 = b_hh3 + 2.0 * vega_over_b * vega_over_b * (1.0 + 3.0 / ln_b * (1.0 + 1.0 / ln_b))
+                    - 3.0 * b_halley * vega_over_b * (1.0 + 2.0 / ln_b);
+                newton * householder_factor(newton, halley, hh3)
+            });
+        }
+
+        // Lower-middle branch: interpolate s(β) directly between s_l and s_c
+        let v_l = normalised_vega(x, s_l);
+        let r = convex_control_parameter(b_l, b_c, s_l, s_c, 1.0 / v_l, 1.0 / v_c, 0.0, false, false);
+        let s = rational_cubic_interpolation(beta, b_l, b_c, s_l, s_c, 1.0 / v_l, 1.0 / v_c, r);
+        return householder_iterations(beta, x, s, s_l, s_c, central_step(beta, x));
+    }
+
+    let s_h = if v_c > f64::MIN_POSITIVE { s_c + (b_max - b_c) / v_c } else { s_c };
+    let b_h = normalised_black_call(x, s_h);
+    if beta <= b_h {
+        // Upper-middle branch
+        let v_h = normalised_vega(x, s_h);
+        let r = convex_control_parameter(b_c, b_h, s_c, s_h, 1.0 / v_c, 1.0 / v_h, 0.0, true, false);
+        let s = rational_cubic_interpolation(beta, b_c, b_h, s_c, s_h, 1.0 / v_c, 1.0 / v_h, r);
+        return householder_iterations(beta, x, s, s_c, s_h, central_step(beta, x));
+    }
+
+    // Highest branch: interpolate in f = N(-s/2), then iterate on ln(b_max - b)
+    let (f_h, df_h, d2f_h) = upper_map(x, s_h);
+    let mut f = f64::MIN;
+    if d2f_h > -SQRT_DBL_MAX && d2f_h < SQRT_DBL_MAX {
+        let r = convex_control_parameter(b_h, b_max, f_h, 0.0, df_h, -0.5, d2f_h, true, true);
+        f = rational_cubic_interpolation(beta, b_h, b_max, f_h, 0.0, df_h, -0.5, r);
+    }
+    if f <= 0.0 {
+        let h = b_max - b_h;
+        let t = (beta - b_h) / h;
+        f = (f_h * (1.0 - t) + 0.5 * h * t) * (1.0 - t);
+    }
+    let s = inverse_upper_map(f);
+    if beta <= 0.5 * b_max {
+        return householder_iterations(beta, x, s, s_h, f64::MAX, central_step(beta, x));
+    }
+    householder_iterations(beta, x, s, s_h, f64::MAX, move |s, b, vega, s_left, s_right| {
+        if b >= b_max || vega <= f64::MIN_POSITIVE {
+            return 0.5 * (s_left + s_right) - s;
+        }
+        let b_max_minus_b = b_max - b;
+        let g = ((b_max - beta) / b_max_minus_b).ln();
+        let g_prime = vega / b_max_minus_b;
+        let b_halley = (x / s) * (x / s) / s - 0.25 * s;
+        let b_hh3 = b_halley * b_halley - 3.0 * (x / (s * s)) * (x / (s * s)) - 0.25;
+        let newton = -g / g_prime;
+        let halley = b_halley + g_prime;
+        let hh3 = b_hh3 + g_prime * (2.0 * g_prime + 3.0 * b_halley);
+        newton * householder_factor(newton, halley, hh3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_erfcx_reference_values() {
+        // e^(x²) erfc(x) evaluated in 50-digit arithmetic
+        let cases = [
+            (-3.0, 16205.988853999586),
+            (-0.3, 1.4537492328427657),
+            (0.0, 1.0),
+            (0.2, 0.8090195199015807),
+            (1.0, 0.427583576155807),
+            (5.0, 0.11070463773306863),
+            (30.0, 0.01879588886141675),
+            (1000.0, 0.0005641893014533876),
+        ];
+        for &(x, expected) in &cases {
+            assert_relative_eq!(erfcx(x), expected, max_relative = 1e-15);
+        }
+    }
+
+    #[test]
+    fn test_normalised_black_reference_values() {
+        // b(x, s) evaluated in 50-digit arithmetic, covering every branch
+        let cases = [
+            (0.0, 0.2, 0.07965567455405796),
+            (-0.5, 0.1, 5.339911785800865e-9),
+            (-5.0, 0.5, 3.624416711834472e-25),
+            (-30.0, 1.0, 1.4405938487063513e-199),
+            (-1.0, 0.03, 5.7061602972737865e-247),
+            (-0.01, 0.001, 7.474559337762835e-28),
+            (-2.0, 3.0, 0.2523190047975495),
+            (1.5, 0.4, 1.6446417259884805),
+            (-200.0, 8.0, 3.355835208774899e-142),
+        ];
+        for &(x, s, expected) in &cases {
+            assert_relative_eq!(normalised_black_call(x, s), expected, max_relative = 1e-13);
+        }
+    }
+
+    #[test]
+    fn test_inverse_norm_cdf() {
+        // The upper tail is limited by the rounding of p itself, so stay below z = 2
+        for &z in &[-30.0, -8.0, -2.0, -0.3, 0.0, 0.5, 1.7] {
+            assert_relative_eq!(inverse_norm_cdf(norm_cdf(z)), z, epsilon = 1e-13, max_relative = 1e-13);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_extreme_moneyness_and_vol() {
+        let forward = 100.0;
+        let strikes = [1e-3, 0.1, 1.0, 10.0, 50.0, 80.0, 95.0, 100.0, 105.0, 120.0, 200.0, 1e3, 1e4, 1e5];
+        let vols = [0.001, 0.01, 0.05, 0.2, 0.5, 1.0, 2.0, 4.0];
+        let maturities = [1.0 / 365.0, 0.1, 1.0, 10.0];
+
+        for &strike in &strikes {
+            for &vol in &vols {
+                for &t in &maturities {
+                    for &option_type in &[OptionType::Call, OptionType::Put] {
+                        let price = black_price(forward, strike, vol, t, option_type);
+                        let intrinsic = match option_type {
+                            OptionType::Call => (forward - strike).max(0.0),
+                            OptionType::Put => (strike - forward).max(0.0),
+                        };
+                        // Skip quotes whose time value is lost to rounding or underflow
+                        if price < 1e-300 || price - intrinsic <= 1e-15 * price {
+                            continue;
+                        }
+
+                        let solved = implied_black_volatility(price, forward, strike, t, option_type).unwrap();
+
+                        // The price pins σ down to about ε p / (σ vega); allow a small multiple of that
+                        let x = (forward / strike).ln();
+                        let s = vol * t.sqrt();
+                        let vega = (forward * strike).sqrt() * normalised_vega(x, s) * s;
+                        let conditioning = f64::EPSILON * price / vega;
+                        assert_relative_eq!(solved, vol, max_relative = 1e-14_f64.max(16.0 * conditioning));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_tiny_premium_wings() {
+        // Deep out-of-the-money quotes where Newton on the raw price diverges
+        let price = black_price(100.0, 400.0, 0.15, 0.25, OptionType::Call);
+        assert!(price < 1e-60);
+        let vol = implied_black_volatility(price, 100.0, 400.0, 0.25, OptionType::Call).unwrap();
+        assert_relative_eq!(vol, 0.15, max_relative = 1e-14);
+
+        let price = black_price(100.0, 20.0, 0.3, 0.5, OptionType::Put);
+        let vol = implied_black_volatility(price, 100.0, 20.0, 0.5, OptionType::Put).unwrap();
+        assert_relative_eq!(vol, 0.3, max_relative = 1e-14);
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert_eq!(implied_black_volatility(10.0, 110.0, 100.0, 1.0, OptionType::Call).unwrap(), 0.0);
+        assert!(matches!(
+            implied_black_volatility(9.0, 110.0, 100.0, 1.0, OptionType::Call),
+            Err(PricingError::ArbitrageViolation { .. })
+        ));
+        assert!(matches!(
+            implied_black_volatility(100.0, 110.0, 100.0, 1.0, OptionType::Put),
+            Err(PricingError::ArbitrageViolation { .. })
+        ));
+    }
+}
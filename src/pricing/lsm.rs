@@ -0,0 +1,371 @@
+//! Longstaff-Schwartz least-squares Monte Carlo for Bermudan options
+//!
+//! The continuation value at each exercise date is regressed on basis functions
+//! of moneyness S/K, using only in-the-money paths, while rolling realised cash
+//! flows back from maturity. The fitted exercise rule is then applied to a fresh,
+//! independent set of paths. Prices from the second pass carry no foresight bias
+//! and, because the rule is suboptimal, are biased low.
+//!
+//! Paths are simulated exactly on the exercise dates from `RngStream`s keyed by
+//! path index, so results are reproducible whatever the number of threads.
+
+use crate::pricing::black_scholes::{expired_greeks, BlackScholesParams};
+use crate::pricing::least_squares::solve_linear_system;
+use crate::pricing::monte_carlo::{PriceEstimate, RngStream};
+use crate::pricing::PricingError;
+use crate::types::OptionType;
+use rayon::prelude::*;
+
+/// Functions of moneyness x = S/K spanning the continuation value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RegressionBasis {
+    /// 1, x, x², ..., x^degree
+    Polynomial,
+    /// 1 and the weighted Laguerre polynomials e^(-x/2) L_n(x) for n < degree,
+    /// as in Longstaff and Schwartz (2001)
+    #[default]
+    Laguerre,
+}
+
+impl RegressionBasis {
+    fn evaluate(self, x: f64, out: &mut [f64]) {
+        match self {
+            RegressionBasis::Polynomial => {
+                let mut power = 1.0;
+                for value in out.iter_mut() {
+                    *value = power;
+                    power *= x;
+                }
+            }
+            RegressionBasis::Laguerre => {
+                out[0] = 1.0;
+                let weight = (-0.5 * x).exp();
+                let (mut previous, mut current) = (0.0, 1.0);
+                for (n, value) in out.iter_mut().enumerate().skip(1) {
+                    *value = weight * current;
+                    let next = ((2 * n - 1) as f64 - x) * current - (n - 1) as f64 * previous;
+                    previous = current;
+                    current = next / n as f64;
+                }
+            }
+        }
+    }
+}
+
+/// Simulation and regression settings
+#[derive(Debug, Clone, Copy)]
+pub struct LsmSettings {
+    /// Paths in the pricing pass, counting both members of an antithetic pair
+    pub paths: usize,
+    /// Paths used to fit the exercise rule
+    pub regression_paths: usize,
+    pub basis: RegressionBasis,
+    /// Basis functions beyond the constant term
+    pub degree: usize,
+    pub seed: u64,
+    /// Pair each pricing path with its mirror image -Z
+    pub antithetic: bool,
+}
+
+impl Default for LsmSettings {
+    fn default() -> Self {
+        Self {
+            paths: 100_000,
+            regression_paths: 50_000,
+            basis: RegressionBasis::default(),
+            degree: 3,
+            seed: 42,
+            antithetic: true,
+        }
+    }
+}
+
+/// Price estimate from the pricing pass
+pub type LsmResult = PriceEstimate;
+
+/// Dates evenly spaced over (0, maturity], ending at maturity
+pub fn uniform_schedule(time_to_maturity: f64, dates: usize) -> Vec<f64> {
+    (1..=dates)
+        .map(|i| time_to_maturity * i as f64 / dates as f64)
+        .collect()
+}
+
+/// Samples simulated per parallel task in the pricing pass
+const CHUNK_SIZE: usize = 1024;
+
+/// Price a Bermudan option exercisable on `exercise_times` and at maturity
+///
+/// An empty schedule gives the European option.
+pub fn price(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    exercise_times: &[f64],
+    settings: &LsmSettings,
+) -> LsmResult {
+    if params.time_to_maturity <= 0.0 {
+        return LsmResult {
+            price: expired_greeks(params, option_type).price,
+            standard_error: 0.0,
+            samples: 0,
+        };
+    }
+
+    let mut dates: Vec<f64> = exercise_times
+        .iter()
+        .copied()
+        .filter(|&t| t < params.time_to_maturity)
+        .collect();
+    dates.push(params.time_to_maturity);
+
+    let contract = Contract { params, option_type, dates: &dates };
+    let rule = contract.fit_exercise_rule(settings);
+
+    let paths_per_sample = if settings.antithetic { 2 } else { 1 };
+    let samples = (settings.paths / paths_per_sample).max(2);
+    // Pricing paths draw from streams after those of the regression pass
+    let first_stream = settings.regression_paths as u64;
+
+    let chunks: Vec<(f64, f64)> = (0..samples.div_ceil(CHUNK_SIZE))
+        .into_par_iter()
+        .map(|chunk| {
+            let mut normals = vec![0.0; dates.len()];
+            let mut path = vec![0.0; dates.len()];
+            let mut basis = vec![0.0; settings.degree + 1];
+            let (mut sum, mut sum_squares) = (0.0, 0.0);
+            for sample in chunk * CHUNK_SIZE..((chunk + 1) * CHUNK_SIZE).min(samples) {
+                let mut rng = RngStream::new(settings.seed, first_stream + sample as u64);
+                normals.iter_mut().for_each(|z| *z = rng.next_normal());
+                contract.simulate(&normals, 1.0, &mut path);
+                let mut value = contract.exercise_value(&rule, &path, settings.basis, &mut basis);
+                if settings.antithetic {
+                    contract.simulate(&normals, -1.0, &mut path);
+                    value = 0.5 * (value + contract.exercise_value(&rule, &path, settings.basis, &mut basis));
+                }
+                sum += value;
+                sum_squares += value * value;
+            }
+            (sum, sum_squares)
+        })
+        .collect();
+    let (sum, sum_squares) = chunks
+        .iter()
+        .fold((0.0, 0.0), |(s, ss), &(c, cc)| (s + c, ss + cc));
+
+    PriceEstimate::from_sums(sum, sum_squares, samples)
+}
+
+/// Price by least-squares Monte Carlo after validating the inputs and schedule
+pub fn try_price(
+    params: &BlackScholesParams,
+    option_type: OptionType,
+    exercise_times: &[f64],
+    settings: &LsmSettings,
+) -> Result<LsmResult, PricingError> {
+    params.validate()?;
+    let mut previous = 0.0;
+    for &t in exercise_times {
+        if !t.is_finite() {
+            return Err(PricingError::NonFinite("exercise_times"));
+        }
+        if t <= previous || t > params.time_to_maturity {
+            return Err(PricingError::InvalidExerciseSchedule(t));
+        }
+        previous = t;
+    }
+    Ok(price(params, option_type, exercise_times, settings))
+}
+
+/// Option and exercise dates, the last being maturity
+struct Contract<'a> {
+    params: &'a BlackScholesParams,
+    option_type: OptionType,
+    dates: &'a [f64],
+}
+
+impl Contract<'_> {
+    fn intrinsic(&self, spot: f64) -> f64 {
+        match self.option_type {
+            OptionType::Call => (spot - self.params.strike).max(0.0),
+            OptionType::Put => (self.params.strike - spot).max(0.0),
+        }
+    }
+
+    /// Exact GBM spots on the exercise dates driven by `sign * normals`
+    fn simulate(&self, normals: &[f64], sign: f64, path: &mut [f64]) {
+        let p = self.params;
+        let drift = p.risk_free_rate - p.dividend_yield - 0.5 * p.volatility * p.volatility;
+        let mut spot = p.spot;
+        let mut time = 0.0;
+        for ((value, &date), &z) in path.iter_mut().zip(self.dates).zip(normals) {
+            let dt = date - time;
+            spot *= (drift * dt + p.volatility * dt.sqrt() * sign * z).exp();
+            *value = spot;
+            time = date;
+        }
+    }
+
+    /// Regression coefficients of the continuation value at each date before
+    /// maturity; `None` where too few paths are in the money to fit
+    fn fit_exercise_rule(&self, settings: &LsmSettings) -> Vec<Option<Vec<f64>>> {
+        let dates = self.dates.len();
+        let paths = settings.regression_paths.max(1);
+        let mut spots = vec![0.0; paths * dates];
+        spots.par_chunks_mut(dates).enumerate().for_each(|(i, path)| {
+            let mut rng = RngStream::new(settings.seed, i as u64);
+            let normals: Vec<f64> = (0..dates).map(|_| rng.next_normal()).collect();
+            self.simulate(&normals, 1.0, path);
+        });
+
+        let size = settings.degree + 1;
+        let mut basis = vec![0.0; size];
+        let mut cash: Vec<f64> = spots.chunks(dates).map(|path| self.intrinsic(path[dates - 1])).collect();
+        let mut rule = vec![None; dates];
+        for j in (0..dates - 1).rev() {
+            let discount = (-self.params.risk_free_rate * (self.dates[j + 1] - self.dates[j])).exp();
+            cash.iter_mut().for_each(|c| *c *= discount);
+
+            let mut normal_matrix = vec![0.0; size * size];
+            let mut moment = vec![0.0; size];
+            let mut in_the_money = 0;
+            for (path, &value) in spots.chunks(dates).zip(&cash) {
+                if self.intrinsic(path[j]) <= 0.0 {
+                    continue;
+                }
+                in_the_money += 1;
+                settings.basis.evaluate(path[j] / self.params.strike, &mut basis);
+                for a in 0..size {
+                    moment[a] += basis[a] * value;
+                    for b in 0..size {
+                        normal_matrix[a * size + b] += basis[a] * basis[b];
+                    }
+                }
+            }
+            if in_the_money <= size {
+                continue;
+            }
+            let Some(coefficients) = solve_linear_system(normal_matrix, moment) else {
+                continue;
+            };
+
+            for (path, value) in spots.chunks(dates).zip(cash.iter_mut()) {
+                let exercise = self.intrinsic(path[j]);
+                if exercise > 0.0 && exercise > continuation(&coefficients, settings.basis, path[j] / self.params.strike, &mut basis) {
+                    *value = exercise;
+                }
+            }
+            rule[j] = Some(coefficients);
+        }
+        rule
+    }
+
+    /// Discounted cash flow of one path under the fitted exercise rule
+    fn exercise_value(
+        &self,
+        rule: &[Option<Vec<f64>>],
+        path: &[f64],
+        basis: RegressionBasis,
+        scratch: &mut [f64],
+    ) -> f64 {
+        let last = self.dates.len() - 1;
+        for (j, &spot) in path.iter().enumerate() {
+            let exercise = self.intrinsic(spot);
+            if exercise <= 0.0 {
+                continue;
+            }
+            let exercised = j == last
+                || rule[j].as_ref().is_some_and(|coefficients| {
+                    exercise > continuation(coefficients, basis, spot / self.params.strike, scratch)
+                });
+            if exercised {
+                return exercise * (-self.params.risk_free_rate * self.dates[j]).exp();
+            }
+        }
+        0.0
+    }
+}
+
+fn continuation(coefficients: &[f64], basis: RegressionBasis, moneyness: f64, scratch: &mut [f64]) -> f64 {
+    basis.evaluate(moneyness, scratch);
+    coefficients.iter().zip(scratch.iter()).map(|(c, b)| c * b).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::binomial::{self, BinomialMethod};
+    use crate::pricing::black_scholes;
+    use crate::types::ExerciseStyle;
+    use approx::assert_relative_eq;
+
+    fn settings() -> LsmSettings {
+        LsmSettings { paths: 40_000, regression_paths: 20_000, ..LsmSettings::default() }
+    }
+
+    #[test]
+    fn test_american_put_against_binomial() {
+        // Longstaff-Schwartz (2001) table 1 cases, exercisable 50 times a year
+        for &(spot, volatility, maturity) in &[(36.0, 0.2, 1.0), (40.0, 0.2, 1.0), (44.0, 0.4, 2.0)] {
+            let params = BlackScholesParams::new(spot, 40.0, maturity, volatility, 0.06, 0.0);
+            let schedule = uniform_schedule(maturity, (50.0 * maturity) as usize);
+            let american = binomial::price(&params, OptionType::Put, ExerciseStyle::American, BinomialMethod::LeisenReimer, 1001);
+            for &basis in &[RegressionBasis::Laguerre, RegressionBasis::Polynomial] {
+                let result = price(&params, OptionType::Put, &schedule, &LsmSettings { basis, ..settings() });
+                // Low biased: discrete exercise and a suboptimal rule
+                assert!(result.price < american + 3.0 * result.standard_error);
+                assert!(american - result.price < 0.04 + 3.0 * result.standard_error);
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_schedule_is_european() {
+        let params = BlackScholesParams::new(100.0, 110.0, 0.5, 0.3, 0.05, 0.02);
+        for &option_type in &[OptionType::Call, OptionType::Put] {
+            let result = price(&params, option_type, &[], &settings());
+            let european = black_scholes::calculate_greeks(&params, option_type).price;
+            assert!((result.price - european).abs() < 4.0 * result.standard_error);
+        }
+    }
+
+    #[test]
+    fn test_call_without_dividends_is_never_exercised() {
+        let params = BlackScholesParams::new(100.0, 100.0, 1.0, 0.25, 0.05, 0.0);
+        let bermudan = price(&params, OptionType::Call, &uniform_schedule(1.0, 12), &settings());
+        let european = price(&params, OptionType::Call, &[], &settings());
+        assert_relative_eq!(bermudan.price, european.price, max_relative = 1e-3);
+    }
+
+    #[test]
+    fn test_confidence_interval() {
+        let params = BlackScholesParams::new(40.0, 40.0, 1.0, 0.2, 0.06, 0.0);
+        let result = price(&params, OptionType::Put, &uniform_schedule(1.0, 10), &settings());
+        assert_eq!(result, price(&params, OptionType::Put, &uniform_schedule(1.0, 10), &settings()));
+
+        let (lower, upper) = result.confidence_interval(0.95);
+        assert_relative_eq!(upper - lower, 2.0 * 1.959964 * result.standard_error, max_relative = 1e-6);
+        let (wide_lower, wide_upper) = result.confidence_interval(0.99);
+        assert!(wide_lower < lower && upper < wide_upper);
+    }
+
+    #[test]
+    fn test_schedule_validation() {
+        let params = BlackScholesParams::new(40.0, 40.0, 1.0, 0.2, 0.06, 0.0);
+        let s = settings();
+        assert_eq!(
+            try_price(&params, OptionType::Put, &[0.5, 0.25], &s),
+            Err(PricingError::InvalidExerciseSchedule(0.25))
+        );
+        assert_eq!(
+            try_price(&params, OptionType::Put, &[0.5, 1.5], &s),
+            Err(PricingError::InvalidExerciseSchedule(1.5))
+        );
+        assert_eq!(
+            try_price(&params, OptionType::Put, &[0.0], &s),
+            Err(PricingError::InvalidExerciseSchedule(0.0))
+        );
+        assert_eq!(
+            try_price(&params, OptionType::Put, &[f64::NAN], &s),
+            Err(PricingError::NonFinite("exercise_times"))
+        );
+    }
+}
@@ -1,5 +1,43 @@
 //! Options pricing module
 
+pub mod american;
+pub mod asian;
+pub mod bachelier;
+pub mod barrier;
+pub mod binomial;
+pub mod black76;
 pub mod black_scholes;
+pub(crate) mod closed_form;
+pub mod digital;
+pub mod error;
+pub mod exotics;
+pub(crate) mod fourier;
+pub mod garman_kohlhagen;
+pub mod heston;
+pub mod heston_calibration;
+pub mod implied_vol;
+pub mod jump_diffusion;
+pub(crate) mod least_squares;
+pub mod lets_be_rational;
+pub mod lsm;
+pub mod monte_carlo;
+pub mod pde;
 
-pub use black_scholes::{BlackScholesParams, calculate_greeks};
+pub use american::AmericanApproximation;
+pub use asian::{AsianAverage, AsianMethod, Averaging};
+pub use bachelier::{BachelierParams, implied_normal_vol, lognormal_vol_from_normal, normal_vol_from_lognormal};
+pub use barrier::{Barrier, BarrierType, DoubleBarrier, DoubleBarrierType, Monitoring};
+pub use binomial::BinomialMethod;
+pub use black76::{Black76Params, calculate_futures_style_greeks};
+pub use black_scholes::{BlackScholesParams, calculate_greeks, calculate_higher_order_greeks, try_calculate_greeks};
+pub use digital::DigitalPayoff;
+pub use error::PricingError;
+pub use exotics::{Chooser, Compound, Lookback, LookbackType};
+pub use garman_kohlhagen::{strike_from_delta, DeltaConvention, GarmanKohlhagenParams};
+pub use heston::HestonParams;
+pub use heston_calibration::{CalibrationWeighting, FellerCondition, HestonCalibrationSettings};
+pub use implied_vol::{implied_vol, implied_vol_with_method, ImpliedVolMethod};
+pub use jump_diffusion::{JumpDiffusionGreeks, KouJumps, MertonJumps};
+pub use lsm::{LsmResult, LsmSettings, RegressionBasis};
+pub use monte_carlo::{EuropeanPayoff, MonteCarloSettings, PathPayoff, PriceEstimate};
+pub use pde::{PdeContract, PdeSettings};
@@ -0,0 +1,531 @@
+//! Monte Carlo pricing with pathwise AD Greeks
+//!
+//! Paths of geometric Brownian motion are simulated in parallel with rayon.
+//! Every path draws from its own `RngStream`, keyed by the seed and the path
+//! index, and partial sums are combined in a fixed order, so results are
+//! bit-for-bit reproducible whatever the number of threads.
+//!
+//! Each path is evaluated on a `MultiDual` with spot, volatility, maturity and
+//! both rates seeded, giving pathwise delta, vega, theta, rho and phi. Pathwise
+//! gamma does not exist for kinked payoffs, so gamma uses the mixed
+//! pathwise/likelihood-ratio estimator: the pathwise delta weighted by the
+//! score of the first Brownian increment.
+
+use crate::ad::{HyperDual, MultiDual, Scalar};
+use crate::pricing::black_scholes::{self, BlackScholesParams};
+use crate::pricing::lets_be_rational::inverse_norm_cdf;
+use crate::pricing::PricingError;
+use crate::types::{Greeks, OptionType};
+use rayon::prelude::*;
+
+/// Payoff of one simulated path
+pub trait PathPayoff: Sync {
+    /// Undiscounted payoff, given the spot at each equally spaced monitoring
+    /// time; the last entry is the spot at maturity
+    fn payoff<T: Scalar>(&self, path: &[T]) -> T;
+
+    /// Side of the European option on the same strike used as control variate
+    fn control_type(&self) -> OptionType;
+}
+
+/// European call or put paying off on the spot at maturity
+#[derive(Debug, Clone, Copy)]
+pub struct EuropeanPayoff {
+    pub strike: f64,
+    pub option_type: OptionType,
+}
+
+impl PathPayoff for EuropeanPayoff {
+    fn payoff<T: Scalar>(&self, path: &[T]) -> T {
+        let terminal = path[path.len() - 1];
+        let intrinsic = match self.option_type {
+            OptionType::Call => terminal - self.strike,
+            OptionType::Put => -(terminal - self.strike),
+        };
+        intrinsic.max(T::constant(0.0))
+    }
+
+    fn control_type(&self) -> OptionType {
+        self.option_type
+    }
+}
+
+/// Simulation settings
+#[derive(Debug, Clone, Copy)]
+pub struct MonteCarloSettings {
+    /// Number of paths, counting both members of an antithetic pair
+    pub paths: usize,
+    /// Monitoring times per path, equally spaced up to maturity
+    pub time_steps: usize,
+    pub seed: u64,
+    /// Pair each path with its mirror image -Z
+    pub antithetic: bool,
+    /// Use the Black-Scholes closed form of a European option on the same
+    /// strike as a control variate, with the coefficient estimated from the paths
+    pub control_variate: bool,
+}
+
+impl Default for MonteCarloSettings {
+    fn default() -> Self {
+        Self {
+            paths: 100_000,
+            time_steps: 1,
+            seed: 42,
+            antithetic: true,
+            control_variate: true,
+        }
+    }
+}
+
+/// Monte Carlo estimates with their standard errors
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MonteCarloGreeks {
+    pub greeks: Greeks,
+    /// Standard error of each field of `greeks`
+    pub standard_error: Greeks,
+    /// Independent samples averaged; antithetic pairs count once
+    pub samples: usize,
+}
+
+/// Price estimate from plain simulation, without Greeks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceEstimate {
+    pub price: f64,
+    pub standard_error: f64,
+    /// Independent samples averaged; antithetic pairs count once
+    pub samples: usize,
+}
+
+impl PriceEstimate {
+    /// Mean and standard error from the sum and sum of squares of the samples
+    pub(crate) fn from_sums(sum: f64, sum_squares: f64, samples: usize) -> Self {
+        let n = samples as f64;
+        let mean = sum / n;
+        let variance = (sum_squares / n - mean * mean).max(0.0);
+        Self {
+            price: mean,
+            standard_error: (variance / (n - 1.0)).sqrt(),
+            samples,
+        }
+    }
+
+    /// Two-sided confidence interval at the given level, e.g. 0.95
+    pub fn confidence_interval(&self, level: f64) -> (f64, f64) {
+        let half_width = inverse_norm_cdf(0.5 + 0.5 * level) * self.standard_error;
+        (self.price - half_width, self.price + half_width)
+    }
+}
+
+/// Reproducible stream of uniform and normal variates (xoshiro256++)
+///
+/// Streams for different `(seed, stream)` pairs are seeded through SplitMix64,
+/// which gives statistically independent sequences for practical purposes.
+#[derive(Debug, Clone)]
+pub struct RngStream {
+    state: [u64; 4],
+}
+
+impl RngStream {
+    pub fn new(seed: u64, stream: u64) -> Self {
+        let mut key = seed ^ splitmix64(&mut stream.wrapping_add(0x5851_f42d_4c95_7f2d));
+        let state = [
+            splitmix64(&mut key),
+            splitmix64(&mut key),
+            splitmix64(&mut key),
+            splitmix64(&mut key),
+        ];
+        Self { state }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let [s0, s1, s2, s3] = self.state;
+        let result = s0.wrapping_add(s3).rotate_left(23).wrapping_add(s0);
+        let t = s1 << 17;
+        let mut state = [s0, s1, s2 ^ s0, s3 ^ s1];
+        state[1] ^= state[2];
+        state[0] ^= state[3];
+        state[2] ^= t;
+        state[3] = state[3].rotate_left(45);
+        self.state = state;
+        result
+    }
+
+    /// Uniform variate in the open interval (0, 1)
+    pub fn next_uniform(&mut self) -> f64 {
+        ((self.next_u64() >> 11) as f64 + 0.5) * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Standard normal variate by inversion
+    pub fn next_normal(&mut self) -> f64 {
+        inverse_norm_cdf(self.next_uniform())
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Gradient slots used when seeding inputs for each path
+const SPOT: usize = 0;
+const VOL: usize = 1;
+const TIME: usize = 2;
+const RATE: usize = 3;
+const DIVIDEND: usize = 4;
+
+/// Estimates per sample, in the field order of `Greeks`
+const OUTPUTS: usize = 7;
+
+/// Samples simulated per parallel task
+const CHUNK_SIZE: usize = 1024;
+
+/// Price a path-dependent payoff under Black-Scholes dynamics and estimate its Greeks
+///
+/// The simulation uses `params.spot`, volatility, maturity and rates;
+/// `params.strike` sets the control variate. With zero volatility the only
+/// path is the forward, which is priced once with no standard error.
+pub fn calculate_greeks<P: PathPayoff>(
+    params: &BlackScholesParams,
+    payoff: &P,
+    settings: &MonteCarloSettings,
+) -> MonteCarloGreeks {
+    if params.time_to_maturity <= 0.0 {
+        let value = payoff.payoff(&[MultiDual::<5>::variable(params.spot, SPOT)]);
+        return MonteCarloGreeks {
+            greeks: Greeks { price: value.value, delta: value.partial(SPOT), ..Greeks::default() },
+            ..MonteCarloGreeks::default()
+        };
+    }
+    if params.volatility == 0.0 {
+        return MonteCarloGreeks {
+            greeks: deterministic(params, payoff, settings.time_steps.max(1)),
+            ..MonteCarloGreeks::default()
+        };
+    }
+
+    let paths_per_sample = if settings.antithetic { 2 } else { 1 };
+    let samples = (settings.paths / paths_per_sample).max(2);
+    let control = EuropeanPayoff { strike: params.strike, option_type: payoff.control_type() };
+
+    // Partial sums per chunk, combined in order so the result does not depend on scheduling
+    let chunks: Vec<Moments> = (0..samples.div_ceil(CHUNK_SIZE))
+        .into_par_iter()
+        .map(|chunk| {
+            let mut moments = Moments::default();
+            let mut normals = vec![0.0; settings.time_steps.max(1)];
+            for sample in chunk * CHUNK_SIZE..((chunk + 1) * CHUNK_SIZE).min(samples) {
+                let mut rng = RngStream::new(settings.seed, sample as u64);
+                normals.iter_mut().for_each(|z| *z = rng.next_normal());
+                let (mut target, mut reference) = simulate(params, payoff, &control, &normals);
+                if settings.antithetic {
+                    normals.iter_mut().for_each(|z| *z = -*z);
+                    let (target_mirror, reference_mirror) = simulate(params, payoff, &control, &normals);
+                    for j in 0..OUTPUTS {
+                        target[j] = 0.5 * (target[j] + target_mirror[j]);
+                        reference[j] = 0.5 * (reference[j] + reference_mirror[j]);
+                    }
+                }
+                moments.add(&target, &reference);
+            }
+            moments
+        })
+        .collect();
+    let mut moments = Moments::default();
+    for chunk in &chunks {
+        moments.merge(chunk);
+    }
+
+    let exact = greeks_array(&black_scholes::calculate_greeks(params, control.option_type));
+    let n = samples as f64;
+    let mut estimate = [0.0; OUTPUTS];
+    let mut standard_error = [0.0; OUTPUTS];
+    for j in 0..OUTPUTS {
+        let mean_x = moments.x[j] / n;
+        let mean_y = moments.y[j] / n;
+        let var_x = (moments.xx[j] / n - mean_x * mean_x).max(0.0);
+        let var_y = (moments.yy[j] / n - mean_y * mean_y).max(0.0);
+        let cov = moments.xy[j] / n - mean_x * mean_y;
+
+        let (value, variance) = if settings.control_variate && var_y > 0.0 {
+            let beta = cov / var_y;
+            (mean_x - beta * (mean_y - exact[j]), (var_x - beta * cov).max(0.0))
+        } else {
+            (mean_x, var_x)
+        };
+        estimate[j] = value;
+        standard_error[j] = (variance / (n - 1.0)).sqrt();
+    }
+
+    MonteCarloGreeks {
+        greeks: greeks_from_array(estimate),
+        standard_error: greeks_from_array(standard_error),
+        samples,
+    }
+}
+
+/// Price and Greeks by simulation after validating the inputs
+pub fn try_calculate_greeks<P: PathPayoff>(
+    params: &BlackScholesParams,
+    payoff: &P,
+    settings: &MonteCarloSettings,
+) -> Result<MonteCarloGreeks, PricingError> {
+    params.validate()?;
+    Ok(calculate_greeks(params, payoff, settings))
+}
+
+/// One GBM path on AD numbers: estimates for the payoff and for the control
+fn simulate<P: PathPayoff>(
+    params: &BlackScholesParams,
+    payoff: &P,
+    control: &EuropeanPayoff,
+    normals: &[f64],
+) -> ([f64; OUTPUTS], [f64; OUTPUTS]) {
+    let (path, discount) = seeded_path(params, normals);
+
+    // Score of the first increment with respect to the initial spot, times S0
+    let first_step_vol = params.volatility * (params.time_to_maturity / normals.len() as f64).sqrt();
+    let gamma_weight = (normals[0] / first_step_vol - 1.0) / params.spot;
+
+    let estimates = |value: MultiDual<5>| {
+        let delta = value.partial(SPOT);
+        [
+            value.value,
+            delta,
+            delta * gamma_weight,
+            value.partial(VOL),
+            -value.partial(TIME),
+            value.partial(RATE),
+            value.partial(DIVIDEND),
+        ]
+    };
+    (
+        estimates(discount * payoff.payoff(&path)),
+        estimates(discount * control.payoff(&path)),
+    )
+}
+
+/// Path and discount factor on a `MultiDual` with every market input seeded
+fn seeded_path(params: &BlackScholesParams, normals: &[f64]) -> (Vec<MultiDual<5>>, MultiDual<5>) {
+    gbm_path(
+        MultiDual::variable(params.spot, SPOT),
+        MultiDual::variable(params.volatility, VOL),
+        MultiDual::variable(params.time_to_maturity, TIME),
+        MultiDual::variable(params.risk_free_rate, RATE),
+        MultiDual::variable(params.dividend_yield, DIVIDEND),
+        normals,
+    )
+}
+
+/// Spot at each monitoring time driven by `normals`, and the discount factor to maturity
+fn gbm_path<T: Scalar>(s0: T, sigma: T, t: T, r: T, q: T, normals: &[f64]) -> (Vec<T>, T) {
+    let dt = t / normals.len() as f64;
+    let drift = (r - q - sigma.powi2() * 0.5) * dt;
+    let diffusion = sigma * dt.sqrt();
+    let mut spot = s0;
+    let path = normals
+        .iter()
+        .map(|&z| {
+            spot = spot * (drift + diffusion * z).exp();
+            spot
+        })
+        .collect();
+    (path, (-r * t).exp())
+}
+
+/// Greeks along the forward, the only path without volatility
+///
+/// The likelihood-ratio weight of the first increment is undefined here, but
+/// the path is smooth in spot, so gamma is its exact second derivative.
+fn deterministic<P: PathPayoff>(params: &BlackScholesParams, payoff: &P, steps: usize) -> Greeks {
+    let normals = vec![0.0; steps];
+    let (path, discount) = seeded_path(params, &normals);
+    let value = discount * payoff.payoff(&path);
+
+    let c = |x: f64| HyperDual::constant(x);
+    let s0 = HyperDual::variable(params.spot);
+    let (path, discount) = gbm_path(
+        s0,
+        c(params.volatility),
+        c(params.time_to_maturity),
+        c(params.risk_free_rate),
+        c(params.dividend_yield),
+        &normals,
+    );
+    let gamma = (discount * payoff.payoff(&path)).second_deriv();
+
+    Greeks::new(
+        value.value,
+        value.partial(SPOT),
+        gamma,
+        value.partial(VOL),
+        -value.partial(TIME),
+        value.partial(RATE),
+        value.partial(DIVIDEND),
+    )
+}
+
+/// Running sums for the mean, variance and control covariance of each output
+#[derive(Debug, Clone, Default)]
+struct Moments {
+    x: [f64; OUTPUTS],
+    y: [f64; OUTPUTS],
+    xx: [f64; OUTPUTS],
+    yy: [f64; OUTPUTS],
+    xy: [f64; OUTPUTS],
+}
+
+impl Moments {
+    fn add(&mut self, x: &[f64; OUTPUTS], y: &[f64; OUTPUTS]) {
+        for j in 0..OUTPUTS {
+            self.x[j] += x[j];
+            self.y[j] += y[j];
+            self.xx[j] += x[j] * x[j];
+            self.yy[j] += y[j] * y[j];
+            self.xy[j] += x[j] * y[j];
+        }
+    }
+
+    fn merge(&mut self, other: &Moments) {
+        for j in 0..OUTPUTS {
+            self.x[j] += other.x[j];
+            self.y[j] += other.y[j];
+            self.xx[j] += other.xx[j];
+            self.yy[j] += other.yy[j];
+            self.xy[j] += other.xy[j];
+        }
+    }
+}
+
+fn greeks_array(greeks: &Greeks) -> [f64; OUTPUTS] {
+    [greeks.price, greeks.delta, greeks.gamma, greeks.vega, greeks.theta, greeks.rho, greeks.phi]
+}
+
+fn greeks_from_array(values: [f64; OUTPUTS]) -> Greeks {
+    let [price, delta, gamma, vega, theta, rho, phi] = values;
+    Greeks::new(price, delta, gamma, vega, theta, rho, phi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ad::norm_cdf;
+    use approx::assert_relative_eq;
+
+    /// Every estimate lies within four standard errors of the exact value
+    fn assert_within_error(estimate: &MonteCarloGreeks, exact: &Greeks) {
+        let fields = greeks_array(&estimate.greeks)
+            .into_iter()
+            .zip(greeks_array(&estimate.standard_error))
+            .zip(greeks_array(exact));
+        for (j, ((value, error), expected)) in fields.enumerate() {
+            assert!(
+                (value - expected).abs() <= 4.0 * error + 1e-12,
+                "output {}: {} ± {} vs {}",
+                j,
+                value,
+                error,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_european_within_standard_error() {
+        let params = BlackScholesParams::new(100.0, 105.0, 0.75, 0.25, 0.04, 0.02);
+        let settings = MonteCarloSettings { paths: 200_000, control_variate: false, ..MonteCarloSettings::default() };
+        for &option_type in &[OptionType::Call, OptionType::Put] {
+            let payoff = EuropeanPayoff { strike: 105.0, option_type };
+            let estimate = calculate_greeks(&params, &payoff, &settings);
+            let exact = black_scholes::calculate_greeks(&params, option_type);
+            assert_within_error(&estimate, &exact);
+            assert!(estimate.standard_error.price < 0.02 * exact.price);
+        }
+    }
+
+    #[test]
+    fn test_control_variate_on_its_own_payoff_is_exact() {
+        let params = BlackScholesParams::new(100.0, 95.0, 1.0, 0.3, 0.05, 0.0);
+        let payoff = EuropeanPayoff { strike: 95.0, option_type: OptionType::Put };
+        let estimate = calculate_greeks(&params, &payoff, &MonteCarloSettings { paths: 1000, ..Default::default() });
+        let exact = black_scholes::calculate_greeks(&params, OptionType::Put);
+        assert_relative_eq!(estimate.greeks.price, exact.price, epsilon = 1e-10);
+        assert_relative_eq!(estimate.greeks.vega, exact.vega, epsilon = 1e-9);
+        assert!(estimate.standard_error.price < 1e-10);
+    }
+
+    #[test]
+    fn test_discrete_geometric_average() {
+        // ln G is normal: mean ln S + (r - q - σ²/2) T (n+1)/2n, variance σ² T (n+1)(2n+1)/6n²
+        struct GeometricAverageCall(f64);
+        impl PathPayoff for GeometricAverageCall {
+            fn payoff<T: Scalar>(&self, path: &[T]) -> T {
+                let mean_log = path.iter().fold(T::constant(0.0), |acc, &s| acc + s.ln()) / path.len() as f64;
+                (mean_log.exp() - self.0).max(T::constant(0.0))
+            }
+            fn control_type(&self) -> OptionType {
+                OptionType::Call
+            }
+        }
+
+        let params = BlackScholesParams::new(100.0, 100.0, 1.0, 0.2, 0.05, 0.01);
+        let n = 12.0;
+        let mean = 100f64.ln() + (0.05 - 0.01 - 0.02) * (n + 1.0) / (2.0 * n);
+        let variance = 0.04 * (n + 1.0) * (2.0 * n + 1.0) / (6.0 * n * n);
+        let d2 = (mean - 100f64.ln()) / variance.sqrt();
+        let d1 = d2 + variance.sqrt();
+        let exact = (-0.05f64).exp()
+            * ((mean + 0.5 * variance).exp() * norm_cdf(d1) - 100.0 * norm_cdf(d2));
+
+        let settings = MonteCarloSettings { paths: 100_000, time_steps: 12, ..MonteCarloSettings::default() };
+        let estimate = calculate_greeks(&params, &GeometricAverageCall(100.0), &settings);
+        assert!((estimate.greeks.price - exact).abs() < 4.0 * estimate.standard_error.price);
+
+        // The vanilla control removes much of the variance
+        let plain = calculate_greeks(&params, &GeometricAverageCall(100.0), &MonteCarloSettings {
+            control_variate: false,
+            ..settings
+        });
+        assert!(estimate.standard_error.price < 0.75 * plain.standard_error.price);
+    }
+
+    #[test]
+    fn test_zero_volatility_is_deterministic() {
+        let params = BlackScholesParams::new(100.0, 90.0, 1.0, 0.0, 0.05, 0.02);
+        for &option_type in &[OptionType::Call, OptionType::Put] {
+            let payoff = EuropeanPayoff { strike: 90.0, option_type };
+            let estimate = calculate_greeks(&params, &payoff, &MonteCarloSettings::default());
+            let exact = black_scholes::calculate_greeks(&params, option_type);
+            assert_eq!(estimate.standard_error.price, 0.0);
+            assert_relative_eq!(estimate.greeks.price, exact.price, epsilon = 1e-12);
+            assert_relative_eq!(estimate.greeks.delta, exact.delta, epsilon = 1e-12);
+            assert_relative_eq!(estimate.greeks.gamma, exact.gamma, epsilon = 1e-12);
+            assert_relative_eq!(estimate.greeks.vega, exact.vega, epsilon = 1e-12);
+            assert_relative_eq!(estimate.greeks.theta, exact.theta, epsilon = 1e-12);
+            assert_relative_eq!(estimate.greeks.rho, exact.rho, epsilon = 1e-12);
+            assert_relative_eq!(estimate.greeks.phi, exact.phi, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_reproducible_streams() {
+        let params = BlackScholesParams::new(100.0, 100.0, 0.5, 0.2, 0.03, 0.0);
+        let payoff = EuropeanPayoff { strike: 110.0, option_type: OptionType::Call };
+        let settings = MonteCarloSettings { paths: 20_000, time_steps: 4, control_variate: false, ..Default::default() };
+
+        let first = calculate_greeks(&params, &payoff, &settings);
+        let second = calculate_greeks(&params, &payoff, &settings);
+        assert_eq!(first.greeks.price, second.greeks.price);
+        assert_eq!(first.greeks.delta, second.greeks.delta);
+        assert_eq!(first.samples, 10_000);
+
+        let other = calculate_greeks(&params, &payoff, &MonteCarloSettings { seed: 7, ..settings });
+        assert_ne!(first.greeks.price, other.greeks.price);
+
+        let mut rng = RngStream::new(1, 0);
+        let mean = (0..100_000).map(|_| rng.next_uniform()).sum::<f64>() / 100_000.0;
+        assert!((mean - 0.5).abs() < 0.005);
+    }
+}
@@ -7,6 +7,16 @@ pub enum OptionType {
     Put,
 }
 
+/// When an option may be exercised
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExerciseStyle {
+    /// Only at maturity
+    #[default]
+    European,
+    /// At any time up to maturity
+    American,
+}
+
 /// Market data for a single option
 #[derive(Debug, Clone, Copy)]
 pub struct OptionData {
@@ -31,10 +41,12 @@ pub struct Greeks {
     pub theta: f64,
     /// Rho: ∂V/∂r (sensitivity to interest rate)
     pub rho: f64,
+    /// Phi: ∂V/∂q (sensitivity to dividend yield)
+    pub phi: f64,
 }
 
 impl Greeks {
-    pub fn new(price: f64, delta: f64, gamma: f64, vega: f64, theta: f64, rho: f64) -> Self {
+    pub fn new(price: f64, delta: f64, gamma: f64, vega: f64, theta: f64, rho: f64, phi: f64) -> Self {
         Self {
             price,
             delta,
@@ -42,6 +54,31 @@ impl Greeks {
             vega,
             theta,
             rho,
+            phi,
         }
     }
 }
+
+/// Second- and third-order Greeks for an option
+///
+/// Time sensitivities (charm, veta, color) are taken with respect to calendar
+/// time, i.e. as maturity shrinks, matching the sign convention of `Greeks::theta`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HigherOrderGreeks {
+    /// Vanna: ∂²V/∂S∂σ (sensitivity of delta to volatility)
+    pub vanna: f64,
+    /// Volga (vomma): ∂²V/∂σ² (sensitivity of vega to volatility)
+    pub volga: f64,
+    /// Charm: ∂Δ/∂t (delta decay)
+    pub charm: f64,
+    /// Veta: ∂ν/∂t (vega decay)
+    pub veta: f64,
+    /// Speed: ∂³V/∂S³ (rate of change of gamma with spot)
+    pub speed: f64,
+    /// Zomma: ∂³V/∂S²∂σ (sensitivity of gamma to volatility)
+    pub zomma: f64,
+    /// Color: ∂Γ/∂t (gamma decay)
+    pub color: f64,
+    /// Ultima: ∂³V/∂σ³ (sensitivity of volga to volatility)
+    pub ultima: f64,
+}
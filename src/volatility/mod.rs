@@ -1,7 +1,9 @@
 //! Volatility surface module
 
+pub mod sabr;
 pub mod svi;
 pub mod surface;
 
+pub use sabr::{SABRParams, SABRSlice, SabrExpansion};
 pub use svi::{SVIParams, SVIJWParams};
-pub use surface::VolatilitySurface;
+pub use surface::{SmileSlice, VolatilitySurface};
@@ -0,0 +1,455 @@
+//! SABR stochastic volatility smile
+//!
+//! dF = α F^β dW₁, dα = ν α dW₂, d⟨W₁, W₂⟩ = ρ dt
+//!
+//! Implied volatilities come from Hagan et al. (2002) asymptotic expansions,
+//! lognormal and normal, or from Obłój's (2008) correction to the lognormal
+//! leading term, which stays accurate further into the wings. Every formula is
+//! written in y = ln(F/K) and generic over `Scalar`, so strike derivatives for
+//! the surface and parameter Jacobians for calibration are exact.
+
+use crate::ad::ops::relative_expm1;
+use crate::ad::{HyperDual, MultiDual, Scalar};
+use crate::pricing::least_squares::levenberg_marquardt;
+use crate::pricing::PricingError;
+use crate::volatility::surface::SmileSlice;
+
+/// Lognormal implied volatility expansion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SabrExpansion {
+    /// Hagan, Kumar, Lesniewski and Woodward (2002)
+    Hagan,
+    /// Hagan's expansion with Obłój's (2008) leading term
+    #[default]
+    Obloj,
+}
+
+/// SABR parameters for a single maturity
+#[derive(Debug, Clone, Copy)]
+pub struct SABRParams {
+    /// alpha: initial volatility level
+    pub alpha: f64,
+    /// beta: CEV exponent, 0 (normal) to 1 (lognormal)
+    pub beta: f64,
+    /// rho: correlation between forward and volatility (controls skew)
+    pub rho: f64,
+    /// nu: volatility of volatility (controls curvature)
+    pub nu: f64,
+}
+
+impl SABRParams {
+    pub fn new(alpha: f64, beta: f64, rho: f64, nu: f64) -> Self {
+        Self { alpha, beta, rho, nu }
+    }
+
+    /// Check every parameter is finite and in range
+    pub fn validate(&self) -> Result<(), PricingError> {
+        let fields = [("alpha", self.alpha), ("beta", self.beta), ("rho", self.rho), ("nu", self.nu)];
+        if let Some(&(name, _)) = fields.iter().find(|(_, v)| !v.is_finite()) {
+            return Err(PricingError::NonFinite(name));
+        }
+
+        let invalid = |name, value| Err(PricingError::InvalidParameter { name, value });
+        if self.alpha <= 0.0 {
+            return invalid("alpha", self.alpha);
+        }
+        if !(0.0..=1.0).contains(&self.beta) {
+            return invalid("beta", self.beta);
+        }
+        if self.rho.abs() >= 1.0 {
+            return invalid("rho", self.rho);
+        }
+        if self.nu < 0.0 {
+            return invalid("nu", self.nu);
+        }
+
+        Ok(())
+    }
+
+    /// Lognormal (Black) implied volatility
+    pub fn implied_volatility(&self, forward: f64, strike: f64, time_to_maturity: f64, expansion: SabrExpansion) -> f64 {
+        Sabr::constant(self).lognormal(forward, (forward / strike).ln(), time_to_maturity, expansion)
+    }
+
+    /// Normal (Bachelier) implied volatility from Hagan's normal expansion
+    pub fn normal_volatility(&self, forward: f64, strike: f64, time_to_maturity: f64) -> f64 {
+        Sabr::constant(self).normal(forward, (forward / strike).ln(), time_to_maturity)
+    }
+}
+
+/// SABR smile at one maturity, as held by a `VolatilitySurface`
+///
+/// The surface's log-moneyness k is read as ln(K/F) against the slice's
+/// forward, so quote the surface with the forward in place of spot.
+#[derive(Debug, Clone, Copy)]
+pub struct SABRSlice {
+    pub params: SABRParams,
+    pub forward: f64,
+    pub expansion: SabrExpansion,
+}
+
+impl SABRSlice {
+    pub fn new(params: SABRParams, forward: f64) -> Self {
+        Self {
+            params,
+            forward,
+            expansion: SabrExpansion::default(),
+        }
+    }
+
+    fn total_variance<T: Scalar>(&self, log_moneyness: T, time_to_maturity: f64) -> T {
+        let vol = Sabr::constant(&self.params).lognormal(self.forward, -log_moneyness, time_to_maturity, self.expansion);
+        vol * vol * time_to_maturity
+    }
+}
+
+impl SmileSlice for SABRSlice {
+    fn implied_variance(&self, log_moneyness: f64, time_to_maturity: f64) -> f64 {
+        self.total_variance(log_moneyness, time_to_maturity)
+    }
+
+    fn implied_variance_derivatives(&self, log_moneyness: f64, time_to_maturity: f64) -> (f64, f64) {
+        let w = self.total_variance(HyperDual::variable(log_moneyness), time_to_maturity);
+        (w.deriv1(), w.second_deriv())
+    }
+
+    /// Valid parameters and Durrleman's condition g(k) ≥ 0 across ±6 ATM
+    /// standard deviations; Hagan's expansion breaks it at low strikes for
+    /// long maturities or large ν
+    fn is_arbitrage_free(&self, time_to_maturity: f64) -> bool {
+        if self.params.validate().is_err() || !self.forward.is_finite() || self.forward <= 0.0 {
+            return false;
+        }
+        let step = 0.1 * self.implied_variance(0.0, time_to_maturity).sqrt();
+        (-60..=60).all(|i| {
+            let k = i as f64 * step;
+            let w = self.implied_variance(k, time_to_maturity);
+            let (slope, curvature) = self.implied_variance_derivatives(k, time_to_maturity);
+            let g = (1.0 - k * slope / (2.0 * w)).powi(2) - 0.25 * slope * slope * (1.0 / w + 0.25) + 0.5 * curvature;
+            w > 0.0 && g >= 0.0
+        })
+    }
+}
+
+/// Fit report
+#[derive(Debug, Clone)]
+pub struct SABRCalibration {
+    pub params: SABRParams,
+    /// Model minus market volatility of each quote, in input order
+    pub residuals: Vec<f64>,
+    /// Root mean square of `residuals`
+    pub rmse: f64,
+    pub iterations: usize,
+    /// Whether the fit stopped on the tolerance, rather than on the iteration
+    /// limit or stalled with no step left that lowers the cost
+    pub converged: bool,
+}
+
+/// Free parameters in the fit: (α, ρ, ν)
+const PARAMETERS: usize = 3;
+
+/// Fit α, ρ and ν to one maturity's lognormal volatilities with β held fixed
+///
+/// `quotes` holds (strike, implied volatility) pairs. As is market practice, β
+/// is chosen up front since it trades off against ρ in the fitted skew.
+pub fn calibrate(
+    forward: f64,
+    time_to_maturity: f64,
+    quotes: &[(f64, f64)],
+    beta: f64,
+    expansion: SabrExpansion,
+) -> Result<SABRCalibration, PricingError> {
+    if !forward.is_finite() {
+        return Err(PricingError::NonFinite("forward"));
+    }
+    if forward <= 0.0 {
+        return Err(PricingError::NonPositiveForward(forward));
+    }
+    if time_to_maturity < 0.0 {
+        return Err(PricingError::NegativeMaturity(time_to_maturity));
+    }
+    if !(0.0..=1.0).contains(&beta) {
+        return Err(PricingError::InvalidParameter { name: "beta", value: beta });
+    }
+    if quotes.len() < PARAMETERS {
+        return Err(PricingError::InsufficientQuotes { required: PARAMETERS, provided: quotes.len() });
+    }
+    for &(strike, vol) in quotes {
+        if !(strike.is_finite() && vol.is_finite()) {
+            return Err(PricingError::NonFinite("quotes"));
+        }
+        if strike <= 0.0 {
+            return Err(PricingError::NonPositiveStrike(strike));
+        }
+        if vol <= 0.0 {
+            return Err(PricingError::NonPositiveVolatility(vol));
+        }
+    }
+
+    // Start from the quote nearest the money, where σ ≈ α / F^(1-β)
+    let &(_, atm_vol) = quotes
+        .iter()
+        .min_by(|a, b| (a.0 / forward).ln().abs().total_cmp(&(b.0 / forward).ln().abs()))
+        .unwrap();
+    let start = [atm_vol * forward.powf(1.0 - beta), 0.0, 0.3];
+    let lower = [1e-8, -0.9999, 0.0];
+    let upper = [f64::INFINITY, 0.9999, 10.0];
+
+    let evaluate = |x: &[f64; PARAMETERS]| {
+        let model = Sabr {
+            alpha: MultiDual::variable(x[0], 0),
+            beta,
+            rho: MultiDual::variable(x[1], 1),
+            nu: MultiDual::variable(x[2], 2),
+        };
+        quotes
+            .iter()
+            .map(|&(strike, vol)| {
+                let y = MultiDual::constant((forward / strike).ln());
+                let model_vol = model.lognormal(forward, y, time_to_maturity, expansion);
+                (model_vol.value - vol, model_vol.grad())
+            })
+            .unzip()
+    };
+    let fit = levenberg_marquardt(start, lower, upper, |_| {}, evaluate, 200, 1e-14);
+
+    let params = SABRParams::new(fit.x[0], beta, fit.x[1], fit.x[2]);
+    let residuals: Vec<f64> = quotes
+        .iter()
+        .map(|&(strike, vol)| params.implied_volatility(forward, strike, time_to_maturity, expansion) - vol)
+        .collect();
+    let rmse = (residuals.iter().map(|r| r * r).sum::<f64>() / residuals.len() as f64).sqrt();
+    Ok(SABRCalibration {
+        params,
+        residuals,
+        rmse,
+        iterations: fit.iterations,
+        converged: fit.converged,
+    })
+}
+
+/// Parameters lifted into the number type being evaluated
+struct Sabr<T> {
+    alpha: T,
+    beta: f64,
+    rho: T,
+    nu: T,
+}
+
+impl<T: Scalar> Sabr<T> {
+    fn constant(params: &SABRParams) -> Self {
+        Self {
+            alpha: T::constant(params.alpha),
+            beta: params.beta,
+            rho: T::constant(params.rho),
+            nu: T::constant(params.nu),
+        }
+    }
+
+    /// (FK)^((1-β)/2) with K = F e^(-y)
+    fn geometric_level(&self, forward: f64, y: T) -> T {
+        let one_minus_beta = 1.0 - self.beta;
+        (y * (-0.5 * one_minus_beta)).exp() * forward.powf(one_minus_beta)
+    }
+
+    /// Time correction shared by both lognormal expansions
+    fn lognormal_correction(&self, level: T) -> T {
+        let one_minus_beta = 1.0 - self.beta;
+        let (alpha, rho, nu) = (self.alpha, self.rho, self.nu);
+        alpha * alpha / (level * level) * (one_minus_beta * one_minus_beta / 24.0)
+            + rho * nu * alpha / level * (0.25 * self.beta)
+            + (-(rho * rho) * 3.0 + 2.0) * nu * nu / 24.0
+    }
+
+    fn lognormal(&self, forward: f64, y: T, time_to_maturity: f64, expansion: SabrExpansion) -> T {
+        let one_minus_beta = 1.0 - self.beta;
+        let level = self.geometric_level(forward, y);
+        let correction = self.lognormal_correction(level) * time_to_maturity + 1.0;
+        match expansion {
+            SabrExpansion::Hagan => {
+                let z = self.nu / self.alpha * level * y;
+                let y2 = y * y * (one_minus_beta * one_minus_beta);
+                let denominator = level * (y2 / 24.0 + y2 * y2 / 1920.0 + 1.0);
+                self.alpha / denominator * z_over_x(z, self.rho) * correction
+            }
+            SabrExpansion::Obloj => {
+                // z = ν (F^(1-β) - K^(1-β)) / (α (1-β)) and σ = ν y / x(z)
+                let scale = relative_expm1(y * (-one_minus_beta)) * forward.powf(one_minus_beta);
+                let z = self.nu / self.alpha * scale * y;
+                self.alpha / scale * z_over_x(z, self.rho) * correction
+            }
+        }
+    }
+
+    fn normal(&self, forward: f64, y: T, time_to_maturity: f64) -> T {
+        let beta = self.beta;
+        let (alpha, rho, nu) = (self.alpha, self.rho, self.nu);
+        let level = self.geometric_level(forward, y);
+        // F - K = F y e₁(-y) and F^(1-β) - K^(1-β) = (1-β) F^(1-β) y e₁(-(1-β) y)
+        let difference = relative_expm1(-y);
+        let leading = alpha * difference / relative_expm1(y * (beta - 1.0)) * forward.powf(beta);
+        let zeta = nu / alpha * difference * y * forward / ((y * (-0.5 * beta)).exp() * forward.powf(beta));
+        let correction = -(alpha * alpha) / (level * level) * (beta * (2.0 - beta) / 24.0)
+            + rho * alpha * nu / level * (0.25 * beta)
+            + (-(rho * rho) * 3.0 + 2.0) * nu * nu / 24.0;
+        leading * z_over_x(zeta, rho) * (correction * time_to_maturity + 1.0)
+    }
+}
+
+/// z / x(z) with x(z) = ln((√(1 - 2ρz + z²) + z - ρ) / (1 - ρ))
+///
+/// Near z = 0 uses x(z) = Σ Pₙ(ρ) zⁿ⁺¹ / (n + 1), the Legendre generating function.
+fn z_over_x<T: Scalar>(z: T, rho: T) -> T {
+    if z.value().abs() < 1e-3 {
+        let rho2 = rho * rho;
+        let p2 = (rho2 * 3.0 - 1.0) * 0.5;
+        let p3 = (rho2 * 5.0 - 3.0) * rho * 0.5;
+        let p4 = ((rho2 * 35.0 - 30.0) * rho2 + 3.0) / 8.0;
+        let series = z * (rho * 0.5 + z * (p2 / 3.0 + z * (p3 * 0.25 + z * p4 * 0.2)));
+        return T::constant(1.0) / (series + 1.0);
+    }
+    let root = (z * z - rho * z * 2.0 + 1.0).sqrt();
+    // (√· + z - ρ)(√· - z + ρ) = 1 - ρ², which keeps z < 0 free of cancellation
+    let x = if z.value() > 0.0 {
+        ((root + z - rho) / (-rho + 1.0)).ln()
+    } else {
+        ((rho + 1.0) / (root - z + rho)).ln()
+    };
+    z / x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn rates_smile() -> SABRParams {
+        SABRParams::new(0.035, 0.5, -0.3, 0.45)
+    }
+
+    #[test]
+    fn test_expansions_match_reference_values() {
+        // Hagan lognormal, Obłój and Hagan normal, evaluated directly in F and K at 30 digits
+        let params = rates_smile();
+        let (forward, t) = (0.03, 2.0);
+        let cases = [
+            (0.015, 0.3242042230287119, 0.3246711966593952, 0.007023322048461045),
+            (0.025, 0.232814002441982, 0.2328208359299534, 0.006362191247721641),
+            (0.04, 0.1874745925635455, 0.1874832619333628, 0.006499173606221594),
+            (0.06, 0.2032563762978693, 0.2036138616104703, 0.008838597461454986),
+        ];
+        for &(strike, hagan, obloj, normal) in &cases {
+            assert_relative_eq!(
+                params.implied_volatility(forward, strike, t, SabrExpansion::Hagan),
+                hagan,
+                max_relative = 1e-13
+            );
+            assert_relative_eq!(
+                params.implied_volatility(forward, strike, t, SabrExpansion::Obloj),
+                obloj,
+                max_relative = 1e-13
+            );
+            assert_relative_eq!(params.normal_volatility(forward, strike, t), normal, max_relative = 1e-13);
+        }
+    }
+
+    #[test]
+    fn test_limits() {
+        let (forward, t) = (0.03, 2.0);
+
+        // At the money both lognormal expansions reduce to α / F^(1-β) times the time correction
+        let params = rates_smile();
+        let level = forward.powf(1.0 - params.beta);
+        let correction = 1.0
+            + t * (0.25 / 24.0 * params.alpha.powi(2) / level.powi(2)
+                + 0.25 * params.rho * params.beta * params.nu * params.alpha / level
+                + (2.0 - 3.0 * params.rho.powi(2)) / 24.0 * params.nu.powi(2));
+        for expansion in [SabrExpansion::Hagan, SabrExpansion::Obloj] {
+            let atm = params.implied_volatility(forward, forward, t, expansion);
+            assert_relative_eq!(atm, params.alpha / level * correction, max_relative = 1e-15);
+            // The series branch joins the closed form smoothly
+            let near = params.implied_volatility(forward, forward * (1.0 + 2e-3), t, expansion);
+            let nearer = params.implied_volatility(forward, forward * (1.0 + 1e-7), t, expansion);
+            assert!((nearer - atm).abs() < 1e-3 * (near - atm).abs());
+        }
+
+        // β = 1, ν = 0 is Black-Scholes with volatility α
+        let flat = SABRParams::new(0.2, 1.0, 0.4, 0.0);
+        for &strike in &[0.01, 0.03, 0.09] {
+            assert_relative_eq!(flat.implied_volatility(forward, strike, t, SabrExpansion::Obloj), 0.2, epsilon = 1e-15);
+            assert_relative_eq!(flat.implied_volatility(forward, strike, t, SabrExpansion::Hagan), 0.2, epsilon = 1e-15);
+        }
+
+        // β = 0 is the normal SABR model: α ζ/x(ζ) (1 + (2 - 3ρ²) ν² T / 24)
+        let normal = SABRParams::new(0.008, 0.0, 0.2, 0.3);
+        let strike = 0.045;
+        let zeta: f64 = 0.3 / 0.008 * (forward - strike);
+        let rho = 0.2;
+        let x = ((1.0 - 2.0 * rho * zeta + zeta * zeta).sqrt() + zeta - rho).ln() - (1.0 - rho).ln();
+        let expected = 0.008 * zeta / x * (1.0 + (2.0 - 3.0 * rho * rho) * 0.09 * t / 24.0);
+        assert_relative_eq!(normal.normal_volatility(forward, strike, t), expected, max_relative = 1e-13);
+    }
+
+    #[test]
+    fn test_slice_derivatives() {
+        let slice = SABRSlice::new(rates_smile(), 0.03);
+        let t = 2.0;
+        let h = 1e-4;
+        for &k in &[-0.5, 0.0, 5e-4, 0.3] {
+            let (slope, curvature) = slice.implied_variance_derivatives(k, t);
+            let (up, mid, down) = (
+                slice.implied_variance(k + h, t),
+                slice.implied_variance(k, t),
+                slice.implied_variance(k - h, t),
+            );
+            assert_relative_eq!(slope, (up - down) / (2.0 * h), epsilon = 1e-8);
+            assert_relative_eq!(curvature, (up - 2.0 * mid + down) / (h * h), epsilon = 1e-5);
+        }
+        assert!(slice.is_arbitrage_free(t));
+
+        // Hagan's expansion is known to imply a negative density at low strikes
+        // for long maturities
+        let mut wild = SABRSlice::new(SABRParams::new(0.05, 0.5, -0.6, 1.0), 0.03);
+        wild.expansion = SabrExpansion::Hagan;
+        assert!(!wild.is_arbitrage_free(30.0));
+    }
+
+    #[test]
+    fn test_calibration_recovers_parameters() {
+        let (forward, t) = (0.03, 2.0);
+        let truth = rates_smile();
+        for expansion in [SabrExpansion::Hagan, SabrExpansion::Obloj] {
+            let quotes: Vec<(f64, f64)> = [0.01, 0.015, 0.02, 0.025, 0.03, 0.035, 0.04, 0.05, 0.06]
+                .iter()
+                .map(|&strike| (strike, truth.implied_volatility(forward, strike, t, expansion)))
+                .collect();
+            let fit = calibrate(forward, t, &quotes, truth.beta, expansion).unwrap();
+            assert!(fit.converged);
+            assert!(fit.rmse < 1e-10, "rmse {}", fit.rmse);
+            assert_relative_eq!(fit.params.alpha, truth.alpha, max_relative = 1e-7);
+            assert_relative_eq!(fit.params.rho, truth.rho, epsilon = 1e-7);
+            assert_relative_eq!(fit.params.nu, truth.nu, max_relative = 1e-7);
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(rates_smile().validate().is_ok());
+        assert_eq!(
+            SABRParams::new(0.03, 1.2, 0.0, 0.4).validate(),
+            Err(PricingError::InvalidParameter { name: "beta", value: 1.2 })
+        );
+        assert_eq!(
+            SABRParams::new(0.03, 0.5, -1.0, 0.4).validate(),
+            Err(PricingError::InvalidParameter { name: "rho", value: -1.0 })
+        );
+        assert_eq!(
+            calibrate(0.03, 1.0, &[(0.02, 0.2), (0.03, 0.18)], 0.5, SabrExpansion::Obloj).unwrap_err(),
+            PricingError::InsufficientQuotes { required: 3, provided: 2 }
+        );
+        assert_eq!(
+            calibrate(0.03, 1.0, &[(0.02, 0.2), (0.03, 0.18), (0.04, -0.1)], 0.5, SabrExpansion::Obloj).unwrap_err(),
+            PricingError::NonPositiveVolatility(-0.1)
+        );
+    }
+}
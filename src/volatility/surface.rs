@@ -1,21 +1,59 @@
 //! Volatility surface construction and management
 
+use crate::ad::Scalar;
 use crate::volatility::svi::SVIParams;
 use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
 
-/// Volatility surface storing SVI parameters for multiple maturities
+/// Smile model for a single maturity, in total implied variance w(k) = σ²(k) T
+///
+/// Log-moneyness is k = ln(K/F). Slices are handed their maturity so models
+/// quoted in volatility rather than variance, such as SABR, can convert.
+pub trait SmileSlice: fmt::Debug + Send + Sync {
+    /// Total implied variance at a log-moneyness
+    fn implied_variance(&self, log_moneyness: f64, time_to_maturity: f64) -> f64;
+
+    /// First and second derivatives of the total variance with respect to log-moneyness
+    fn implied_variance_derivatives(&self, log_moneyness: f64, time_to_maturity: f64) -> (f64, f64);
+
+    /// Check the slice is free of butterfly arbitrage
+    fn is_arbitrage_free(&self, time_to_maturity: f64) -> bool;
+}
+
+impl SmileSlice for SVIParams {
+    fn implied_variance(&self, log_moneyness: f64, _time_to_maturity: f64) -> f64 {
+        SVIParams::implied_variance(self, log_moneyness)
+    }
+
+    fn implied_variance_derivatives(&self, log_moneyness: f64, _time_to_maturity: f64) -> (f64, f64) {
+        SVIParams::implied_variance_derivatives(self, log_moneyness)
+    }
+
+    fn is_arbitrage_free(&self, _time_to_maturity: f64) -> bool {
+        SVIParams::is_arbitrage_free(self)
+    }
+}
+
+/// Volatility surface storing a smile slice, SVI or SABR, for each maturity
 #[derive(Debug, Clone)]
 pub struct VolatilitySurface {
-    /// Map from time to maturity to SVI parameters
-    slices: BTreeMap<OrderedFloat, SVIParams>,
+    /// Map from time to maturity to smile slice
+    slices: BTreeMap<OrderedFloat, Arc<dyn SmileSlice>>,
 }
 
 /// Wrapper for f64 to use as BTreeMap key
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 struct OrderedFloat(f64);
 
 impl Eq for OrderedFloat {}
 
+impl PartialOrd for OrderedFloat {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Ord for OrderedFloat {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         self.0.partial_cmp(&other.0).unwrap_or(std::cmp::Ordering::Equal)
@@ -30,72 +68,133 @@ impl VolatilitySurface {
         }
     }
 
-    /// Add a maturity slice with SVI parameters
-    pub fn add_slice(&mut self, time_to_maturity: f64, params: SVIParams) {
-        self.slices.insert(OrderedFloat(time_to_maturity), params);
+    /// Add a maturity slice, e.g. `SVIParams` or a `SABRSlice`
+    pub fn add_slice(&mut self, time_to_maturity: f64, slice: impl SmileSlice + 'static) {
+        self.slices.insert(OrderedFloat(time_to_maturity), Arc::new(slice));
+    }
+
+    /// Maturities of the quoted slices, in increasing order
+    pub fn maturities(&self) -> Vec<f64> {
+        self.slices.keys().map(|t| t.0).collect()
     }
 
     /// Get implied volatility for a given strike, spot, and time to maturity
     pub fn get_implied_volatility(&self, strike: f64, spot: f64, time_to_maturity: f64) -> Option<f64> {
-        // Calculate log-moneyness
         let log_moneyness = (strike / spot).ln();
+        self.total_variance(log_moneyness, time_to_maturity, |_, variance| variance)
+            .map(|variance| (variance / time_to_maturity).sqrt())
+    }
 
-        // Find the appropriate maturity slice(s)
-        let key = OrderedFloat(time_to_maturity);
-
-        // Exact match
-        if let Some(params) = self.slices.get(&key) {
-            return Some(params.implied_volatility(log_moneyness, time_to_maturity));
+    /// Get implied volatility with each slice's total variance shifted by the
+    /// matching entry of `variance_shifts` (slices in maturity order)
+    ///
+    /// Evaluating with shifts registered on an `ad::tape::Tape` gives bucketed
+    /// sensitivities to every maturity node from one backward sweep.
+    pub fn get_implied_volatility_shifted<T: Scalar>(
+        &self,
+        strike: f64,
+        spot: f64,
+        time_to_maturity: f64,
+        variance_shifts: &[T],
+    ) -> Option<T> {
+        if variance_shifts.len() != self.slices.len() {
+            return None;
         }
-
-        // Interpolate between two nearest maturities
-        self.interpolate_volatility(log_moneyness, time_to_maturity)
+        let log_moneyness = (strike / spot).ln();
+        self.total_variance(log_moneyness, time_to_maturity, |i, variance| variance_shifts[i] + variance)
+            .map(|variance| (variance / time_to_maturity).sqrt())
     }
 
-    /// Interpolate volatility between maturities
-    fn interpolate_volatility(&self, log_moneyness: f64, time_to_maturity: f64) -> Option<f64> {
+    /// Total implied variance at a log-moneyness, interpolated linearly between
+    /// the surrounding maturities
+    ///
+    /// `node` lifts the i-th slice's variance into the number type being evaluated.
+    fn total_variance<T: Scalar>(
+        &self,
+        log_moneyness: f64,
+        time_to_maturity: f64,
+        node: impl Fn(usize, f64) -> T,
+    ) -> Option<T> {
         // Find surrounding maturities
         let mut before = None;
         let mut after = None;
 
-        for (&t, _) in self.slices.iter() {
+        for (i, (&t, params)) in self.slices.iter().enumerate() {
             if t.0 <= time_to_maturity {
-                before = Some(t);
+                before = Some((i, t, params));
             }
             if t.0 >= time_to_maturity && after.is_none() {
-                after = Some(t);
+                after = Some((i, t, params));
             }
         }
 
         match (before, after) {
-            (Some(t1), Some(t2)) if t1 != t2 => {
-                // Linear interpolation in total variance
-                let params1 = self.slices.get(&t1)?;
-                let params2 = self.slices.get(&t2)?;
-
-                let var1 = params1.implied_variance(log_moneyness);
-                let var2 = params2.implied_variance(log_moneyness);
+            (Some((i1, t1, params1)), Some((i2, t2, params2))) if t1 != t2 => {
+                let var1 = node(i1, params1.implied_variance(log_moneyness, t1.0));
+                let var2 = node(i2, params2.implied_variance(log_moneyness, t2.0));
 
                 // Interpolate total variance linearly
                 let weight = (time_to_maturity - t1.0) / (t2.0 - t1.0);
-                let interpolated_variance = var1 + weight * (var2 - var1);
-
-                Some((interpolated_variance / time_to_maturity).sqrt())
+                Some(var1 + (var2 - var1) * weight)
             }
-            (Some(t), _) | (_, Some(t)) => {
-                // Use the single available maturity
-                let params = self.slices.get(&t)?;
-                Some(params.implied_volatility(log_moneyness, time_to_maturity))
+            (Some((i, t, params)), _) | (_, Some((i, t, params))) => {
+                // Exact match, or the single available maturity
+                Some(node(i, params.implied_variance(log_moneyness, t.0)))
             }
             _ => None,
         }
     }
 
+    /// Dupire local volatility at a log-moneyness ln(K/F) and maturity
+    ///
+    /// Uses Gatheral's form in total implied variance w(y, T):
+    /// σ² = ∂w/∂T / (1 - y w'/w + ¼(-¼ - 1/w + y²/w²) w'² + ½ w'').
+    /// Between slices w is linear in T as in `get_implied_volatility`, but outside
+    /// the quoted maturities implied volatility rather than total variance is held
+    /// flat, since flat total variance would mean zero local volatility.
+    ///
+    /// Returns `None` for an empty surface, or where calendar or butterfly
+    /// arbitrage leaves the local variance undefined.
+    pub fn local_volatility(&self, log_moneyness: f64, time_to_maturity: f64) -> Option<f64> {
+        let slices: Vec<(f64, &dyn SmileSlice)> = self.slices.iter().map(|(t, params)| (t.0, params.as_ref())).collect();
+        let (first, last) = (slices.first()?, slices.last()?);
+
+        let node = |t: f64, params: &dyn SmileSlice| {
+            let (slope, curvature) = params.implied_variance_derivatives(log_moneyness, t);
+            [params.implied_variance(log_moneyness, t), slope, curvature]
+        };
+        let scaled = |(t, params): (f64, &dyn SmileSlice)| {
+            let [w, slope, curvature] = node(t, params);
+            let scale = time_to_maturity / t;
+            ([w * scale, slope * scale, curvature * scale], w / t)
+        };
+        let ([w, slope, curvature], dw_dt) = if time_to_maturity <= first.0 {
+            scaled(*first)
+        } else if time_to_maturity >= last.0 {
+            scaled(*last)
+        } else {
+            let i = slices.iter().rposition(|&(t, _)| t <= time_to_maturity)?;
+            let ((t1, params1), (t2, params2)) = (slices[i], slices[i + 1]);
+            let (lower, upper) = (node(t1, params1), node(t2, params2));
+            let weight = (time_to_maturity - t1) / (t2 - t1);
+            let interpolate = |j: usize| lower[j] + (upper[j] - lower[j]) * weight;
+            ([interpolate(0), interpolate(1), interpolate(2)], (upper[0] - lower[0]) / (t2 - t1))
+        };
+
+        let y = log_moneyness;
+        let denominator = 1.0 - y * slope / w + 0.25 * (-0.25 - 1.0 / w + y * y / (w * w)) * slope * slope
+            + 0.5 * curvature;
+        if !(w > 0.0 && dw_dt >= 0.0 && denominator > 0.0) {
+            return None;
+        }
+        Some((dw_dt / denominator).sqrt())
+    }
+
     /// Check if the entire surface is arbitrage-free
     pub fn is_arbitrage_free(&self) -> bool {
         // Check each slice
-        for params in self.slices.values() {
-            if !params.is_arbitrage_free() {
+        for (t, params) in self.slices.iter() {
+            if !params.is_arbitrage_free(t.0) {
                 return false;
             }
         }
@@ -107,8 +206,8 @@ impl VolatilitySurface {
                 // For a given strike, total variance should increase with time
                 // This is a simplified check at k=0 (ATM)
                 let prev_params = self.slices.get(&prev_time).unwrap();
-                let prev_var = prev_params.implied_variance(0.0);
-                let curr_var = params.implied_variance(0.0);
+                let prev_var = prev_params.implied_variance(0.0, prev_time.0);
+                let curr_var = params.implied_variance(0.0, t.0);
 
                 if curr_var < prev_var {
                     return false;
@@ -135,6 +234,7 @@ impl Default for VolatilitySurface {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use approx::assert_relative_eq;
 
     #[test]
     fn test_surface_creation() {
@@ -174,4 +274,90 @@ mod tests {
         let vol = surface.get_implied_volatility(100.0, 100.0, 0.5);
         assert!(vol.is_some());
     }
+
+    #[test]
+    fn test_mixed_smile_models() {
+        use crate::volatility::sabr::{SABRParams, SABRSlice, SabrExpansion};
+
+        let forward = 0.03;
+        let sabr = SABRParams::new(0.035, 0.5, -0.3, 0.45);
+        let svi = SVIParams::new(0.08, 0.1, -0.3, 0.0, 0.2);
+        let mut surface = VolatilitySurface::new();
+        surface.add_slice(1.0, SABRSlice::new(sabr, forward));
+        surface.add_slice(2.0, svi);
+        assert_eq!(surface.maturities(), vec![1.0, 2.0]);
+
+        for &strike in &[0.02, 0.03, 0.045] {
+            let vol = surface.get_implied_volatility(strike, forward, 1.0).unwrap();
+            assert_relative_eq!(vol, sabr.implied_volatility(forward, strike, 1.0, SabrExpansion::Obloj), epsilon = 1e-14);
+
+            // Total variance interpolates linearly across the two models
+            let k = (strike / forward).ln();
+            let (w1, w2) = (vol * vol, svi.implied_variance(k));
+            let vol = surface.get_implied_volatility(strike, forward, 1.5).unwrap();
+            assert_relative_eq!(vol * vol * 1.5, 0.5 * (w1 + w2), epsilon = 1e-14);
+        }
+
+        assert!(surface.local_volatility(-0.2, 1.25).unwrap() > 0.0);
+        assert!(surface.is_arbitrage_free());
+    }
+
+    #[test]
+    fn test_local_volatility() {
+        // Flat 20% implied vol: every slice has w = 0.04 T, so local vol is flat too
+        let mut surface = VolatilitySurface::new();
+        surface.add_slice(0.5, SVIParams::new(0.02, 0.0, 0.0, 0.0, 0.1));
+        surface.add_slice(1.0, SVIParams::new(0.04, 0.0, 0.0, 0.0, 0.1));
+        for &(y, t) in &[(0.0, 0.1), (-0.3, 0.75), (0.2, 2.0)] {
+            assert_relative_eq!(surface.local_volatility(y, t).unwrap(), 0.2, epsilon = 1e-15);
+        }
+
+        // ATM with a symmetric smile, local variance is the forward variance over ½ w'' + 1
+        let mut surface = VolatilitySurface::new();
+        let params = SVIParams::new(0.03, 0.1, 0.0, 0.0, 0.2);
+        surface.add_slice(1.0, params);
+        let (_, curvature) = params.implied_variance_derivatives(0.0);
+        let expected = (params.implied_variance(0.0) / (1.0 + 0.5 * curvature)).sqrt();
+        assert_relative_eq!(surface.local_volatility(0.0, 1.0).unwrap(), expected, epsilon = 1e-15);
+
+        assert!(VolatilitySurface::new().local_volatility(0.0, 1.0).is_none());
+    }
+
+    #[test]
+    fn test_bucketed_vega_on_tape() {
+        use crate::ad::tape::{Tape, Var};
+        use crate::pricing::black_scholes::call_price;
+
+        let mut surface = VolatilitySurface::new();
+        surface.add_slice(0.25, SVIParams::new(0.01, 0.05, -0.4, 0.0, 0.2));
+        surface.add_slice(0.5, SVIParams::new(0.02, 0.06, -0.35, 0.0, 0.22));
+        surface.add_slice(1.0, SVIParams::new(0.04, 0.08, -0.3, 0.0, 0.25));
+
+        let (spot, strike, t, r) = (100.0, 105.0, 0.75, 0.03);
+        let price_with = |shifts: &[f64]| {
+            let vol = surface.get_implied_volatility_shifted(strike, spot, t, shifts).unwrap();
+            call_price(spot, strike, t, vol, r, 0.0)
+        };
+
+        let tape = Tape::new();
+        let shifts: Vec<Var> = (0..surface.num_slices()).map(|_| tape.var(0.0)).collect();
+        let vol = surface.get_implied_volatility_shifted(strike, spot, t, &shifts).unwrap();
+        let price = call_price(Var::constant(spot), strike, Var::constant(t), vol, Var::constant(r), Var::constant(0.0));
+        let grads = price.gradients();
+
+        assert_relative_eq!(price.value(), price_with(&[0.0; 3]), epsilon = 1e-12);
+
+        let h = 1e-6;
+        for (i, shift) in shifts.iter().enumerate() {
+            let mut up = [0.0; 3];
+            let mut down = [0.0; 3];
+            up[i] = h;
+            down[i] = -h;
+            let fd = (price_with(&up) - price_with(&down)) / (2.0 * h);
+            assert_relative_eq!(grads.wrt(shift), fd, epsilon = 1e-6, max_relative = 1e-5);
+        }
+
+        // The 3-month node lies outside the interpolation bracket
+        assert_relative_eq!(grads.wrt(&shifts[0]), 0.0);
+    }
 }
@@ -40,6 +40,16 @@ impl SVIParams {
         (variance / time_to_maturity).sqrt()
     }
 
+    /// First and second derivatives of the implied variance with respect to log-moneyness
+    #[inline]
+    pub fn implied_variance_derivatives(&self, log_moneyness: f64) -> (f64, f64) {
+        let k_minus_m = log_moneyness - self.m;
+        let sqrt_term = (k_minus_m * k_minus_m + self.sigma * self.sigma).sqrt();
+        let slope = self.b * (self.rho + k_minus_m / sqrt_term);
+        let curvature = self.b * self.sigma * self.sigma / (sqrt_term * sqrt_term * sqrt_term);
+        (slope, curvature)
+    }
+
     /// Check if parameters satisfy no-arbitrage constraints
     pub fn is_arbitrage_free(&self) -> bool {
         // Basic constraints for SVI
@@ -71,16 +81,7 @@ impl SVIParams {
     /// Check butterfly arbitrage condition
     /// The density must be non-negative, which requires d²C/dK² >= 0
     pub fn check_butterfly_arbitrage(&self, log_moneyness: f64) -> bool {
-        let k = log_moneyness - self.m;
-        let sigma_sq = self.sigma * self.sigma;
-        let k_sq = k * k;
-        let sqrt_term = (k_sq + sigma_sq).sqrt();
-
-        // First derivative of w with respect to k
-        let dw_dk = self.b * (self.rho + k / sqrt_term);
-
-        // Second derivative of w with respect to k
-        let d2w_dk2 = self.b * sigma_sq / (sqrt_term * sqrt_term * sqrt_term);
+        let (dw_dk, d2w_dk2) = self.implied_variance_derivatives(log_moneyness);
 
         // For no butterfly arbitrage, we need specific conditions on derivatives
         // This is a simplified check - full implementation would be more complex
@@ -11,6 +11,7 @@ pub struct WasmGreeks {
     pub vega: f64,
     pub theta: f64,
     pub rho: f64,
+    pub phi: f64,
 }
 
 #[wasm_bindgen]
@@ -47,6 +48,7 @@ pub fn calculate_greeks_wasm(
         vega: result.vega,
         theta: result.theta,
         rho: result.rho,
+        phi: result.phi,
     };
 
     serde_wasm_bindgen::to_value(&wasm_result).unwrap()